@@ -1,14 +1,20 @@
 use bevy::{
-  asset::{io::Reader, AssetLoader, LoadContext, LoadedFolder},
+  asset::{
+    io::Reader, AssetLoadError, AssetLoadFailedEvent, AssetLoader, LoadContext, LoadedFolder,
+  },
   ecs::system::{SystemParam, SystemState},
   prelude::*,
   reflect::GetTypeRegistration,
   utils::hashbrown::HashMap,
 };
-use serde::Deserialize;
-use std::marker::PhantomData;
+use serde::{Deserialize, Serialize};
+use std::{
+  collections::BTreeMap,
+  marker::PhantomData,
+  path::{Path, PathBuf},
+};
 
-use crate::util;
+use crate::{startup::StartupProgress, util};
 
 pub struct PrefabPlugin<T> {
   _pd: PhantomData<T>,
@@ -27,12 +33,22 @@ where
   fn build(&self, app: &mut App) {
     app
       .init_asset::<T::Descriptor>()
+      .init_resource::<PrefabLoadErrors>()
       .add_event::<PrefabLoadedEvent<T>>()
       .register_asset_loader(PrefabLoader::<T>::default())
       // on startup create a prefab loader
       .add_systems(Startup, Self::on_start)
       // then read all events that come in for the loaded prefab
-      .add_systems(Update, (Self::on_load, Self::on_prefab_loaded));
+      .add_systems(
+        Update,
+        (
+          Self::on_load,
+          Self::on_folder_failed,
+          Self::on_file_loaded,
+          Self::on_file_failed,
+          Self::on_prefab_loaded,
+        ),
+      );
   }
 }
 
@@ -40,8 +56,16 @@ impl<T> PrefabPlugin<T>
 where
   T: Prefab,
 {
-  fn on_start(assets: ResMut<AssetServer>, mut commands: Commands) {
+  fn on_start(
+    assets: ResMut<AssetServer>,
+    mut commands: Commands,
+    mut startup_progress: ResMut<StartupProgress>,
+  ) {
     let handle = assets.load_folder(T::DIR);
+    startup_progress.track(
+      format!("{} prefabs ({})", util::short_name_of::<T>(), T::DIR),
+      handle.clone(),
+    );
     commands.insert_resource(PrefabFolder::<T>::new(handle));
     info!("Started folder load for {}", util::short_name_of::<T>());
   }
@@ -64,6 +88,92 @@ where
     }
   }
 
+  /// A single malformed descriptor fails the whole folder, so the files are then loaded one by
+  /// one to still register the valid ones
+  fn on_folder_failed(
+    mut event_reader: EventReader<AssetLoadFailedEvent<LoadedFolder>>,
+    mut folder: ResMut<PrefabFolder<T>>,
+    mut errors: ResMut<PrefabLoadErrors>,
+    assets: Res<AssetServer>,
+  ) {
+    for event in event_reader.read() {
+      if event.id != folder.handle().id() {
+        continue;
+      }
+
+      if let AssetLoadError::AssetLoaderError(err) = &event.error {
+        errors.record(err.path().to_string(), err.to_string());
+      }
+
+      let dir = util::asset_root();
+      let mut files = Vec::new();
+      Self::descriptor_files(&dir.join(T::DIR), &mut files);
+
+      warn!(
+        "Loading {} prefabs file by file, {} failed to load",
+        util::short_name_of::<T>(),
+        T::DIR
+      );
+
+      folder.files = files
+        .iter()
+        .filter_map(|file| file.strip_prefix(&dir).ok())
+        .map(|file| assets.load(file.to_path_buf()))
+        .collect();
+    }
+  }
+
+  fn descriptor_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+      return;
+    };
+
+    for path in entries.flatten().map(|entry| entry.path()) {
+      if path.is_dir() {
+        Self::descriptor_files(&path, files);
+      } else if path.to_str().is_some_and(|path| {
+        T::EXTENSIONS
+          .iter()
+          .any(|extension| path.ends_with(&format!(".{extension}")))
+      }) {
+        files.push(path);
+      }
+    }
+  }
+
+  fn on_file_loaded(
+    mut event_reader: EventReader<AssetEvent<T::Descriptor>>,
+    folder: Res<PrefabFolder<T>>,
+    mut errors: ResMut<PrefabLoadErrors>,
+    assets: Res<AssetServer>,
+    mut event_writer: EventWriter<PrefabLoadedEvent<T>>,
+  ) {
+    for event in event_reader.read() {
+      let AssetEvent::LoadedWithDependencies { id } = event else {
+        continue;
+      };
+
+      // fixed files are reloaded when hot reloading is on
+      if let Some(path) = assets.get_path(*id) {
+        errors.clear(&path.to_string());
+      }
+
+      // files loaded with the folder are announced once the folder is loaded
+      if folder.files.iter().any(|handle| handle.id() == *id) {
+        event_writer.send(PrefabLoadedEvent::<T>::new(*id));
+      }
+    }
+  }
+
+  fn on_file_failed(
+    mut event_reader: EventReader<AssetLoadFailedEvent<T::Descriptor>>,
+    mut errors: ResMut<PrefabLoadErrors>,
+  ) {
+    for event in event_reader.read() {
+      errors.record(event.path.to_string(), event.error.to_string());
+    }
+  }
+
   fn on_prefab_loaded(
     mut event_reader: EventReader<PrefabLoadedEvent<T>>,
     descriptors: Res<Assets<T::Descriptor>>,
@@ -108,6 +218,7 @@ impl PrefabRegistrar {
         let params = state.get_mut(world);
         let bundle = T::spawn(entity_id, params);
         world.entity_mut(entity_id).insert(bundle);
+        entity_id
       }
     });
   }
@@ -115,7 +226,7 @@ impl PrefabRegistrar {
   /// Calls R which produces a closure S that is later invoked to return the spawn function
   fn register_internal<R, S>(&mut self, name: impl Into<String>, f: R)
   where
-    S: FnMut(&mut World) + Send + Sync + 'static,
+    S: FnMut(&mut World) -> Entity + Send + Sync + 'static,
     R: Fn(&mut World) -> S + Send + Sync + 'static,
   {
     self
@@ -124,7 +235,7 @@ impl PrefabRegistrar {
   }
 }
 
-type SpawnFn = dyn FnMut(&mut World) + Send + Sync;
+type SpawnFn = dyn FnMut(&mut World) -> Entity + Send + Sync;
 type PrefabSpawnMap = HashMap<String, Box<SpawnFn>>;
 
 #[derive(Resource, Deref, DerefMut)]
@@ -141,22 +252,25 @@ impl Prefabs {
     Self(prefabs)
   }
 
+  /// Adds or replaces the prefabs of another registrar, used when registrations are rerun
+  pub fn extend_with(&mut self, world: &mut World, registrar: PrefabRegistrar) {
+    for (k, v) in registrar.registrations {
+      self.insert(k, (v)(world));
+    }
+  }
+
   fn register<T>(&mut self, prefab: T)
   where
     T: Prefab,
   {
     self.insert(
       prefab.name().to_string(),
-      Box::new(move |world| {
-        world.spawn(prefab.clone());
-      }),
+      Box::new(move |world| world.spawn(prefab.clone()).id()),
     );
   }
 
-  pub fn spawn(&mut self, id: impl AsRef<str>, world: &mut World) {
-    if let Some(spawn_fn) = self.get_mut(id.as_ref()) {
-      (spawn_fn)(world);
-    }
+  pub fn spawn(&mut self, id: impl AsRef<str>, world: &mut World) -> Option<Entity> {
+    self.get_mut(id.as_ref()).map(|spawn_fn| (spawn_fn)(world))
   }
 }
 
@@ -181,6 +295,12 @@ pub trait Prefab: GetTypeRegistration + Bundle + Clone {
   fn transform(desc: &Self::Descriptor, assets: &AssetServer) -> Self;
 }
 
+/// A prefab that can be written back to its descriptor file from the prefab editor
+pub trait EditablePrefab: Prefab<Descriptor: Serialize> + Component {
+  /// Inverse of [`Prefab::transform`]
+  fn describe(&self, assets: &AssetServer) -> Self::Descriptor;
+}
+
 pub struct PrefabLoader<T>
 where
   T: Prefab,
@@ -207,7 +327,7 @@ where
 
   type Settings = ();
 
-  type Error = std::io::Error;
+  type Error = PrefabLoadError;
 
   async fn load(
     &self,
@@ -217,7 +337,7 @@ where
   ) -> Result<Self::Asset, Self::Error> {
     let mut bytes = Vec::new();
     reader.read_to_end(&mut bytes).await?;
-    let ron: <T as Prefab>::Descriptor = ron::de::from_bytes(&bytes).unwrap();
+    let ron: <T as Prefab>::Descriptor = ron::de::from_bytes(&bytes)?;
     Ok(ron)
   }
 
@@ -226,12 +346,51 @@ where
   }
 }
 
+#[derive(thiserror::Error, Debug)]
+pub enum PrefabLoadError {
+  #[error(transparent)]
+  Io(#[from] std::io::Error),
+  #[error(transparent)]
+  Ron(#[from] ron::error::SpannedError),
+}
+
+/// Prefab descriptor files that failed to load, by asset path
+#[derive(Resource, Default)]
+pub struct PrefabLoadErrors {
+  errors: BTreeMap<String, String>,
+}
+
+impl PrefabLoadErrors {
+  fn record(&mut self, path: String, error: String) {
+    error!("Failed to load prefab {path}: {error}");
+    self.errors.insert(path, error);
+  }
+
+  fn clear(&mut self, path: &str) {
+    self.errors.remove(path);
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.errors.is_empty()
+  }
+
+  /// Paths and their errors, sorted by path
+  pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+    self
+      .errors
+      .iter()
+      .map(|(path, error)| (path.as_str(), error.as_str()))
+  }
+}
+
 #[derive(Resource)]
 pub struct PrefabFolder<T>
 where
   T: Prefab,
 {
   handle: Handle<LoadedFolder>,
+  /// Descriptors loaded one by one after the folder failed to load
+  files: Vec<Handle<T::Descriptor>>,
   _phantom_data: PhantomData<T>,
 }
 
@@ -242,6 +401,7 @@ where
   pub fn new(handle: Handle<LoadedFolder>) -> Self {
     Self {
       handle,
+      files: Vec::new(),
       _phantom_data: default(),
     }
   }
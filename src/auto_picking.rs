@@ -0,0 +1,97 @@
+use crate::{cache::Saveable, settings::EditorSettings, util::matches_pattern};
+use bevy::{
+  ecs::component::{ComponentId, Components},
+  prelude::*,
+  render::view::RenderLayers,
+};
+use serde::{Deserialize, Serialize};
+
+/// Keeps the editor from making the entity pickable in the editor view
+///
+/// Saved with the scene so the opt out is kept between sessions
+#[derive(Component, Reflect, Default, Clone, Copy)]
+#[reflect(Component)]
+pub struct NoAutoPicking;
+
+/// Which meshes and sprites are left out when the editor makes everything pickable
+///
+/// Changes apply right away, every entity is reconsidered
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone, Default)]
+#[reflect(Resource)]
+pub struct AutoPickingSettings {
+  /// Short or full type paths of components, i.e. `Skybox`
+  pub excluded_components: Vec<String>,
+  /// Entities on any of these render layers are left out
+  pub excluded_layers: Vec<usize>,
+  /// Name patterns where `*` matches anything, i.e. `UI *`
+  pub excluded_names: Vec<String>,
+}
+
+impl Saveable for AutoPickingSettings {
+  const KEY: &str = "auto_picking";
+}
+
+impl EditorSettings for AutoPickingSettings {
+  const NAME: &str = "Auto Picking";
+}
+
+impl AutoPickingSettings {
+  /// Resolves the excluded components once so entities can be checked against them cheaply
+  pub(crate) fn exclusions(
+    &self,
+    registry: &AppTypeRegistry,
+    components: &Components,
+  ) -> Exclusions {
+    let registry = registry.read();
+
+    let components = self
+      .excluded_components
+      .iter()
+      .filter_map(|path| {
+        registry
+          .get_with_short_type_path(path)
+          .or_else(|| registry.get_with_type_path(path))
+      })
+      .filter_map(|registration| components.get_id(registration.type_id()))
+      .collect();
+
+    Exclusions {
+      components,
+      layers: self.excluded_layers.clone(),
+      names: self.excluded_names.clone(),
+    }
+  }
+}
+
+pub(crate) struct Exclusions {
+  components: Vec<ComponentId>,
+  layers: Vec<usize>,
+  names: Vec<String>,
+}
+
+impl Exclusions {
+  pub(crate) fn excludes(
+    &self,
+    entity: EntityRef,
+    name: Option<&Name>,
+    layers: Option<&RenderLayers>,
+  ) -> bool {
+    if self.components.iter().any(|id| entity.contains_id(*id)) {
+      return true;
+    }
+
+    if !self.layers.is_empty() {
+      let layers = layers.cloned().unwrap_or_default();
+      if layers.iter().any(|layer| self.layers.contains(&layer)) {
+        return true;
+      }
+    }
+
+    name.is_some_and(|name| {
+      self
+        .names
+        .iter()
+        .any(|pattern| matches_pattern(pattern, name.as_str()))
+    })
+  }
+}
@@ -1,44 +1,169 @@
-use crate::util::sorted_keys;
+use crate::util::{self, sorted_keys};
 use bevy::{prelude::*, utils::HashMap};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-fn cache_path() -> PathBuf {
-  const FILE: &str = concat!(env!("CARGO_PKG_NAME"), ".cache.json");
+/// How many previous versions of the cache are kept around
+const MAX_BACKUPS: usize = 5;
+const VERSION_KEY: &str = "cache_version";
+
+type Migration = fn(&mut HashMap<String, serde_json::Value>);
+
+/// `MIGRATIONS[n]` upgrades a cache from version `n` to `n + 1`, caches written before versioning
+/// was added are version 0
+const MIGRATIONS: &[Migration] = &[
+  // versioning was added without changing the layout of the cache
+  |_| {},
+];
+
+const VERSION: u64 = MIGRATIONS.len() as u64;
+
+pub(crate) fn cache_dir() -> PathBuf {
   std::env::current_exe()
     .unwrap()
     .parent()
     .unwrap()
     .to_path_buf()
-    .join(FILE)
+}
+
+fn cache_path() -> PathBuf {
+  const FILE: &str = concat!(env!("CARGO_PKG_NAME"), ".cache.json");
+  cache_dir().join(FILE)
+}
+
+fn backup_dir() -> PathBuf {
+  const DIR: &str = concat!(env!("CARGO_PKG_NAME"), ".cache.backups");
+  cache_dir().join(DIR)
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum CacheLoadError {
+  #[error(transparent)]
+  Io(#[from] std::io::Error),
+  #[error(transparent)]
+  Corrupted(#[from] serde_json::Error),
 }
 
 #[derive(Default, Resource, Serialize, Deserialize, Debug)]
 pub struct Cache(#[serde(serialize_with = "sorted_keys")] HashMap<String, serde_json::Value>);
 
 impl Cache {
-  pub fn load_or_default() -> Self {
+  /// Loads the cache, falling back to the defaults if there is none
+  ///
+  /// A corrupted cache is moved out of the way so it is not overwritten on exit, and the returned
+  /// [`CacheRecovery`] offers to restore one of the backups instead
+  pub fn load_or_recover() -> (Self, Option<CacheRecovery>) {
     let cache_path = cache_path();
     println!("Loading cache from: {}", cache_path.display());
 
-    match std::fs::read_to_string(cache_path).map(|data| serde_json::from_str(&data)) {
-      Ok(Ok(cache)) => cache,
-      Ok(Err(err)) => {
-        eprintln!("Error deserializing during initial load: {err}");
-        Self::default()
-      }
-      Err(err) => {
+    match Self::load(&cache_path) {
+      Ok(cache) => (cache, None),
+      Err(CacheLoadError::Io(err)) => {
         eprintln!("Error loading cache from disk: {err}");
-        Self::default()
+        (Self::default(), None)
+      }
+      Err(CacheLoadError::Corrupted(err)) => {
+        eprintln!("Error deserializing during initial load: {err}");
+
+        let corrupted = cache_path.with_extension("json.corrupt");
+        if let Err(err) = std::fs::rename(&cache_path, &corrupted) {
+          eprintln!("Failed to move the corrupted cache aside: {err}");
+        }
+
+        let recovery = CacheRecovery {
+          error: err.to_string(),
+          corrupted,
+          backups: Self::backups(),
+          restored: None,
+          restore_error: None,
+          open: true,
+        };
+
+        (Self::default(), Some(recovery))
       }
     }
   }
 
+  fn load(path: &Path) -> Result<Self, CacheLoadError> {
+    let data = std::fs::read_to_string(path)?;
+    let mut cache: Self = serde_json::from_str(&data)?;
+    cache.migrate();
+    Ok(cache)
+  }
+
+  fn migrate(&mut self) {
+    let version = self
+      .0
+      .remove(VERSION_KEY)
+      .and_then(|version| version.as_u64())
+      .unwrap_or_default();
+
+    if version > VERSION {
+      warn!("Cache was written by a newer version of the editor ({version} > {VERSION})");
+      return;
+    }
+
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+      info!("Migrating cache from version {from} to {}", from + 1);
+      migration(&mut self.0);
+    }
+  }
+
+  /// Backups of the cache, newest first
+  fn backups() -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(backup_dir()) else {
+      return Vec::new();
+    };
+
+    let mut backups = entries
+      .filter_map(Result::ok)
+      .map(|entry| entry.path())
+      .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+      .collect::<Vec<_>>();
+
+    // file names start with the time they were taken at
+    backups.sort();
+    backups.reverse();
+    backups
+  }
+
+  /// Copies the cache currently on disk into the backup folder, dropping the oldest backups
+  fn backup() -> std::io::Result<()> {
+    let cache_path = cache_path();
+    if !cache_path.exists() {
+      return Ok(());
+    }
+
+    let backup_dir = backup_dir();
+    std::fs::create_dir_all(&backup_dir)?;
+
+    let timestamp = util::unix_time();
+    std::fs::copy(
+      &cache_path,
+      backup_dir.join(format!("{timestamp:020}.json")),
+    )?;
+
+    for old in Self::backups().into_iter().skip(MAX_BACKUPS) {
+      std::fs::remove_file(old)?;
+    }
+
+    Ok(())
+  }
+
   pub fn save(&self) {
     let cache_path = cache_path();
     info!("Saving cache to: {}", cache_path.display());
 
-    match serde_json::to_string_pretty(self).map(|data| std::fs::write(cache_path, data)) {
+    if let Err(e) = Self::backup() {
+      error!("Failed to back up the previous cache: {e}");
+    }
+
+    let mut versioned = self.0.clone();
+    versioned.insert(VERSION_KEY.to_string(), VERSION.into());
+
+    match serde_json::to_string_pretty(&Self(versioned))
+      .map(|data| std::fs::write(cache_path, data))
+    {
       Ok(Ok(_)) => {
         info!("Saved cache");
       }
@@ -65,6 +190,27 @@ impl Cache {
     }
   }
 
+  /// Removes the stored value, returning it as it was saved
+  pub fn remove<S>(&mut self) -> Option<serde_json::Value>
+  where
+    S: Saveable,
+  {
+    self.0.remove(S::KEY)
+  }
+
+  /// The stored value as it was saved, without deserializing it
+  pub(crate) fn get_raw<S>(&self) -> Option<serde_json::Value>
+  where
+    S: Saveable,
+  {
+    self.0.get(S::KEY).cloned()
+  }
+
+  /// Puts back a value taken with [`Cache::remove`] or [`Cache::get_raw`]
+  pub(crate) fn insert_raw(&mut self, key: &str, value: serde_json::Value) {
+    self.0.insert(key.to_string(), value);
+  }
+
   pub fn get<S>(&self) -> Option<S>
   where
     S: Saveable,
@@ -86,3 +232,69 @@ impl Cache {
 pub trait Saveable: Serialize + for<'de> Deserialize<'de> + Sized + 'static {
   const KEY: &str;
 }
+
+/// Present when the cache could not be read on startup
+#[derive(Resource)]
+pub struct CacheRecovery {
+  error: String,
+  corrupted: PathBuf,
+  backups: Vec<PathBuf>,
+  /// Written instead of the current cache on exit so the backup is used on the next launch
+  restored: Option<Cache>,
+  restore_error: Option<String>,
+  open: bool,
+}
+
+impl CacheRecovery {
+  pub fn is_open(&self) -> bool {
+    self.open
+  }
+
+  pub fn close(&mut self) {
+    self.open = false;
+  }
+
+  pub fn error(&self) -> &str {
+    &self.error
+  }
+
+  pub fn corrupted_file(&self) -> &Path {
+    &self.corrupted
+  }
+
+  pub fn backups(&self) -> &[PathBuf] {
+    &self.backups
+  }
+
+  pub fn restored(&self) -> bool {
+    self.restored.is_some()
+  }
+
+  pub fn restore_error(&self) -> Option<&str> {
+    self.restore_error.as_deref()
+  }
+
+  pub fn restore(&mut self, backup: &Path) {
+    match Cache::load(backup) {
+      Ok(cache) => {
+        info!("Restoring cache from {}", backup.display());
+        self.restored = Some(cache);
+        self.restore_error = None;
+      }
+      Err(err) => {
+        self.restore_error = Some(format!("{}: {err}", backup.display()));
+      }
+    }
+  }
+
+  /// Saves the restored backup if there is one, otherwise the current cache
+  pub fn save(recovery: Option<Res<Self>>, cache: &Cache) {
+    match recovery
+      .as_ref()
+      .and_then(|recovery| recovery.restored.as_ref())
+    {
+      Some(restored) => restored.save(),
+      None => cache.save(),
+    }
+  }
+}
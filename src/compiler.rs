@@ -0,0 +1,198 @@
+use crate::{assets::Prefab, scenes::SceneTypeRegistry, util};
+use bevy::{prelude::*, reflect::TypeRegistry, scene::serde::SceneDeserializer};
+use serde::de::DeserializeSeed;
+use std::path::{Path, PathBuf};
+
+/// Command line flag that runs the compiler instead of opening the editor
+const CHECK_FLAG: &str = "--check";
+const DEFAULT_ASSET_DIR: &str = "assets";
+const SCENE_EXTENSIONS: &[&str] = &["scn", "scn.ron"];
+
+#[derive(thiserror::Error, Debug)]
+pub enum CompileError {
+  #[error("{}: {source}", path.display())]
+  Io {
+    path: PathBuf,
+    source: std::io::Error,
+  },
+  #[error("{}:{message}", path.display())]
+  Scene { path: PathBuf, message: String },
+  #[error(
+    "{}: '{type_path}' is registered for scenes but is not a reflected component, add #[reflect(Component)] to it",
+    path.display()
+  )]
+  NotAComponent { path: PathBuf, type_path: String },
+  #[error("{}:{message}\n  the file does not deserialize into the descriptor of {prefab}", path.display())]
+  Prefab {
+    path: PathBuf,
+    prefab: &'static str,
+    message: String,
+  },
+}
+
+type PrefabCheck = fn(&Path, &mut Vec<CompileError>);
+
+/// Validates scenes and prefab descriptors without opening a window
+///
+/// Run the editor binary with `--check [asset dir]` to use it, i.e. from CI
+#[derive(Default)]
+pub struct SceneCompiler {
+  prefab_checks: Vec<(&'static str, PrefabCheck)>,
+}
+
+impl SceneCompiler {
+  /// The asset directory to check if the editor was launched with `--check`
+  pub fn requested() -> Option<PathBuf> {
+    let mut args = std::env::args().skip_while(|arg| arg != CHECK_FLAG);
+    args.next()?;
+    Some(
+      args
+        .next()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_ASSET_DIR)),
+    )
+  }
+
+  pub fn add_prefab<T: Prefab>(&mut self) {
+    self
+      .prefab_checks
+      .push((util::short_name_of::<T>(), Self::check_prefabs::<T>));
+  }
+
+  pub fn run(&self, root: &Path, scene_type_registry: &SceneTypeRegistry) -> AppExit {
+    let mut errors = Vec::new();
+
+    let scene_files = Self::files_in(root, SCENE_EXTENSIONS, &mut errors);
+    let type_registry = scene_type_registry.read();
+    for file in &scene_files {
+      Self::check_scene(file, &type_registry, &mut errors);
+    }
+
+    println!(
+      "checked {} scene(s) in {}",
+      scene_files.len(),
+      root.display()
+    );
+
+    for (name, check) in &self.prefab_checks {
+      check(root, &mut errors);
+      println!("checked {name} prefabs");
+    }
+
+    if errors.is_empty() {
+      println!("no errors");
+      return AppExit::Success;
+    }
+
+    for error in &errors {
+      eprintln!("error: {error}");
+    }
+
+    eprintln!("{} error(s)", errors.len());
+
+    AppExit::error()
+  }
+
+  fn check_scene(path: &Path, type_registry: &TypeRegistry, errors: &mut Vec<CompileError>) {
+    let bytes = match std::fs::read(path) {
+      Ok(bytes) => bytes,
+      Err(source) => {
+        errors.push(CompileError::Io {
+          path: path.to_path_buf(),
+          source,
+        });
+        return;
+      }
+    };
+
+    let scene_error = |message: String| CompileError::Scene {
+      path: path.to_path_buf(),
+      message,
+    };
+
+    let mut deserializer = match ron::de::Deserializer::from_bytes(&bytes) {
+      Ok(deserializer) => deserializer,
+      Err(err) => {
+        errors.push(scene_error(err.to_string()));
+        return;
+      }
+    };
+
+    let scene = match (SceneDeserializer { type_registry }).deserialize(&mut deserializer) {
+      Ok(scene) => scene,
+      Err(err) => {
+        errors.push(scene_error(deserializer.span_error(err).to_string()));
+        return;
+      }
+    };
+
+    // deserializing only needs the type to be registered, spawning also needs ReflectComponent
+    let mut reported = Vec::new();
+    for component in scene.entities.iter().flat_map(|entity| &entity.components) {
+      let Some(info) = component.get_represented_type_info() else {
+        continue;
+      };
+
+      let registered = type_registry
+        .get(info.type_id())
+        .is_some_and(|registration| registration.data::<ReflectComponent>().is_some());
+
+      if !registered && !reported.contains(&info.type_id()) {
+        reported.push(info.type_id());
+        errors.push(CompileError::NotAComponent {
+          path: path.to_path_buf(),
+          type_path: info.type_path().to_string(),
+        });
+      }
+    }
+  }
+
+  fn check_prefabs<T: Prefab>(root: &Path, errors: &mut Vec<CompileError>) {
+    for file in Self::files_in(&root.join(T::DIR), T::EXTENSIONS, errors) {
+      let result = std::fs::read(&file).map(|bytes| ron::de::from_bytes::<T::Descriptor>(&bytes));
+
+      match result {
+        Ok(Ok(_)) => (),
+        Ok(Err(err)) => errors.push(CompileError::Prefab {
+          path: file,
+          prefab: util::short_name_of::<T>(),
+          message: err.to_string(),
+        }),
+        Err(source) => errors.push(CompileError::Io { path: file, source }),
+      }
+    }
+  }
+
+  /// All files under `dir` ending in one of `extensions`
+  fn files_in(dir: &Path, extensions: &[&str], errors: &mut Vec<CompileError>) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut dirs = vec![dir.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+      let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(source) => {
+          errors.push(CompileError::Io { path: dir, source });
+          continue;
+        }
+      };
+
+      for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+          dirs.push(path);
+        } else if path.file_name().is_some_and(|name| {
+          let name = name.to_string_lossy();
+          extensions
+            .iter()
+            .any(|ext| name.ends_with(&format!(".{ext}")))
+        }) {
+          files.push(path);
+        }
+      }
+    }
+
+    files.sort();
+    files
+  }
+}
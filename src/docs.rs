@@ -0,0 +1,247 @@
+use crate::{assets::Prefabs, cache, scenes::SceneTypeRegistry};
+use bevy::{
+  prelude::*,
+  reflect::{TypeInfo, TypeRegistration, VariantInfo},
+};
+use bevy_inspector_egui::inspector_egui_impls::InspectorEguiImpl;
+use std::{fmt::Write, path::PathBuf};
+
+const DOCS_DIR: &str = "docs";
+const REFERENCE_FILE: &str = "reference.md";
+
+/// Markdown reference of the components, prefabs and panels the editor was set up with
+///
+/// Doc comments of the types are only included when the `reflect_docs` feature is enabled
+pub(crate) struct Reference {
+  components: Vec<TypeDoc>,
+  prefabs: Vec<TypeDoc>,
+  panels: Vec<&'static str>,
+}
+
+struct TypeDoc {
+  name: String,
+  type_path: String,
+  docs: Option<String>,
+  custom_editor: bool,
+  rows: Vec<Row>,
+}
+
+/// A field of a struct or a variant of an enum
+struct Row {
+  name: String,
+  type_path: String,
+  docs: Option<String>,
+}
+
+impl Reference {
+  pub(crate) fn new(world: &World, panels: Vec<&'static str>) -> Self {
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+
+    let mut components = world
+      .resource::<SceneTypeRegistry>()
+      .read()
+      .iter()
+      .map(|registration| {
+        // editor impls are registered on the app's registry, not the scene one
+        let custom_editor = type_registry
+          .get_type_data::<InspectorEguiImpl>(registration.type_id())
+          .is_some();
+        TypeDoc::new(registration, custom_editor)
+      })
+      .collect::<Vec<_>>();
+    components.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut prefabs = world
+      .get_resource::<Prefabs>()
+      .map(|prefabs| {
+        prefabs
+          .keys()
+          .map(|name| match type_registry.get_with_short_type_path(name) {
+            Some(registration) => TypeDoc::new(registration, false),
+            None => TypeDoc::unknown(name),
+          })
+          .collect::<Vec<_>>()
+      })
+      .unwrap_or_default();
+    prefabs.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut panels = panels;
+    panels.sort();
+
+    Self {
+      components,
+      prefabs,
+      panels,
+    }
+  }
+
+  /// Writes the reference into the docs directory next to the executable, returns the file
+  pub(crate) fn write(&self) -> std::io::Result<PathBuf> {
+    let dir = cache::cache_dir().join(DOCS_DIR);
+    std::fs::create_dir_all(&dir)?;
+
+    let file = dir.join(REFERENCE_FILE);
+    std::fs::write(&file, self.to_markdown())?;
+    Ok(file)
+  }
+
+  fn to_markdown(&self) -> String {
+    let mut out = String::from("# Editor Reference\n\n");
+
+    writeln!(out, "## Components\n").unwrap();
+    if self.components.is_empty() {
+      writeln!(out, "No components are registered for scenes.\n").unwrap();
+    }
+    for component in &self.components {
+      component.write(&mut out, "Edited in the Inspector");
+    }
+
+    writeln!(out, "## Prefabs\n").unwrap();
+    if self.prefabs.is_empty() {
+      writeln!(out, "No prefabs are registered.\n").unwrap();
+    }
+    for prefab in &self.prefabs {
+      prefab.write(&mut out, "Spawned from the Prefabs panel");
+    }
+
+    writeln!(out, "## Panels\n").unwrap();
+    for panel in &self.panels {
+      writeln!(out, "- {panel}").unwrap();
+    }
+
+    out
+  }
+}
+
+impl TypeDoc {
+  fn new(registration: &TypeRegistration, custom_editor: bool) -> Self {
+    let info = registration.type_info();
+
+    let rows = match info {
+      TypeInfo::Struct(info) => info
+        .iter()
+        .map(|field| Row {
+          name: field.name().to_string(),
+          type_path: field.type_path().to_string(),
+          docs: field_docs(field),
+        })
+        .collect(),
+      TypeInfo::TupleStruct(info) => info
+        .iter()
+        .map(|field| Row {
+          name: field.index().to_string(),
+          type_path: field.type_path().to_string(),
+          docs: None,
+        })
+        .collect(),
+      TypeInfo::Enum(info) => info
+        .iter()
+        .map(|variant| Row {
+          name: variant.name().to_string(),
+          type_path: variant_fields(variant),
+          docs: variant_docs(variant),
+        })
+        .collect(),
+      _ => Vec::new(),
+    };
+
+    Self {
+      name: info.type_path_table().short_path().to_string(),
+      type_path: info.type_path().to_string(),
+      docs: type_docs(info),
+      custom_editor,
+      rows,
+    }
+  }
+
+  fn unknown(name: &str) -> Self {
+    Self {
+      name: name.to_string(),
+      type_path: name.to_string(),
+      docs: None,
+      custom_editor: false,
+      rows: Vec::new(),
+    }
+  }
+
+  fn write(&self, out: &mut String, consumer: &str) {
+    writeln!(out, "### {}\n", self.name).unwrap();
+    writeln!(out, "`{}`\n", self.type_path).unwrap();
+
+    if let Some(docs) = &self.docs {
+      writeln!(out, "{}\n", docs.trim()).unwrap();
+    }
+
+    if self.custom_editor {
+      writeln!(out, "{consumer} with a custom editor.\n").unwrap();
+    } else {
+      writeln!(out, "{consumer}.\n").unwrap();
+    }
+
+    if self.rows.is_empty() {
+      return;
+    }
+
+    writeln!(out, "| Name | Type | Description |").unwrap();
+    writeln!(out, "| --- | --- | --- |").unwrap();
+    for row in &self.rows {
+      let docs = row.docs.as_deref().unwrap_or_default().replace('\n', " ");
+      writeln!(
+        out,
+        "| {} | `{}` | {} |",
+        row.name,
+        row.type_path,
+        docs.trim()
+      )
+      .unwrap();
+    }
+    writeln!(out).unwrap();
+  }
+}
+
+/// Fields of the variant in place of a type
+fn variant_fields(variant: &VariantInfo) -> String {
+  match variant {
+    VariantInfo::Unit(_) => String::new(),
+    VariantInfo::Tuple(info) => info
+      .iter()
+      .map(|field| field.type_path().to_string())
+      .collect::<Vec<_>>()
+      .join(", "),
+    VariantInfo::Struct(info) => info
+      .iter()
+      .map(|field| format!("{}: {}", field.name(), field.type_path()))
+      .collect::<Vec<_>>()
+      .join(", "),
+  }
+}
+
+#[cfg(feature = "reflect_docs")]
+fn type_docs(info: &TypeInfo) -> Option<String> {
+  info.docs().map(str::to_string)
+}
+
+#[cfg(not(feature = "reflect_docs"))]
+fn type_docs(_info: &TypeInfo) -> Option<String> {
+  None
+}
+
+#[cfg(feature = "reflect_docs")]
+fn field_docs(field: &bevy::reflect::NamedField) -> Option<String> {
+  field.docs().map(str::to_string)
+}
+
+#[cfg(not(feature = "reflect_docs"))]
+fn field_docs(_field: &bevy::reflect::NamedField) -> Option<String> {
+  None
+}
+
+#[cfg(feature = "reflect_docs")]
+fn variant_docs(variant: &VariantInfo) -> Option<String> {
+  variant.docs().map(str::to_string)
+}
+
+#[cfg(not(feature = "reflect_docs"))]
+fn variant_docs(_variant: &VariantInfo) -> Option<String> {
+  None
+}
@@ -0,0 +1,85 @@
+use crate::{util::WorldExtensions, EditorState};
+use bevy::prelude::*;
+use std::path::PathBuf;
+
+/// The editor is about to hand the world to the game, sent before the testing snapshot is taken
+#[derive(Event, Clone, Debug)]
+pub struct EditorWillEnterTesting;
+
+/// The testing snapshot has been taken and the game is running
+#[derive(Event, Clone, Debug)]
+pub struct EditorDidEnterTesting;
+
+/// Testing is about to stop, sent before the world is restored from the testing snapshot
+#[derive(Event, Clone, Debug)]
+pub struct EditorWillEnterEditing;
+
+/// The editor is back in control of the world, also sent once on startup
+#[derive(Event, Clone, Debug)]
+pub struct EditorDidEnterEditing;
+
+/// The editor is about to save its state and close the app
+#[derive(Event, Clone, Debug)]
+pub struct EditorWillExit;
+
+/// The scene is about to be written to the path
+#[derive(Event, Clone, Debug)]
+pub struct EditorWillSaveScene(pub PathBuf);
+
+/// The scene at the path is about to be loaded
+#[derive(Event, Clone, Debug)]
+pub struct EditorWillLoadScene(pub PathBuf);
+
+/// Every entity of the scene at the path has been spawned
+#[derive(Event, Clone, Debug)]
+pub struct EditorDidLoadScene(pub PathBuf);
+
+pub struct EditorEventsPlugin;
+
+impl Plugin for EditorEventsPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .add_event::<EditorWillEnterTesting>()
+      .add_event::<EditorDidEnterTesting>()
+      .add_event::<EditorWillEnterEditing>()
+      .add_event::<EditorDidEnterEditing>()
+      .add_event::<EditorWillExit>()
+      .add_event::<EditorWillSaveScene>()
+      .add_event::<EditorWillLoadScene>()
+      .add_event::<EditorDidLoadScene>();
+  }
+}
+
+/// Sends the event to readers and triggers it for observers
+///
+/// Observers run immediately, so they are the way to react before the editor carries on with the
+/// operation. Readers only see the event once their system runs
+pub(crate) fn notify<E: Event + Clone>(world: &mut World, event: E) {
+  world.trigger(event.clone());
+  world.send_event(event);
+}
+
+/// Runs when leaving [`EditorState::Editing`], the state resource already holds the next state
+pub(crate) fn on_leave_editing(world: &mut World) {
+  match world.get_state::<EditorState>() {
+    EditorState::Testing => notify(world, EditorWillEnterTesting),
+    EditorState::Exiting => notify(world, EditorWillExit),
+    EditorState::Editing => (),
+  }
+}
+
+pub(crate) fn on_leave_testing(world: &mut World) {
+  match world.get_state::<EditorState>() {
+    EditorState::Editing => notify(world, EditorWillEnterEditing),
+    EditorState::Exiting => notify(world, EditorWillExit),
+    EditorState::Testing => (),
+  }
+}
+
+pub(crate) fn on_enter_editing(world: &mut World) {
+  notify(world, EditorDidEnterEditing);
+}
+
+pub(crate) fn on_enter_testing(world: &mut World) {
+  notify(world, EditorDidEnterTesting);
+}
@@ -0,0 +1,196 @@
+use bevy::{
+  prelude::*,
+  reflect::{GetPath, TypeRegistration, TypeRegistry},
+};
+use std::fmt::{self, Display};
+
+/// Where the reflected value of a [`FieldPath`] lives
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FieldTarget {
+  Component(Entity),
+  Resource,
+}
+
+/// Address of a single reflected value in the world, i.e. `Transform.translation.x` on an entity
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FieldPath {
+  pub target: FieldTarget,
+  /// Short or full type path of the component or resource
+  pub type_name: String,
+  /// Reflection path into the value, empty to refer to the whole value
+  pub field: String,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum FieldPathError {
+  #[error("no registered type named '{0}'")]
+  UnknownType(String),
+  #[error("'{0}' is not a reflected component")]
+  NotAComponent(String),
+  #[error("'{0}' is not a reflected resource")]
+  NotAResource(String),
+  #[error("entity {0} does not exist")]
+  MissingEntity(Entity),
+  #[error("'{0}' is not present")]
+  MissingValue(String),
+  #[error("invalid field '{field}': {message}")]
+  InvalidField { field: String, message: String },
+}
+
+impl FieldPath {
+  pub fn component(entity: Entity, type_name: impl Into<String>, field: impl Into<String>) -> Self {
+    Self {
+      target: FieldTarget::Component(entity),
+      type_name: type_name.into(),
+      field: field.into(),
+    }
+  }
+
+  pub fn resource(type_name: impl Into<String>, field: impl Into<String>) -> Self {
+    Self {
+      target: FieldTarget::Resource,
+      type_name: type_name.into(),
+      field: field.into(),
+    }
+  }
+
+  /// Calls `f` with the value the path points to
+  ///
+  /// `f` returns whether it modified the value so change detection is only triggered on writes
+  pub fn with_value(
+    &self,
+    world: &mut World,
+    f: impl FnOnce(&mut dyn PartialReflect, &TypeRegistry) -> bool,
+  ) -> Result<bool, FieldPathError> {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+
+    let registration = Self::registration(&type_registry, &self.type_name)?;
+
+    match self.target {
+      FieldTarget::Component(entity) => {
+        let reflect_component = registration
+          .data::<ReflectComponent>()
+          .ok_or_else(|| FieldPathError::NotAComponent(self.type_name.clone()))?;
+        let Ok(mut entity_mut) = world.get_entity_mut(entity) else {
+          return Err(FieldPathError::MissingEntity(entity));
+        };
+        let value = reflect_component
+          .reflect_mut(&mut entity_mut)
+          .ok_or_else(|| FieldPathError::MissingValue(self.to_string()))?;
+        self.apply(value, &type_registry, f)
+      }
+      FieldTarget::Resource => {
+        let reflect_resource = registration
+          .data::<ReflectResource>()
+          .ok_or_else(|| FieldPathError::NotAResource(self.type_name.clone()))?;
+        let value = reflect_resource
+          .reflect_mut(world)
+          .ok_or_else(|| FieldPathError::MissingValue(self.to_string()))?;
+        self.apply(value, &type_registry, f)
+      }
+    }
+  }
+
+  fn apply(
+    &self,
+    mut value: Mut<dyn Reflect>,
+    type_registry: &TypeRegistry,
+    f: impl FnOnce(&mut dyn PartialReflect, &TypeRegistry) -> bool,
+  ) -> Result<bool, FieldPathError> {
+    let field =
+      Self::field_of(value.bypass_change_detection(), &self.field).map_err(|message| {
+        FieldPathError::InvalidField {
+          field: self.field.clone(),
+          message,
+        }
+      })?;
+
+    let changed = f(field, type_registry);
+
+    if changed {
+      value.set_changed();
+    }
+
+    Ok(changed)
+  }
+
+  /// Numeric value of the path if it refers to a primitive number
+  pub fn as_f64(value: &dyn PartialReflect) -> Option<f64> {
+    let value = value.try_as_reflect()?.as_any();
+    macro_rules! try_num {
+      ($($t:ty),*) => {
+        $(if let Some(v) = value.downcast_ref::<$t>() {
+          return Some(*v as f64);
+        })*
+      };
+    }
+    try_num!(f32, f64, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+    None
+  }
+
+  /// Writes a number to a primitive number field, rounding for integers, returns whether it did
+  pub fn set_f64(value: &mut dyn PartialReflect, number: f64) -> bool {
+    let Some(value) = value.try_as_reflect_mut().map(Reflect::as_any_mut) else {
+      return false;
+    };
+    macro_rules! try_set {
+      ($($t:ty),*) => {
+        $(if let Some(v) = value.downcast_mut::<$t>() {
+          *v = number.round() as $t;
+          return true;
+        })*
+      };
+    }
+    if let Some(v) = value.downcast_mut::<f32>() {
+      *v = number as f32;
+      return true;
+    }
+    if let Some(v) = value.downcast_mut::<f64>() {
+      *v = number;
+      return true;
+    }
+    try_set!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+    false
+  }
+
+  fn registration<'r>(
+    type_registry: &'r TypeRegistry,
+    name: &str,
+  ) -> Result<&'r TypeRegistration, FieldPathError> {
+    type_registry
+      .get_with_short_type_path(name)
+      .or_else(|| type_registry.get_with_type_path(name))
+      .ok_or_else(|| FieldPathError::UnknownType(name.to_string()))
+  }
+
+  fn field_of<'a>(
+    value: &'a mut dyn Reflect,
+    field: &str,
+  ) -> Result<&'a mut dyn PartialReflect, String> {
+    if field.is_empty() {
+      return Ok(value.as_partial_reflect_mut());
+    }
+
+    value.reflect_path_mut(field).map_err(|err| err.to_string())
+  }
+}
+
+impl Display for FieldPath {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if let FieldTarget::Component(entity) = self.target {
+      write!(f, "{entity}/")?;
+    }
+
+    write!(f, "{}", self.type_name)?;
+
+    if !self.field.is_empty() {
+      if !self.field.starts_with(['.', '[', '#']) {
+        write!(f, ".")?;
+      }
+      write!(f, "{}", self.field)?;
+    }
+
+    Ok(())
+  }
+}
@@ -0,0 +1,400 @@
+use crate::{field_path::FieldPath, testing::EditorOwned, util::matches_pattern};
+use bevy::{
+  asset::{ReflectAsset, ReflectHandle, UntypedHandle},
+  prelude::*,
+  reflect::{ReflectPath, ReflectRef, TypeRegistry},
+  utils::HashSet,
+};
+use std::{any::TypeId, str::FromStr};
+
+/// A search for entities by the reflected value of one of their components
+///
+/// Written as `Type.field op value`, separated by spaces, i.e. `Health.current < 10` or
+/// `Name ~ "Enemy*"`. Without an operator every entity with the component matches. `->` follows
+/// an asset handle into the asset, so `MeshMaterial3d<StandardMaterial>.0->base_color_texture ~
+/// "textures/rock*"` finds the entities whose material uses a rock texture
+///
+/// | Operator | Meaning |
+/// | --- | --- |
+/// | `==`, `!=` | Equal numbers, booleans or text |
+/// | `<`, `<=`, `>`, `>=` | Compares numbers |
+/// | `~` | Text matching a pattern where `*` matches anything |
+///
+/// Handles compare by their asset path, enums without fields by their variant name and other
+/// values by their debug representation
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldQuery {
+  /// Short or full type path of the component
+  pub type_name: String,
+  /// Reflection paths, each one after the first is applied to the asset of the previous handle
+  pub fields: Vec<String>,
+  pub condition: Option<(QueryOp, QueryValue)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QueryOp {
+  Eq,
+  Ne,
+  Lt,
+  Le,
+  Gt,
+  Ge,
+  Matches,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum QueryValue {
+  Number(f64),
+  Bool(bool),
+  Text(String),
+}
+
+/// An entity found by a [`FieldQuery`] along with the value that matched
+#[derive(Clone, Debug)]
+pub struct QueryMatch {
+  pub entity: Entity,
+  pub value: String,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum FieldQueryError {
+  #[error("the query is empty")]
+  Empty,
+  #[error("unknown operator '{0}'")]
+  UnknownOperator(String),
+  #[error("'{0}' needs a value to compare with")]
+  MissingValue(String),
+  #[error("'{0}' only compares numbers")]
+  NotANumber(String),
+  #[error("no registered type named '{0}'")]
+  UnknownType(String),
+  #[error("'{0}' is not a reflected component")]
+  NotAComponent(String),
+  #[error("invalid field '{field}': {message}")]
+  InvalidField { field: String, message: String },
+}
+
+impl FromStr for QueryOp {
+  type Err = FieldQueryError;
+
+  fn from_str(op: &str) -> Result<Self, Self::Err> {
+    match op {
+      "==" | "=" => Ok(Self::Eq),
+      "!=" => Ok(Self::Ne),
+      "<" => Ok(Self::Lt),
+      "<=" => Ok(Self::Le),
+      ">" => Ok(Self::Gt),
+      ">=" => Ok(Self::Ge),
+      "~" => Ok(Self::Matches),
+      _ => Err(FieldQueryError::UnknownOperator(op.to_string())),
+    }
+  }
+}
+
+impl QueryOp {
+  fn is_ordering(self) -> bool {
+    matches!(self, Self::Lt | Self::Le | Self::Gt | Self::Ge)
+  }
+
+  fn compare<T: PartialOrd + ?Sized>(self, actual: &T, expected: &T) -> bool {
+    match self {
+      Self::Eq | Self::Matches => actual == expected,
+      Self::Ne => actual != expected,
+      Self::Lt => actual < expected,
+      Self::Le => actual <= expected,
+      Self::Gt => actual > expected,
+      Self::Ge => actual >= expected,
+    }
+  }
+}
+
+impl QueryValue {
+  fn parse(text: &str) -> Self {
+    if let Some(text) = text
+      .strip_prefix('"')
+      .and_then(|text| text.strip_suffix('"'))
+    {
+      return Self::Text(text.to_string());
+    }
+
+    match text {
+      "true" => Self::Bool(true),
+      "false" => Self::Bool(false),
+      _ => text
+        .parse()
+        .map(Self::Number)
+        .unwrap_or_else(|_| Self::Text(text.to_string())),
+    }
+  }
+}
+
+impl FromStr for FieldQuery {
+  type Err = FieldQueryError;
+
+  fn from_str(query: &str) -> Result<Self, Self::Err> {
+    let query = query.trim();
+    let (lhs, rest) = query.split_once(char::is_whitespace).unwrap_or((query, ""));
+    if lhs.is_empty() {
+      return Err(FieldQueryError::Empty);
+    }
+
+    let mut fields = lhs.split("->").map(str::to_string).collect::<Vec<_>>();
+    let (type_name, field) = Self::split_type(&fields[0]);
+    let type_name = type_name.to_string();
+    fields[0] = field.to_string();
+
+    let rest = rest.trim();
+    let condition = if rest.is_empty() {
+      None
+    } else {
+      let (op, value) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+      let op_text = op;
+      let op = op.parse::<QueryOp>()?;
+
+      let value = value.trim();
+      if value.is_empty() {
+        return Err(FieldQueryError::MissingValue(op_text.to_string()));
+      }
+
+      let value = QueryValue::parse(value);
+      if op.is_ordering() && !matches!(value, QueryValue::Number(_)) {
+        return Err(FieldQueryError::NotANumber(op_text.to_string()));
+      }
+
+      Some((op, value))
+    };
+
+    Ok(Self {
+      type_name,
+      fields,
+      condition,
+    })
+  }
+}
+
+impl FieldQuery {
+  /// Splits `Type.field` where the type may have generics, i.e. `Handle<Image>`
+  fn split_type(path: &str) -> (&str, &str) {
+    let mut depth = 0;
+    for (i, c) in path.char_indices() {
+      match c {
+        '<' => depth += 1,
+        '>' => depth -= 1,
+        '.' | '[' if depth == 0 => return (&path[..i], &path[i..]),
+        _ => {}
+      }
+    }
+    (path, "")
+  }
+
+  /// Entities of the scene that match, editor entities are never searched
+  pub fn run(&self, world: &mut World) -> Result<Vec<QueryMatch>, FieldQueryError> {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+
+    let registration = type_registry
+      .get_with_short_type_path(&self.type_name)
+      .or_else(|| type_registry.get_with_type_path(&self.type_name))
+      .ok_or_else(|| FieldQueryError::UnknownType(self.type_name.clone()))?;
+    let reflect_component = registration
+      .data::<ReflectComponent>()
+      .ok_or_else(|| FieldQueryError::NotAComponent(self.type_name.clone()))?;
+
+    let Some(component_id) = world.components().get_id(registration.type_id()) else {
+      // never inserted so nothing can have it
+      return Ok(Vec::new());
+    };
+
+    let editor_owned = world
+      .query_filtered::<Entity, EditorOwned>()
+      .iter(world)
+      .collect::<HashSet<_>>();
+
+    let world = &*world;
+    let mut matches = Vec::new();
+    let mut invalid_field = None;
+
+    let entities = world
+      .iter_entities()
+      .filter(|entity| entity.contains_id(component_id) && !editor_owned.contains(&entity.id()));
+
+    for entity in entities {
+      let id = entity.id();
+      let Some(component) = reflect_component.reflect(entity) else {
+        continue;
+      };
+
+      let value = match self.resolve(component.as_partial_reflect(), world, &type_registry) {
+        Ok(value) => value,
+        Err(err) => {
+          // enum variants may hold the field on some entities and not on others
+          invalid_field.get_or_insert(err);
+          continue;
+        }
+      };
+
+      let text = Self::describe(value, world, &type_registry);
+      let matched = match &self.condition {
+        Some((op, expected)) => Self::test(value, &text, *op, expected),
+        None => true,
+      };
+
+      if matched {
+        matches.push(QueryMatch {
+          entity: id,
+          value: text,
+        });
+      }
+    }
+
+    match invalid_field {
+      Some(err) if matches.is_empty() => Err(err),
+      _ => Ok(matches),
+    }
+  }
+
+  fn resolve<'w>(
+    &self,
+    mut value: &'w dyn PartialReflect,
+    world: &'w World,
+    type_registry: &TypeRegistry,
+  ) -> Result<&'w dyn PartialReflect, FieldQueryError> {
+    for (i, field) in self.fields.iter().enumerate() {
+      if i > 0 {
+        value = Self::asset_of(value, world, type_registry)
+          .ok_or_else(|| FieldQueryError::InvalidField {
+            field: self.fields[..i].join("->"),
+            message: String::from("not a handle to a loaded asset"),
+          })?
+          .as_partial_reflect();
+      }
+
+      if !field.is_empty() {
+        value =
+          field
+            .as_str()
+            .reflect_element(value)
+            .map_err(|err| FieldQueryError::InvalidField {
+              field: field.clone(),
+              message: err.to_string(),
+            })?;
+      }
+    }
+
+    Ok(value)
+  }
+
+  /// The handle the value is or, for optional handles, holds
+  fn handle_of(
+    value: &dyn PartialReflect,
+    type_registry: &TypeRegistry,
+  ) -> Option<(UntypedHandle, TypeId)> {
+    if let ReflectRef::Enum(value) = value.reflect_ref() {
+      if value.variant_name() == "Some" && value.field_len() == 1 {
+        return Self::handle_of(value.field_at(0)?, type_registry);
+      }
+    }
+
+    let value = value.try_as_reflect()?.as_any();
+    let reflect_handle = type_registry.get_type_data::<ReflectHandle>(value.type_id())?;
+    let handle = reflect_handle.downcast_handle_untyped(value)?;
+    Some((handle, reflect_handle.asset_type_id()))
+  }
+
+  fn asset_of<'w>(
+    value: &dyn PartialReflect,
+    world: &'w World,
+    type_registry: &TypeRegistry,
+  ) -> Option<&'w dyn Reflect> {
+    let (handle, asset_type) = Self::handle_of(value, type_registry)?;
+    type_registry
+      .get_type_data::<ReflectAsset>(asset_type)?
+      .get(world, handle)
+  }
+
+  /// Text the value is shown as and compared with
+  fn describe(value: &dyn PartialReflect, world: &World, type_registry: &TypeRegistry) -> String {
+    if let Some(any) = value.try_as_reflect().map(Reflect::as_any) {
+      if let Some(text) = any.downcast_ref::<String>() {
+        return text.clone();
+      }
+      if let Some(name) = any.downcast_ref::<Name>() {
+        return name.to_string();
+      }
+    }
+
+    if let Some((handle, _)) = Self::handle_of(value, type_registry) {
+      return world
+        .resource::<AssetServer>()
+        .get_path(handle.id())
+        .map(|path| path.to_string())
+        .unwrap_or_else(|| format!("{:?}", handle.id()));
+    }
+
+    if let Some(number) = FieldPath::as_f64(value) {
+      return number.to_string();
+    }
+
+    match value.reflect_ref() {
+      ReflectRef::Enum(value) if value.field_len() == 0 => value.variant_name().to_string(),
+      _ => format!("{value:?}"),
+    }
+  }
+
+  fn test(value: &dyn PartialReflect, text: &str, op: QueryOp, expected: &QueryValue) -> bool {
+    match expected {
+      QueryValue::Number(expected) => match FieldPath::as_f64(value) {
+        Some(actual) => op.compare(&actual, expected),
+        None => false,
+      },
+      QueryValue::Bool(expected) => value
+        .try_downcast_ref::<bool>()
+        .is_some_and(|actual| op.compare(actual, expected)),
+      QueryValue::Text(pattern) if op == QueryOp::Matches => matches_pattern(pattern, text),
+      QueryValue::Text(expected) => op.compare(text, expected.as_str()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn follows_optional_handles_into_assets() {
+    let mut app = App::new();
+    app
+      .add_plugins((MinimalPlugins, AssetPlugin::default()))
+      .init_asset::<Image>()
+      .register_asset_reflect::<Image>()
+      .init_asset::<StandardMaterial>()
+      .register_asset_reflect::<StandardMaterial>()
+      .register_type::<MeshMaterial3d<StandardMaterial>>();
+
+    let asset_server = app.world().resource::<AssetServer>().clone();
+    let mut materials = app.world_mut().resource_mut::<Assets<StandardMaterial>>();
+    let rock = materials.add(StandardMaterial {
+      base_color_texture: Some(asset_server.load("textures/rock.png")),
+      ..default()
+    });
+    let grass = materials.add(StandardMaterial {
+      base_color_texture: Some(asset_server.load("textures/grass.png")),
+      ..default()
+    });
+    let untextured = materials.add(StandardMaterial::default());
+
+    let world = app.world_mut();
+    let rock = world.spawn(MeshMaterial3d(rock)).id();
+    world.spawn(MeshMaterial3d(grass));
+    world.spawn(MeshMaterial3d(untextured));
+
+    let query = r#"MeshMaterial3d<StandardMaterial>.0->base_color_texture ~ "textures/rock*""#
+      .parse::<FieldQuery>()
+      .unwrap();
+    let matches = query.run(world).unwrap();
+
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].entity, rock);
+    assert_eq!(matches[0].value, "textures/rock.png");
+  }
+}
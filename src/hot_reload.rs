@@ -0,0 +1,213 @@
+use crate::{
+  assets::{PrefabRegistrar, Prefabs, StaticPrefab},
+  safe_mode::ProjectTrust,
+  scenes::SceneTypeRegistry,
+};
+use bevy::{
+  prelude::*,
+  reflect::{GetTypeRegistration, TypeRegistry},
+  time::common_conditions::on_timer,
+};
+use libloading::{Library, Symbol};
+use std::{
+  path::{Path, PathBuf},
+  time::{Duration, SystemTime},
+};
+
+/// Name of the function the game library must export
+///
+/// ```ignore
+/// #[no_mangle]
+/// pub fn bevy_editor_hot_reload(ctx: &mut HotReloadContext) {
+///   ctx.register_type::<MyComponent>();
+/// }
+/// ```
+pub const HOT_RELOAD_SYMBOL: &[u8] = b"bevy_editor_hot_reload";
+
+type HotReloadFn = fn(&mut HotReloadContext);
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Watches the game's dynamic library and reruns its registrations whenever it is rebuilt
+///
+/// Experimental, the library has to be built with the exact same compiler and dependency versions
+/// as the editor otherwise the types that it registers will not match up. Nothing is loaded until
+/// the project is trusted, see [`ProjectTrust`]
+pub struct HotReloadPlugin {
+  path: PathBuf,
+}
+
+impl HotReloadPlugin {
+  pub fn new(path: impl Into<PathBuf>) -> Self {
+    Self { path: path.into() }
+  }
+}
+
+impl Plugin for HotReloadPlugin {
+  fn build(&self, app: &mut App) {
+    ProjectTrust::request(
+      app,
+      format!("Load and run the game library at {}", self.path.display()),
+    );
+
+    // the library as it is at startup is already linked in, only rebuilds get reloaded
+    let modified = std::fs::metadata(&self.path)
+      .and_then(|meta| meta.modified())
+      .ok();
+
+    app
+      .insert_resource(HotReloadState {
+        path: self.path.clone(),
+        modified,
+        generation: 0,
+        libraries: Vec::new(),
+        copy: None,
+      })
+      .add_systems(
+        Update,
+        HotReloadState::check_for_changes
+          .run_if(ProjectTrust::allows_extensions.and(on_timer(POLL_INTERVAL))),
+      );
+  }
+}
+
+#[derive(Resource)]
+struct HotReloadState {
+  path: PathBuf,
+  modified: Option<SystemTime>,
+  generation: usize,
+  /// Every library that was ever loaded is kept around since the type registry may still point into them
+  libraries: Vec<Library>,
+  /// File the current library was loaded from
+  copy: Option<PathBuf>,
+}
+
+#[derive(thiserror::Error, Debug)]
+enum HotReloadError {
+  #[error("failed to copy the library: {0}")]
+  Copy(#[from] std::io::Error),
+  #[error(transparent)]
+  Load(#[from] libloading::Error),
+}
+
+impl HotReloadState {
+  fn check_for_changes(world: &mut World) {
+    world.resource_scope(|world, mut state: Mut<HotReloadState>| {
+      let modified = match std::fs::metadata(&state.path).and_then(|meta| meta.modified()) {
+        Ok(modified) => modified,
+        Err(err) => {
+          debug!("Unable to read {}: {err}", state.path.display());
+          return;
+        }
+      };
+
+      if state.modified == Some(modified) {
+        return;
+      }
+
+      state.modified = Some(modified);
+
+      if let Err(err) = state.reload(world) {
+        error!("Failed to hot reload {}: {err}", state.path.display());
+      }
+    });
+  }
+
+  fn reload(&mut self, world: &mut World) -> Result<(), HotReloadError> {
+    info!("Hot reloading {}", self.path.display());
+
+    // the same path can't be loaded twice, so each generation gets loaded from a copy
+    let copy = Self::copy_path(&self.path, self.generation);
+    std::fs::copy(&self.path, &copy)?;
+    self.generation += 1;
+
+    let library = unsafe { Library::new(&copy)? };
+
+    let mut ctx = HotReloadContext {
+      types: TypeRegistry::empty(),
+      prefab_registrar: default(),
+    };
+
+    {
+      let register: Symbol<HotReloadFn> = unsafe { library.get(HOT_RELOAD_SYMBOL)? };
+      register(&mut ctx);
+    }
+
+    self.libraries.push(library);
+
+    // the previous library stays loaded, its file is no longer needed once it's mapped
+    if let Some(previous) = self.copy.replace(copy) {
+      if let Err(err) = std::fs::remove_file(&previous) {
+        warn!("Failed to remove {}: {err}", previous.display());
+      }
+    }
+
+    ctx.apply(world);
+
+    Ok(())
+  }
+
+  fn copy_path(path: &Path, generation: usize) -> PathBuf {
+    let mut file_name = path.file_stem().unwrap_or_default().to_os_string();
+    file_name.push(format!("-hot-{generation}"));
+    let mut copy = path.with_file_name(file_name);
+    if let Some(extension) = path.extension() {
+      copy.set_extension(extension);
+    }
+    copy
+  }
+}
+
+/// Handed to the game library so it can redo its registrations
+pub struct HotReloadContext {
+  types: TypeRegistry,
+  prefab_registrar: PrefabRegistrar,
+}
+
+impl HotReloadContext {
+  pub fn register_type<T>(&mut self) -> &mut Self
+  where
+    T: GetTypeRegistration,
+  {
+    self.types.register::<T>();
+    self
+  }
+
+  pub fn register_static_prefab<T>(&mut self) -> &mut Self
+  where
+    T: StaticPrefab,
+  {
+    self.register_type::<T>();
+    self.prefab_registrar.register::<T>();
+    self
+  }
+
+  fn apply(self, world: &mut World) {
+    let Self {
+      types,
+      prefab_registrar,
+    } = self;
+
+    let registrations = types.iter().cloned().collect::<Vec<_>>();
+
+    {
+      let mut app_types = world.resource::<AppTypeRegistry>().write();
+      for registration in registrations.iter().cloned() {
+        app_types.overwrite_registration(registration);
+      }
+    }
+
+    {
+      let mut scene_types = world.resource::<SceneTypeRegistry>().write();
+      for registration in registrations.iter().cloned() {
+        scene_types.overwrite_registration(registration);
+      }
+    }
+
+    world.resource_scope(|world, mut prefabs: Mut<Prefabs>| {
+      prefabs.extend_with(world, prefab_registrar);
+    });
+
+    info!("Hot reloaded {} types", registrations.len());
+  }
+}
@@ -0,0 +1,173 @@
+use crate::{read_only::ReadOnly, scenes::LoadEvent, ui::status_bar::StatusMessage, util};
+use bevy::{ecs::event::EventCursor, prelude::*, utils::HashMap, window::FileDragAndDrop};
+use std::{
+  path::{Path, PathBuf},
+  sync::Arc,
+};
+
+const SCENE_EXTENSIONS: &[&str] = &["scn", "scn.ron"];
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "bmp", "tga", "hdr", "ktx2", "dds"];
+const GLTF_EXTENSIONS: &[&str] = &["gltf", "glb"];
+
+#[derive(thiserror::Error, Debug)]
+pub enum ImportError {
+  #[error("failed to copy into the asset folder: {0}")]
+  Io(#[from] std::io::Error),
+  #[error("{0}")]
+  Other(String),
+}
+
+pub type ImportFn = dyn Fn(&Path, &mut World) -> Result<(), ImportError> + Send + Sync;
+
+pub trait ImporterAppExt {
+  /// Handle files with any of `extensions` that are dropped onto the editor window
+  ///
+  /// Replaces any importer previously registered for the same extension
+  fn register_importer(
+    &mut self,
+    extensions: &[&str],
+    importer: impl Fn(&Path, &mut World) -> Result<(), ImportError> + Send + Sync + 'static,
+  ) -> &mut Self;
+}
+
+impl ImporterAppExt for App {
+  fn register_importer(
+    &mut self,
+    extensions: &[&str],
+    importer: impl Fn(&Path, &mut World) -> Result<(), ImportError> + Send + Sync + 'static,
+  ) -> &mut Self {
+    let importer: Arc<ImportFn> = Arc::new(importer);
+    let mut importers = self.world_mut().get_resource_or_init::<Importers>();
+    for ext in extensions {
+      importers
+        .by_extension
+        .insert(ext.to_lowercase(), importer.clone());
+    }
+    self
+  }
+}
+
+pub struct ImportPlugin;
+
+impl Plugin for ImportPlugin {
+  fn build(&self, app: &mut App) {
+    // built after the user's code, so these must not replace importers it registered
+    let mut importers = app.world_mut().get_resource_or_init::<Importers>();
+    importers.register_default(SCENE_EXTENSIONS, Arc::new(import_scene));
+    importers.register_default(IMAGE_EXTENSIONS, Arc::new(import_image));
+    importers.register_default(GLTF_EXTENSIONS, Arc::new(import_gltf));
+  }
+}
+
+/// Importers keyed by file extension
+#[derive(Resource, Default)]
+pub struct Importers {
+  by_extension: HashMap<String, Arc<ImportFn>>,
+  cursor: EventCursor<FileDragAndDrop>,
+}
+
+impl Importers {
+  /// Registers the importer for the extensions that don't have one yet
+  fn register_default(&mut self, extensions: &[&str], importer: Arc<ImportFn>) {
+    for ext in extensions {
+      self
+        .by_extension
+        .entry(ext.to_lowercase())
+        .or_insert_with(|| importer.clone());
+    }
+  }
+
+  /// The importer for the longest extension the file name ends with, so `scn.ron` wins over `ron`
+  fn importer_for(&self, path: &Path) -> Option<Arc<ImportFn>> {
+    let name = path.file_name()?.to_string_lossy().to_lowercase();
+    name
+      .match_indices('.')
+      .map(|(i, _)| &name[i + 1..])
+      .find_map(|ext| self.by_extension.get(ext).cloned())
+  }
+
+  pub fn on_file_drop(world: &mut World) {
+    let dropped = world.resource_scope(|world, mut importers: Mut<Self>| {
+      importers
+        .cursor
+        .read(world.resource::<Events<FileDragAndDrop>>())
+        .filter_map(|event| match event {
+          FileDragAndDrop::DroppedFile { path_buf, .. } => Some(path_buf.clone()),
+          _ => None,
+        })
+        .collect::<Vec<_>>()
+    });
+
+    // imports copy files into the asset folder and spawn into the scene
+    if !dropped.is_empty() && !ReadOnly::writable(world) {
+      warn!("Ignoring dropped files, the editor is read-only");
+      world.send_event(StatusMessage::warning(
+        "Ignoring dropped files, the editor is read-only",
+      ));
+      return;
+    }
+
+    for path in dropped {
+      let Some(importer) = world.resource::<Self>().importer_for(&path) else {
+        warn!("No importer for dropped file '{}'", path.display());
+        continue;
+      };
+
+      match importer(&path, world) {
+        Ok(()) => {
+          info!("Imported '{}'", path.display());
+          world.send_event(StatusMessage::info(format!("Imported {}", path.display())));
+        }
+        Err(err) => {
+          error!("Failed to import '{}': {err}", path.display());
+          world.send_event(StatusMessage::error(format!(
+            "Failed to import {}: {err}",
+            path.display()
+          )));
+        }
+      }
+    }
+  }
+}
+
+/// Path of the file relative to the asset folder, copying it there first if it lives elsewhere
+pub fn into_assets(path: &Path) -> Result<PathBuf, ImportError> {
+  let asset_dir = util::asset_root();
+
+  if let Ok(relative) = path.strip_prefix(&asset_dir) {
+    return Ok(relative.to_path_buf());
+  }
+
+  let file_name = path
+    .file_name()
+    .ok_or_else(|| ImportError::Other(format!("'{}' is not a file", path.display())))?;
+
+  std::fs::copy(path, asset_dir.join(file_name))?;
+
+  Ok(PathBuf::from(file_name))
+}
+
+fn import_scene(path: &Path, world: &mut World) -> Result<(), ImportError> {
+  let asset_path = into_assets(path)?;
+  world.send_event(LoadEvent::_new(asset_path));
+  Ok(())
+}
+
+fn import_image(path: &Path, _world: &mut World) -> Result<(), ImportError> {
+  into_assets(path)?;
+  Ok(())
+}
+
+fn import_gltf(path: &Path, world: &mut World) -> Result<(), ImportError> {
+  let asset_path = into_assets(path)?;
+  let scene = world
+    .resource::<AssetServer>()
+    .load(GltfAssetLabel::Scene(0).from_asset(asset_path.clone()));
+
+  world.spawn((
+    Name::new(asset_path.display().to_string()),
+    SceneRoot(scene),
+  ));
+
+  Ok(())
+}
@@ -17,13 +17,21 @@ pub enum EditorActions {
   MoveSouth,
   MoveWest,
   MoveEast,
+  Paint,
+  ToggleStats,
 }
 
+type EditorInputs = (
+  &'static mut InputMap<EditorActions>,
+  &'static mut ActionState<EditorActions>,
+);
+
 pub struct InputPlugin;
 
 impl InputPlugin {
-  fn init_input(mut commands: Commands) {
-    let inputs = InputMap::default()
+  /// Every binding, active while editing
+  fn editing_input_map() -> InputMap<EditorActions> {
+    InputMap::default()
       .with(EditorActions::Play, KeyCode::F5)
       .with(EditorActions::OrbitCamera, MouseButton::Right)
       .with(EditorActions::PanCamera, MouseButton::Middle)
@@ -31,13 +39,38 @@ impl InputPlugin {
       .with(EditorActions::MoveNorth, KeyCode::KeyW)
       .with(EditorActions::MoveSouth, KeyCode::KeyS)
       .with(EditorActions::MoveWest, KeyCode::KeyA)
-      .with(EditorActions::MoveEast, KeyCode::KeyD);
+      .with(EditorActions::MoveEast, KeyCode::KeyD)
+      .with(EditorActions::Paint, MouseButton::Left)
+      .with(EditorActions::ToggleStats, KeyCode::F3)
+  }
 
+  /// Only what is needed to get back to editing so the game gets the rest of the input
+  fn testing_input_map() -> InputMap<EditorActions> {
+    InputMap::default().with(EditorActions::Play, KeyCode::F5)
+  }
+
+  fn init_input(mut commands: Commands) {
     commands.spawn((
       Name::new("Editor Input"),
-      InputManagerBundle::with_map(inputs),
+      InputManagerBundle::with_map(Self::editing_input_map()),
     ));
   }
+
+  fn use_editing_inputs(q_inputs: Query<EditorInputs>) {
+    Self::swap_input_map(q_inputs, Self::editing_input_map());
+  }
+
+  fn use_testing_inputs(q_inputs: Query<EditorInputs>) {
+    Self::swap_input_map(q_inputs, Self::testing_input_map());
+  }
+
+  fn swap_input_map(mut q_inputs: Query<EditorInputs>, input_map: InputMap<EditorActions>) {
+    for (mut inputs, mut action_state) in &mut q_inputs {
+      *inputs = input_map.clone();
+      // anything held during the switch would otherwise stay pressed
+      action_state.reset_all();
+    }
+  }
 }
 
 impl Plugin for InputPlugin {
@@ -45,7 +78,9 @@ impl Plugin for InputPlugin {
     app
       .add_plugins(InputManagerPlugin::<EditorActions>::default())
       .register_type::<Box<dyn Buttonlike>>()
-      .add_systems(Startup, Self::init_input);
+      .add_systems(Startup, Self::init_input)
+      .add_systems(OnEnter(EditorState::Editing), Self::use_editing_inputs)
+      .add_systems(OnEnter(EditorState::Testing), Self::use_testing_inputs);
   }
 }
 
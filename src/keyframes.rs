@@ -0,0 +1,336 @@
+use crate::field_path::FieldPath;
+use bevy::{
+  asset::{io::Reader, AssetLoader, LoadContext},
+  prelude::*,
+  utils::HashMap,
+};
+use serde::{Deserialize, Serialize};
+
+/// Keys closer than this in time are the same key
+const KEY_EPSILON: f32 = 1.0 / 240.0;
+
+/// Plays [`KeyframeClip`]s authored in the editor's timeline panel, add it to the game to play
+/// exported clips
+pub struct KeyframePlugin;
+
+impl Plugin for KeyframePlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .init_asset::<KeyframeClip>()
+      .register_asset_loader(KeyframeClipLoader)
+      .register_type::<KeyframePlayer>()
+      .add_systems(Update, KeyframePlayer::animate);
+  }
+}
+
+/// A value of a keyed field
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum KeyValue {
+  Number(f64),
+  Vec3(Vec3),
+  Quat(Quat),
+}
+
+impl KeyValue {
+  /// `None` for values that can't be keyed
+  pub fn read(value: &dyn PartialReflect) -> Option<Self> {
+    if let Some(vec) = value.try_downcast_ref::<Vec3>() {
+      return Some(Self::Vec3(*vec));
+    }
+    if let Some(quat) = value.try_downcast_ref::<Quat>() {
+      return Some(Self::Quat(*quat));
+    }
+    FieldPath::as_f64(value).map(Self::Number)
+  }
+
+  /// Returns whether the field was of the same kind and was written to
+  pub fn write(self, value: &mut dyn PartialReflect) -> bool {
+    match self {
+      Self::Number(number) => FieldPath::set_f64(value, number),
+      Self::Vec3(vec) => value
+        .try_downcast_mut::<Vec3>()
+        .map(|value| *value = vec)
+        .is_some(),
+      Self::Quat(quat) => value
+        .try_downcast_mut::<Quat>()
+        .map(|value| *value = quat)
+        .is_some(),
+    }
+  }
+
+  fn interpolate(self, to: Self, t: f32) -> Self {
+    match (self, to) {
+      (Self::Number(from), Self::Number(to)) => Self::Number(from + (to - from) * t as f64),
+      (Self::Vec3(from), Self::Vec3(to)) => Self::Vec3(from.lerp(to, t)),
+      (Self::Quat(from), Self::Quat(to)) => Self::Quat(from.slerp(to, t)),
+      // a track keeps the kind of its first key, mixed keys step instead
+      _ if t < 1.0 => self,
+      _ => to,
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Keyframe {
+  /// Seconds from the start of the clip
+  pub time: f32,
+  pub value: KeyValue,
+}
+
+/// The keys of a single reflected field
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct KeyframeTrack {
+  /// [`Name`] of the animated entity, the player's entity or one of its descendants
+  pub target: String,
+  /// Short or full type path of the component
+  pub component: String,
+  /// Reflection path into the component, empty for the whole component
+  pub field: String,
+  /// Sorted by time
+  pub keys: Vec<Keyframe>,
+}
+
+impl KeyframeTrack {
+  pub fn new(
+    target: impl Into<String>,
+    component: impl Into<String>,
+    field: impl Into<String>,
+  ) -> Self {
+    Self {
+      target: target.into(),
+      component: component.into(),
+      field: field.into(),
+      keys: Vec::new(),
+    }
+  }
+
+  /// Adds a key, replacing the one already at that time
+  pub fn insert(&mut self, time: f32, value: KeyValue) {
+    match self
+      .keys
+      .iter_mut()
+      .find(|key| (key.time - time).abs() < KEY_EPSILON)
+    {
+      Some(key) => key.value = value,
+      None => {
+        let index = self.keys.partition_point(|key| key.time < time);
+        self.keys.insert(index, Keyframe { time, value });
+      }
+    }
+  }
+
+  /// The value at the time, interpolated between the keys around it
+  pub fn sample(&self, time: f32) -> Option<KeyValue> {
+    let next = self.keys.partition_point(|key| key.time <= time);
+
+    match (
+      next.checked_sub(1).map(|prev| self.keys[prev]),
+      self.keys.get(next).copied(),
+    ) {
+      (Some(prev), Some(next)) => {
+        let span = (next.time - prev.time).max(f32::EPSILON);
+        Some(
+          prev
+            .value
+            .interpolate(next.value, (time - prev.time) / span),
+        )
+      }
+      (Some(key), None) | (None, Some(key)) => Some(key.value),
+      (None, None) => None,
+    }
+  }
+
+  fn path(&self, entity: Entity) -> FieldPath {
+    FieldPath::component(entity, &self.component, &self.field)
+  }
+}
+
+/// Keyframed reflected fields of a group of named entities, saved as `.keyframes.ron`
+#[derive(Asset, TypePath, Clone, Debug, Serialize, Deserialize)]
+pub struct KeyframeClip {
+  /// Seconds
+  pub duration: f32,
+  pub tracks: Vec<KeyframeTrack>,
+}
+
+impl Default for KeyframeClip {
+  fn default() -> Self {
+    Self {
+      duration: 2.0,
+      tracks: Vec::new(),
+    }
+  }
+}
+
+impl KeyframeClip {
+  pub const EXTENSION: &str = "keyframes.ron";
+
+  pub fn track_mut(&mut self, target: &str, component: &str, field: &str) -> &mut KeyframeTrack {
+    let index = self
+      .tracks
+      .iter()
+      .position(|track| {
+        track.target == target && track.component == component && track.field == field
+      })
+      .unwrap_or_else(|| {
+        self
+          .tracks
+          .push(KeyframeTrack::new(target, component, field));
+        self.tracks.len() - 1
+      });
+    &mut self.tracks[index]
+  }
+
+  /// Writes the sampled value of every track to its target, looked up by name in `targets`
+  pub fn apply(&self, time: f32, world: &mut World, targets: &HashMap<String, Entity>) {
+    for track in &self.tracks {
+      let (Some(entity), Some(value)) = (targets.get(&track.target), track.sample(time)) else {
+        continue;
+      };
+
+      let result = track
+        .path(*entity)
+        .with_value(world, |field, _| value.write(field));
+      if let Err(err) = result {
+        warn_once!("Failed to animate {}: {err}", track.path(*entity));
+      }
+    }
+  }
+
+  /// Reads the current value of a field on the entity and keys it
+  pub fn key(
+    &mut self,
+    time: f32,
+    world: &mut World,
+    entity: Entity,
+    target: &str,
+    component: &str,
+    field: &str,
+  ) -> Result<(), String> {
+    let mut value = None;
+    FieldPath::component(entity, component, field)
+      .with_value(world, |field, _| {
+        value = KeyValue::read(field);
+        false
+      })
+      .map_err(|err| err.to_string())?;
+
+    let value =
+      value.ok_or_else(|| String::from("only numbers, Vec3 and Quat fields can be keyed"))?;
+    self.track_mut(target, component, field).insert(time, value);
+    Ok(())
+  }
+}
+
+/// Plays a [`KeyframeClip`] on the entity and its named descendants
+#[derive(Component, Reflect, Clone)]
+#[reflect(Component)]
+pub struct KeyframePlayer {
+  pub clip: Handle<KeyframeClip>,
+  /// Seconds into the clip
+  pub time: f32,
+  pub speed: f32,
+  pub looping: bool,
+  pub playing: bool,
+}
+
+impl KeyframePlayer {
+  pub fn new(clip: Handle<KeyframeClip>) -> Self {
+    Self {
+      clip,
+      time: 0.0,
+      speed: 1.0,
+      looping: false,
+      playing: true,
+    }
+  }
+
+  /// Named entities of the hierarchy below the root, including the root
+  pub fn targets(root: Entity, world: &World) -> HashMap<String, Entity> {
+    let mut targets = HashMap::default();
+    let mut pending = vec![root];
+
+    while let Some(entity) = pending.pop() {
+      if let Some(name) = world.get::<Name>(entity) {
+        targets.entry(name.to_string()).or_insert(entity);
+      }
+      if let Some(children) = world.get::<Children>(entity) {
+        pending.extend(children.iter().copied());
+      }
+    }
+
+    targets
+  }
+
+  fn animate(world: &mut World) {
+    let delta = world.resource::<Time>().delta_secs();
+
+    let mut q_players = world.query::<(Entity, &mut Self)>();
+    let players = q_players
+      .iter_mut(world)
+      .filter(|(_, player)| player.playing)
+      .map(|(entity, mut player)| {
+        player.time += delta * player.speed;
+        (entity, player.clone())
+      })
+      .collect::<Vec<_>>();
+
+    world.resource_scope(|world, clips: Mut<Assets<KeyframeClip>>| {
+      for (entity, player) in players {
+        let Some(clip) = clips.get(&player.clip) else {
+          continue;
+        };
+
+        let mut time = player.time;
+        if time > clip.duration {
+          if player.looping && clip.duration > 0.0 {
+            time %= clip.duration;
+          } else {
+            time = clip.duration;
+          }
+          if let Some(mut player) = world.get_mut::<Self>(entity) {
+            player.time = time;
+            player.playing = player.looping;
+          }
+        }
+
+        let targets = Self::targets(entity, world);
+        clip.apply(time, world, &targets);
+      }
+    });
+  }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum KeyframeClipError {
+  #[error(transparent)]
+  Io(#[from] std::io::Error),
+  #[error(transparent)]
+  Ron(#[from] ron::error::SpannedError),
+}
+
+#[derive(Default)]
+struct KeyframeClipLoader;
+
+impl AssetLoader for KeyframeClipLoader {
+  type Asset = KeyframeClip;
+
+  type Settings = ();
+
+  type Error = KeyframeClipError;
+
+  async fn load(
+    &self,
+    reader: &mut dyn Reader,
+    _settings: &Self::Settings,
+    _load_context: &mut LoadContext<'_>,
+  ) -> Result<Self::Asset, Self::Error> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes).await?;
+    Ok(ron::de::from_bytes(&bytes)?)
+  }
+
+  fn extensions(&self) -> &[&str] {
+    &[KeyframeClip::EXTENSION]
+  }
+}
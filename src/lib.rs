@@ -1,39 +1,107 @@
 pub mod assets;
+mod auto_picking;
 mod cache;
+mod compiler;
+mod docs;
+mod events;
+mod field_path;
+mod field_query;
+#[cfg(feature = "hot_reload")]
+pub mod hot_reload;
+mod importers;
 mod input;
+pub mod keyframes;
+mod prefab_editor;
+mod read_only;
+#[cfg(feature = "remote")]
+pub mod remote;
+mod safe_mode;
+mod scene_prefabs;
 mod scenes;
+mod session;
+mod settings;
+mod startup;
+mod tasks;
+mod testing;
 mod ui;
 mod util;
 mod view;
 
+pub use auto_picking::{AutoPickingSettings, NoAutoPicking};
 pub use bevy_egui;
 pub use bevy_egui::egui;
+pub use events::{
+  EditorDidEnterEditing, EditorDidEnterTesting, EditorDidLoadScene, EditorWillEnterEditing,
+  EditorWillEnterTesting, EditorWillExit, EditorWillLoadScene, EditorWillSaveScene,
+};
+pub use importers::ImportError;
+pub use read_only::ReadOnly;
+pub use safe_mode::{ProjectTrust, SafeMode, SafeModeReason};
 pub use serde;
-pub use ui::{RawUi, Ui};
+pub use tasks::{EditorTasks, TaskHandle};
+pub use testing::{
+  PlayStart, TestMonitor, TestMonitorSettings, TestRecorder, TestRecorderSettings, TestRunConfig,
+  TestRunSettings,
+};
+pub use ui::{
+  curves::{ColorGradient, FloatCurve},
+  enum_editors::{InspectorAppExt, InspectorFlags},
+  menus::{MenuItem, MenuRegistry},
+  misc::{Badge, UiInfo},
+  prebuilt::{
+    game_view::{GameCursor, GameInputSettings},
+    hierarchy::{EditorGroup, HiddenByGroup, InGroup},
+    session_stats::SessionStats,
+  },
+  status_bar::{StatusKind, StatusMessage},
+  RawUi, Ui,
+};
 use util::{LogInfo, LogLevel, LoggingSettings};
 pub use uuid;
 
-use assets::{Prefab, PrefabPlugin, PrefabRegistrar, Prefabs, StaticPrefab};
+use assets::{EditablePrefab, Prefab, PrefabPlugin, PrefabRegistrar, Prefabs, StaticPrefab};
 use bevy::{
-  color::palettes::tailwind::{PINK_100, RED_500},
   diagnostic::{
     EntityCountDiagnosticsPlugin, FrameTimeDiagnosticsPlugin, SystemInformationDiagnosticsPlugin,
   },
+  ecs::component::Components,
   log::{LogPlugin, DEFAULT_FILTER},
   picking::pointer::PointerInteraction,
   prelude::*,
   reflect::GetTypeRegistration,
-  window::{WindowCloseRequested, WindowMode},
+  render::view::RenderLayers,
+  window::{PrimaryWindow, WindowCloseRequested, WindowMode},
 };
 use bevy_egui::EguiContext;
 use bevy_inspector_egui::DefaultInspectorConfigPlugin;
-use cache::Cache;
+use cache::{Cache, CacheRecovery};
+use compiler::SceneCompiler;
+use events::EditorEventsPlugin;
+use importers::{ImportPlugin, ImporterAppExt, Importers};
 use input::InputPlugin;
 use parking_lot::Mutex;
-use scenes::{LoadEvent, SaveEvent, SceneTypeRegistry};
+use prefab_editor::PrefabEditor;
+use safe_mode::TrustPrompt;
+use scene_prefabs::ScenePrefabPlugin;
+pub use scenes::SceneLoadProgress;
+use scenes::{
+  integrity::{ReferenceTargets, SceneIntegritySettings},
+  LoadEvent, SaveEvent, SceneTypeRegistry, StreamingScenes,
+};
+use session::{SessionPlugin, SessionPrompts, SessionSettings};
+use settings::{SettingsAppExt, SettingsRegistry};
+use startup::StartupProgress;
 use std::cell::RefCell;
-use ui::{managers::UiManager, prebuilt::game_view::GameView, UiPlugin};
-use view::EditorViewPlugin;
+use tasks::EditorTasksPlugin;
+use testing::TestingSnapshot;
+use ui::{
+  managers::UiManager,
+  prebuilt::{game_view::GameView, material_editor::MaterialPreview},
+  UiPlugin,
+};
+use view::{
+  outline::SelectionOutline, shading::ShadingProxy, EditorViewPlugin, GizmoColorSettings,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, States)]
 pub enum EditorState {
@@ -47,9 +115,11 @@ pub struct Editor {
   #[deref]
   app: App,
   cache: Cache,
+  cache_recovery: Option<CacheRecovery>,
   scene_type_registry: SceneTypeRegistry,
   prefab_registrar: PrefabRegistrar,
   layout: UiManager,
+  compiler: SceneCompiler,
 }
 
 impl Default for Editor {
@@ -85,12 +155,18 @@ impl Editor {
         }),
     );
 
+    util::init_asset_root(&app);
+
+    let (cache, cache_recovery) = Cache::load_or_recover();
+
     Self {
       app,
-      cache: Cache::load_or_default(),
+      cache,
+      cache_recovery,
       scene_type_registry: default(),
       prefab_registrar: default(),
       layout: default(),
+      compiler: default(),
     }
   }
 
@@ -115,10 +191,35 @@ impl Editor {
     T: Prefab,
   {
     self.register_type::<T>();
+    self.compiler.add_prefab::<T>();
     self.app.add_plugins(PrefabPlugin::<T>::default());
     self
   }
 
+  /// Handle files with any of `extensions` dropped onto the editor window, replacing the built in
+  /// handling of scenes, images and glTF files for those extensions
+  pub fn register_importer(
+    &mut self,
+    extensions: &[&str],
+    importer: impl Fn(&std::path::Path, &mut World) -> Result<(), ImportError> + Send + Sync + 'static,
+  ) -> &mut Self {
+    self.app.register_importer(extensions, importer);
+    self
+  }
+
+  /// Like [`Editor::load_prefabs`], additionally lets the prefab editor save changes back to the
+  /// descriptor files
+  pub fn load_editable_prefabs<T>(&mut self) -> &mut Self
+  where
+    T: EditablePrefab,
+  {
+    self.load_prefabs::<T>();
+    self
+      .app
+      .add_systems(Update, PrefabEditor::register_saver::<T>);
+    self
+  }
+
   pub fn add_game_camera<C>(&mut self) -> &mut Self
   where
     C: Component + Reflect + TypePath,
@@ -127,6 +228,39 @@ impl Editor {
     self.register_ui::<GameView<C>>()
   }
 
+  /// Adds an item to a menu of the menu bar, see [`MenuRegistry`]
+  pub fn add_menu_item(&mut self, menu: &str, item: MenuItem) -> &mut Self {
+    self
+      .app
+      .world_mut()
+      .get_resource_or_init::<MenuRegistry>()
+      .add_item(menu, item);
+    self
+  }
+
+  /// Lists hot reloads and load failures of the game's own asset type in the Asset Reloads panel
+  ///
+  /// Images, meshes, materials, scenes, gltf files, fonts, audio, shaders and animations are
+  /// listed already
+  pub fn track_asset_reloads<A: Asset>(&mut self) -> &mut Self {
+    ui::prebuilt::asset_reloads::track_reloads::<A>(&mut self.app);
+    self
+  }
+
+  /// Watch the game's dynamic library and rerun its registrations whenever it is rebuilt
+  #[cfg(feature = "hot_reload")]
+  pub fn enable_hot_reload(&mut self, path: impl Into<std::path::PathBuf>) -> &mut Self {
+    self.app.add_plugins(hot_reload::HotReloadPlugin::new(path));
+    self
+  }
+
+  /// Serve a JSON api on `127.0.0.1:port` for external tools, see [`remote::RemotePlugin`]
+  #[cfg(feature = "remote")]
+  pub fn enable_remote(&mut self, port: u16) -> &mut Self {
+    self.app.add_plugins(remote::RemotePlugin::new(port));
+    self
+  }
+
   fn register_type<T>(&mut self)
   where
     T: GetTypeRegistration,
@@ -177,15 +311,33 @@ impl Editor {
   #[allow(clippy::type_complexity)]
   fn auto_register_picking_targets(
     mut commands: Commands,
+    settings: Res<AutoPickingSettings>,
+    type_registry: Res<AppTypeRegistry>,
+    components: &Components,
     q_entities: Query<
-      Entity,
+      (EntityRef, Option<&Name>, Option<&RenderLayers>),
       (
         Without<RayCastPickable>,
+        Without<SelectionOutline>,
+        Without<MaterialPreview>,
+        Without<ShadingProxy>,
+        Without<NoAutoPicking>,
         Or<(With<Sprite>, With<Mesh2d>, With<Mesh3d>)>,
       ),
     >,
   ) {
-    for entity in &q_entities {
+    if q_entities.is_empty() {
+      return;
+    }
+
+    let exclusions = settings.exclusions(&type_registry, components);
+
+    for (entity, name, layers) in &q_entities {
+      if exclusions.excludes(entity, name, layers) {
+        continue;
+      }
+
+      let entity = entity.id();
       debug!("Registered Picking: {}", entity);
       commands.entity(entity).insert((
         RayCastPickable,
@@ -200,8 +352,10 @@ impl Editor {
   fn handle_pick_events(
     mut selection: ResMut<ui::InspectorSelection>,
     mut click_events: EventReader<Pointer<Click>>,
-    mut q_egui: Single<&mut EguiContext>,
+    mut q_egui: Single<&mut EguiContext, With<PrimaryWindow>>,
     q_raycast_pickables: Query<&RayCastPickable>,
+    q_members: Query<&InGroup>,
+    q_groups: Query<&EditorGroup>,
   ) {
     let egui_context = q_egui.get_mut();
     let modifiers = egui_context.input(|i| i.modifiers);
@@ -212,52 +366,109 @@ impl Editor {
     {
       let target = click.target;
 
-      if q_raycast_pickables.get(target).is_ok() {
+      if q_raycast_pickables.get(target).is_ok()
+        && !EditorGroup::is_locked(target, &q_members, &q_groups)
+      {
         selection.add_selected(target, modifiers.ctrl);
       }
     }
   }
 
-  fn draw_mesh_intersections(pointers: Query<&PointerInteraction>, mut gizmos: Gizmos) {
+  fn draw_mesh_intersections(
+    pointers: Query<&PointerInteraction>,
+    colors: Res<GizmoColorSettings>,
+    mut gizmos: Gizmos,
+  ) {
     for (point, normal) in pointers
       .iter()
       .filter_map(|interaction| interaction.get_nearest_hit())
       .filter_map(|(_entity, hit)| hit.position.zip(hit.normal))
     {
-      gizmos.sphere(point, 0.05, RED_500);
-      gizmos.arrow(point, point + normal.normalize() * 0.5, PINK_100);
+      gizmos.sphere(point, 0.05, colors.pointer_hit);
+      gizmos.arrow(
+        point,
+        point + normal.normalize() * 0.5,
+        colors.pointer_normal,
+      );
     }
   }
 
   fn on_close_requested(
-    close_requests: EventReader<WindowCloseRequested>,
+    mut close_requests: EventReader<WindowCloseRequested>,
+    q_primary_window: Query<(), With<PrimaryWindow>>,
+    settings: Res<SessionSettings>,
+    mut prompts: ResMut<SessionPrompts>,
     mut next_editor_state: ResMut<NextState<EditorState>>,
   ) {
-    if !close_requests.is_empty() {
-      next_editor_state.set(EditorState::Exiting)
+    // other windows belong to workspaces, those are closed by the ui
+    if close_requests
+      .read()
+      .any(|request| q_primary_window.contains(request.window))
+    {
+      if settings.confirm_exit {
+        prompts.confirm_exit();
+      } else {
+        next_editor_state.set(EditorState::Exiting)
+      }
     }
   }
 
-  fn on_app_exit(cache: ResMut<Cache>, mut app_exit: EventWriter<AppExit>) {
-    cache.save();
+  fn on_app_exit(
+    cache: Res<Cache>,
+    recovery: Option<Res<CacheRecovery>>,
+    mut app_exit: EventWriter<AppExit>,
+  ) {
+    CacheRecovery::save(recovery, &cache);
     app_exit.send(AppExit::Success);
   }
 
+  /// Opens the editor, or validates the project's scenes and prefabs headlessly when launched
+  /// with `--check [asset dir]`
+  ///
+  /// Launching with `--read-only` opens the editor in [`ReadOnly`] mode, and `--safe-mode` in
+  /// [`SafeMode`]
   pub fn launch(self) -> AppExit {
     let Self {
       mut app,
       scene_type_registry,
       prefab_registrar,
       layout,
-      cache,
+      mut cache,
+      cache_recovery,
+      compiler,
     } = self;
 
+    {
+      let mut scene_type_registry = scene_type_registry.write();
+      scene_type_registry.register::<ui::PersistentEntityId>();
+      scene_type_registry.register::<ReferenceTargets>();
+      scene_type_registry.register::<ui::prebuilt::tile_brush::Tile>();
+      scene_type_registry.register::<PlayStart>();
+      scene_type_registry.register::<NoAutoPicking>();
+    }
+
+    if let Some(root) = SceneCompiler::requested() {
+      return compiler.run(&root, &scene_type_registry);
+    }
+
+    let mut safe_mode = SafeMode::requested();
+    safe_mode.strip(&mut cache);
+
+    if let Some(cache_recovery) = cache_recovery {
+      app.insert_resource(cache_recovery);
+    }
+
     app
       .add_plugins((
         EditorViewPlugin,
         MeshPickingPlugin,
         DefaultInspectorConfigPlugin,
         InputPlugin,
+        ImportPlugin,
+        ScenePrefabPlugin,
+        EditorEventsPlugin,
+        EditorTasksPlugin,
+        SessionPlugin,
         UiPlugin(Mutex::new(RefCell::new(Some(layout)))),
         FrameTimeDiagnosticsPlugin,
         EntityCountDiagnosticsPlugin,
@@ -266,9 +477,29 @@ impl Editor {
       .insert_resource(cache)
       .insert_resource(scene_type_registry)
       .insert_resource(prefab_registrar)
+      .insert_resource(ReadOnly::requested().unwrap_or_default())
+      .insert_resource(safe_mode)
+      .init_resource::<TrustPrompt>()
+      .init_resource::<StartupProgress>()
+      .init_resource::<SettingsRegistry>()
+      .init_resource::<PrefabEditor>()
+      .init_resource::<TestRunConfig>()
+      .init_resource::<TestMonitor>()
+      .init_resource::<TestRecorder>()
+      .register_type::<PlayStart>()
+      .register_type::<NoAutoPicking>()
+      .register_type::<ReferenceTargets>()
+      .register_settings::<AutoPickingSettings>()
+      .register_settings::<TestRunSettings>()
+      .register_settings::<TestMonitorSettings>()
+      .register_settings::<TestRecorderSettings>()
+      .register_settings::<SceneIntegritySettings>()
+      .register_settings::<ProjectTrust>()
       .insert_state(EditorState::Editing)
       .add_event::<SaveEvent>()
       .add_event::<LoadEvent>()
+      .add_event::<SceneLoadProgress>()
+      .init_resource::<StreamingScenes>()
       .configure_sets(
         Update,
         (
@@ -286,35 +517,92 @@ impl Editor {
           LoggingSettings::restore,
         ),
       )
-      .add_systems(PostStartup, Self::show_window)
-      .add_systems(OnEnter(EditorState::Editing), Self::show_window_cursor)
+      .add_systems(
+        PostStartup,
+        (Self::show_window, TrustPrompt::open_if_unanswered),
+      )
+      .add_systems(
+        OnEnter(EditorState::Editing),
+        (Self::show_window_cursor, events::on_enter_editing).chain(),
+      )
       .add_systems(
         OnExit(EditorState::Editing),
-        Self::remove_picking_from_targets,
+        (
+          PrefabEditor::on_leave_editing,
+          events::on_leave_editing,
+          Self::remove_picking_from_targets,
+        )
+          .chain(),
+      )
+      .add_systems(
+        OnEnter(EditorState::Testing),
+        (
+          TestingSnapshot::take,
+          TestRunConfig::begin,
+          TestMonitor::reset,
+          TestRecorder::reset,
+          events::on_enter_testing,
+        )
+          .chain(),
+      )
+      .add_systems(
+        OnExit(EditorState::Testing),
+        (
+          events::on_leave_testing,
+          TestRunConfig::end,
+          TestRecorder::end,
+          TestingSnapshot::restore,
+        )
+          .chain(),
       )
       .add_systems(
         Update,
         (
           scenes::check_for_saves,
-          scenes::check_for_loads,
+          (scenes::check_for_loads, StreamingScenes::update).chain(),
           Self::on_close_requested,
           Self::draw_mesh_intersections,
-          Self::auto_register_picking_targets,
+          (
+            Self::remove_picking_from_targets.run_if(resource_changed::<AutoPickingSettings>),
+            Self::auto_register_picking_targets,
+          )
+            .chain(),
           Self::handle_pick_events,
+          Importers::on_file_drop,
         )
           .in_set(Editing),
       )
-      .add_systems(Update, input::global_input_actions.in_set(EditorGlobal))
+      .add_systems(
+        Update,
+        (
+          TestMonitor::sample,
+          (TestRecorder::apply_request, TestRecorder::record).chain(),
+        )
+          .run_if(in_state(EditorState::Testing)),
+      )
+      .add_systems(
+        Update,
+        (
+          input::global_input_actions,
+          StartupProgress::update.run_if(not(StartupProgress::is_finished)),
+          SafeMode::confirm_startup.run_if(StartupProgress::is_finished.and(SafeMode::is_starting)),
+        )
+          .in_set(EditorGlobal),
+      )
       .add_systems(
         OnEnter(EditorState::Exiting),
         (
+          PrefabEditor::on_app_exit,
           (
             view::save_view_state,
             view::view2d::save_settings,
             view::view3d::save_settings,
             UiPlugin::on_app_exit,
             LogInfo::on_app_exit,
+            SettingsRegistry::on_app_exit,
+            session::save_session,
           ),
+          SafeMode::on_app_exit,
           Self::on_app_exit,
         )
           .chain()
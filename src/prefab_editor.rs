@@ -0,0 +1,254 @@
+use crate::{
+  assets::{EditablePrefab, PrefabLoadedEvent, Prefabs},
+  session::UnsavedChanges,
+  testing::EditorOwned,
+  ui::InspectorSelection,
+  util,
+  view::{EditorCamera, UP},
+  EditorState,
+};
+use bevy::{
+  prelude::*,
+  render::mesh::MeshAabb,
+  utils::{HashMap, HashSet},
+};
+use std::path::PathBuf;
+
+/// Direction the camera frames an isolated prefab from, scaled by the size of the prefab
+const FRAME_OFFSET: Vec3 = Vec3::new(0.0, 1.5, 3.0);
+
+#[derive(thiserror::Error, Debug)]
+pub enum PrefabSaveError {
+  #[error("not editing a prefab")]
+  NoSession,
+  #[error("'{0}' can't be saved, load it with `load_editable_prefabs`")]
+  NotEditable(String),
+  #[error("the prefab root no longer has a {0} component")]
+  MissingComponent(&'static str),
+  #[error(transparent)]
+  Serialize(#[from] ron::Error),
+  #[error(transparent)]
+  Io(#[from] std::io::Error),
+}
+
+/// Edits a single prefab in isolation, everything else in the scene is hidden until the session
+/// ends
+#[derive(Resource, Default)]
+pub struct PrefabEditor {
+  session: Option<PrefabEditSession>,
+  savers: HashMap<String, PrefabSaver>,
+}
+
+struct PrefabEditSession {
+  prefab: String,
+  root: Entity,
+  hidden: Vec<(Entity, Visibility)>,
+  cameras: Vec<(Entity, Transform)>,
+}
+
+struct PrefabSaver {
+  /// Path of the descriptor relative to the asset folder
+  path: PathBuf,
+  save: fn(&World, Entity) -> Result<String, PrefabSaveError>,
+}
+
+impl PrefabEditor {
+  pub fn editing(&self) -> Option<&str> {
+    self.session.as_ref().map(|session| session.prefab.as_str())
+  }
+
+  pub(crate) fn root(&self) -> Option<Entity> {
+    self.session.as_ref().map(|session| session.root)
+  }
+
+  pub fn can_save(&self) -> bool {
+    self
+      .session
+      .as_ref()
+      .is_some_and(|session| self.savers.contains_key(&session.prefab))
+  }
+
+  /// Prefabs are only edited while editing, the session ends when testing starts
+  pub fn enter(world: &mut World, prefab: impl Into<String>) {
+    let prefab = prefab.into();
+
+    if *world.resource::<State<EditorState>>().get() != EditorState::Editing {
+      warn!("Not editing prefab '{prefab}', prefabs can only be edited while editing");
+      return;
+    }
+
+    Self::exit(world);
+
+    let Some(root) =
+      world.resource_scope(|world, mut prefabs: Mut<Prefabs>| prefabs.spawn(&prefab, world))
+    else {
+      warn!("No prefab named '{prefab}'");
+      return;
+    };
+
+    let mut q_editor = world.query_filtered::<Entity, EditorOwned>();
+    let editor_entities = q_editor.iter(world).collect::<HashSet<_>>();
+
+    // lights are left alone so the prefab isn't shown in the dark
+    let mut q_roots = world.query_filtered::<(Entity, &mut Visibility), (
+      Without<Parent>,
+      Without<PointLight>,
+      Without<SpotLight>,
+      Without<DirectionalLight>,
+    )>();
+
+    let hidden = q_roots
+      .iter_mut(world)
+      .filter(|(entity, _)| *entity != root && !editor_entities.contains(entity))
+      .map(|(entity, mut visibility)| {
+        let previous = *visibility;
+        *visibility = Visibility::Hidden;
+        (entity, previous)
+      })
+      .collect();
+
+    let cameras = Self::frame(root, world);
+
+    world
+      .resource_mut::<InspectorSelection>()
+      .add_selected(root, false);
+
+    info!("Editing prefab '{prefab}'");
+
+    world.resource_mut::<Self>().session = Some(PrefabEditSession {
+      prefab,
+      root,
+      hidden,
+      cameras,
+    });
+  }
+
+  /// Ends the session, throwing away unsaved changes
+  pub fn exit(world: &mut World) {
+    let Some(session) = world.resource_mut::<Self>().session.take() else {
+      return;
+    };
+
+    if let Ok(root) = world.get_entity_mut(session.root) {
+      root.despawn_recursive();
+    }
+
+    for (entity, visibility) in session.hidden {
+      if let Some(mut current) = world.get_mut::<Visibility>(entity) {
+        *current = visibility;
+      }
+    }
+
+    for (entity, transform) in session.cameras {
+      if let Some(mut current) = world.get_mut::<Transform>(entity) {
+        *current = transform;
+      }
+    }
+
+    info!("Stopped editing prefab '{}'", session.prefab);
+  }
+
+  /// Writes the edited prefab back to its descriptor file
+  pub fn save(world: &mut World) -> Result<PathBuf, PrefabSaveError> {
+    let editor = world.resource::<Self>();
+    let session = editor.session.as_ref().ok_or(PrefabSaveError::NoSession)?;
+    let saver = editor
+      .savers
+      .get(&session.prefab)
+      .ok_or_else(|| PrefabSaveError::NotEditable(session.prefab.clone()))?;
+
+    let serialized = (saver.save)(world, session.root)?;
+
+    let path = util::asset_root().join(&saver.path);
+
+    std::fs::write(&path, serialized)?;
+
+    info!("Saved prefab '{}' to {}", session.prefab, path.display());
+
+    world.resource_mut::<UnsavedChanges>().prefab_saved();
+
+    Ok(path)
+  }
+
+  pub fn on_app_exit(world: &mut World) {
+    Self::exit(world);
+  }
+
+  /// Ends the session before testing so the hidden scene plays as it was
+  pub(crate) fn on_leave_editing(world: &mut World) {
+    Self::exit(world);
+  }
+
+  pub fn register_saver<T: EditablePrefab>(
+    mut event_reader: EventReader<PrefabLoadedEvent<T>>,
+    descriptors: Res<Assets<T::Descriptor>>,
+    assets: Res<AssetServer>,
+    mut editor: ResMut<Self>,
+  ) {
+    for event in event_reader.read() {
+      let Some(desc) = descriptors.get(event.id) else {
+        continue;
+      };
+
+      let Some(path) = assets.get_path(event.id) else {
+        continue;
+      };
+
+      let name = T::transform(desc, &assets).name().to_string();
+
+      editor.savers.insert(
+        name,
+        PrefabSaver {
+          path: path.path().to_path_buf(),
+          save: Self::save_prefab::<T>,
+        },
+      );
+    }
+  }
+
+  fn save_prefab<T: EditablePrefab>(
+    world: &World,
+    root: Entity,
+  ) -> Result<String, PrefabSaveError> {
+    let prefab = world
+      .get::<T>(root)
+      .ok_or(PrefabSaveError::MissingComponent(util::short_name_of::<T>()))?;
+
+    let descriptor = prefab.describe(world.resource::<AssetServer>());
+
+    Ok(ron::ser::to_string_pretty(&descriptor, default())?)
+  }
+
+  /// Points the editor cameras at the prefab, returns where they were before
+  fn frame(root: Entity, world: &mut World) -> Vec<(Entity, Transform)> {
+    let Some(target) = world.get::<Transform>(root).copied() else {
+      return Vec::new();
+    };
+
+    let size = world
+      .get::<Mesh3d>(root)
+      .and_then(|mesh| world.resource::<Assets<Mesh>>().get(mesh))
+      .and_then(|mesh| mesh.compute_aabb())
+      .map(|aabb| (Vec3::from(aabb.half_extents) * target.scale).length())
+      .unwrap_or(1.0);
+
+    let mut q_cams =
+      world.query_filtered::<(Entity, &mut Transform, Has<Camera3d>), With<EditorCamera>>();
+
+    q_cams
+      .iter_mut(world)
+      .map(|(entity, mut transform, is_3d)| {
+        let previous = *transform;
+
+        if is_3d {
+          *transform = Transform::from_translation(target.translation + FRAME_OFFSET * size)
+            .looking_at(target.translation, UP);
+        } else {
+          transform.translation = target.translation.with_z(transform.translation.z);
+        }
+
+        (entity, previous)
+      })
+      .collect()
+  }
+}
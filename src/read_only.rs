@@ -0,0 +1,47 @@
+use bevy::prelude::*;
+
+const FLAG: &str = "--read-only";
+
+/// Shown when hovering controls that are disabled by [`ReadOnly`]
+pub const REASON: &str = "The editor is in read-only mode, turn it off in Tools to make changes";
+
+/// While enabled the scene can be inspected, navigated and selected but not changed
+///
+/// Component edits, spawning, deleting, imports and saves are all disabled. Starts enabled when
+/// the editor is launched with `--read-only`
+#[derive(Resource, Default)]
+pub struct ReadOnly {
+  enabled: bool,
+}
+
+impl ReadOnly {
+  /// Whether the editor was launched with `--read-only`
+  pub fn requested() -> Option<Self> {
+    std::env::args()
+      .any(|arg| arg == FLAG)
+      .then_some(Self { enabled: true })
+  }
+
+  pub fn enabled(&self) -> bool {
+    self.enabled
+  }
+
+  pub fn set_enabled(&mut self, enabled: bool) {
+    if self.enabled != enabled {
+      info!(
+        "Read-only mode {}",
+        if enabled { "enabled" } else { "disabled" }
+      );
+    }
+    self.enabled = enabled;
+  }
+
+  /// Run condition for systems that change the scene
+  pub fn is_writable(read_only: Res<Self>) -> bool {
+    !read_only.enabled
+  }
+
+  pub(crate) fn writable(world: &World) -> bool {
+    !world.resource::<Self>().enabled
+  }
+}
@@ -0,0 +1,396 @@
+use crate::{
+  assets::Prefabs,
+  read_only::ReadOnly,
+  scenes::{LoadEvent, SaveEvent},
+  testing::EditorOwned,
+  ui::InspectorSelection,
+  util,
+};
+use bevy::{prelude::*, utils::HashSet};
+use parking_lot::Mutex;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{
+  io::{BufRead, BufReader, Read, Write},
+  net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream},
+  path::{Component, PathBuf},
+  sync::mpsc::{self, Receiver, Sender},
+  time::Duration,
+};
+
+/// How long a connection waits for the editor to answer before giving up
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long a client has to send its request, connections are served one at a time
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+
+const MAX_BODY_LEN: usize = 64 * 1024;
+
+/// Serves a small JSON api on localhost so external tools can drive the editor
+///
+/// | Method | Path | Body | Result |
+/// | --- | --- | --- | --- |
+/// | `GET` | `/selection` | | Selected entities |
+/// | `GET` | `/entities?component=Name` | | Entities with the component, by short or full type path |
+/// | `GET` | `/prefabs` | | Names of the registered prefabs |
+/// | `POST` | `/prefabs/spawn` | `{ "prefab": "Name" }` | The spawned entity |
+/// | `POST` | `/scene/save` | `{ "file": "path" }` | Queues saving the scene |
+/// | `POST` | `/scene/load` | `{ "file": "path" }` | Queues loading the scene |
+///
+/// Entities are identified by the bits of their [`Entity`]. Scene files are relative to the asset
+/// folder and can't leave it. Requests that change the scene fail while the editor is
+/// [`ReadOnly`]
+///
+/// Only requests addressed to localhost without an `Origin` header are answered, so web pages open
+/// in a browser can't reach the editor
+pub struct RemotePlugin {
+  port: u16,
+}
+
+impl RemotePlugin {
+  pub fn new(port: u16) -> Self {
+    Self { port }
+  }
+}
+
+impl Plugin for RemotePlugin {
+  fn build(&self, app: &mut App) {
+    let (sender, receiver) = mpsc::channel();
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, self.port));
+
+    std::thread::Builder::new()
+      .name(String::from("editor remote"))
+      .spawn(move || listen(address, sender))
+      .expect("failed to spawn the remote control thread");
+
+    app
+      .insert_resource(RemoteRequests(Mutex::new(receiver)))
+      .add_systems(Update, RemoteRequests::handle);
+  }
+}
+
+#[derive(thiserror::Error, Debug)]
+enum RemoteError {
+  #[error("not found")]
+  NotFound,
+  #[error("{0}")]
+  BadRequest(String),
+  #[error("{}", crate::read_only::REASON)]
+  ReadOnly,
+  #[error("{0}")]
+  Forbidden(&'static str),
+  #[error("the body is larger than {MAX_BODY_LEN} bytes")]
+  TooLarge,
+  #[error("the editor did not respond")]
+  Unavailable,
+}
+
+impl RemoteError {
+  fn status(&self) -> &'static str {
+    match self {
+      Self::NotFound => "404 Not Found",
+      Self::BadRequest(_) => "400 Bad Request",
+      Self::ReadOnly | Self::Forbidden(_) => "403 Forbidden",
+      Self::TooLarge => "413 Payload Too Large",
+      Self::Unavailable => "503 Service Unavailable",
+    }
+  }
+}
+
+type RemoteResult = Result<Value, RemoteError>;
+
+enum RemoteRequest {
+  Selection,
+  Entities { component: String },
+  Prefabs,
+  SpawnPrefab { prefab: String },
+  SaveScene { file: PathBuf },
+  LoadScene { file: PathBuf },
+}
+
+#[derive(Deserialize)]
+struct PrefabBody {
+  prefab: String,
+}
+
+#[derive(Deserialize)]
+struct SceneBody {
+  file: PathBuf,
+}
+
+impl RemoteRequest {
+  fn parse(method: &str, target: &str, body: &[u8]) -> Result<Self, RemoteError> {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    match (method, path) {
+      ("GET", "/selection") => Ok(Self::Selection),
+      ("GET", "/entities") => query
+        .split('&')
+        .find_map(|param| param.strip_prefix("component="))
+        .map(|component| Self::Entities {
+          component: component.to_string(),
+        })
+        .ok_or_else(|| RemoteError::BadRequest(String::from("missing the component parameter"))),
+      ("GET", "/prefabs") => Ok(Self::Prefabs),
+      ("POST", "/prefabs/spawn") => {
+        let PrefabBody { prefab } = parse_body(body)?;
+        Ok(Self::SpawnPrefab { prefab })
+      }
+      ("POST", "/scene/save") => {
+        let SceneBody { file } = parse_body(body)?;
+        Ok(Self::SaveScene {
+          file: asset_file(file)?,
+        })
+      }
+      ("POST", "/scene/load") => {
+        let SceneBody { file } = parse_body(body)?;
+        Ok(Self::LoadScene {
+          file: asset_file(file)?,
+        })
+      }
+      _ => Err(RemoteError::NotFound),
+    }
+  }
+
+  fn handle(self, world: &mut World) -> RemoteResult {
+    match self {
+      Self::Selection => {
+        let entities = match world.resource::<InspectorSelection>() {
+          InspectorSelection::Entities(selected) => selected.iter().collect(),
+          _ => Vec::new(),
+        };
+        Ok(entities_json(world, entities))
+      }
+      Self::Entities { component } => {
+        let type_id = {
+          let type_registry = world.resource::<AppTypeRegistry>().read();
+          type_registry
+            .get_with_short_type_path(&component)
+            .or_else(|| type_registry.get_with_type_path(&component))
+            .map(|registration| registration.type_id())
+            .ok_or_else(|| RemoteError::BadRequest(format!("unknown component '{component}'")))?
+        };
+
+        let Some(component_id) = world.components().get_id(type_id) else {
+          return Ok(entities_json(world, Vec::new()));
+        };
+
+        let editor_owned = world
+          .query_filtered::<Entity, EditorOwned>()
+          .iter(world)
+          .collect::<HashSet<_>>();
+
+        let entities = world
+          .iter_entities()
+          .filter(|entity| entity.contains_id(component_id))
+          .map(|entity| entity.id())
+          .filter(|entity| !editor_owned.contains(entity))
+          .collect();
+
+        Ok(entities_json(world, entities))
+      }
+      Self::Prefabs => {
+        let mut prefabs = world
+          .get_resource::<Prefabs>()
+          .map(|prefabs| prefabs.keys().cloned().collect::<Vec<_>>())
+          .unwrap_or_default();
+        prefabs.sort();
+        Ok(json!({ "prefabs": prefabs }))
+      }
+      Self::SpawnPrefab { prefab } => {
+        writable(world)?;
+        let entity = world
+          .resource_scope(|world, mut prefabs: Mut<Prefabs>| prefabs.spawn(&prefab, world))
+          .ok_or_else(|| RemoteError::BadRequest(format!("unknown prefab '{prefab}'")))?;
+        Ok(json!({ "entity": entity.to_bits() }))
+      }
+      Self::SaveScene { file } => {
+        writable(world)?;
+        world.send_event(SaveEvent::_new(util::asset_root().join(&file)));
+        Ok(json!({ "file": file }))
+      }
+      Self::LoadScene { file } => {
+        writable(world)?;
+        world.send_event(LoadEvent::_new(file.clone()));
+        Ok(json!({ "file": file }))
+      }
+    }
+  }
+}
+
+fn parse_body<T: for<'de> Deserialize<'de>>(body: &[u8]) -> Result<T, RemoteError> {
+  serde_json::from_slice(body).map_err(|err| RemoteError::BadRequest(err.to_string()))
+}
+
+/// Scene files are given relative to the asset folder and must stay inside it
+fn asset_file(file: PathBuf) -> Result<PathBuf, RemoteError> {
+  let inside = file.components().next().is_some()
+    && file
+      .components()
+      .all(|component| matches!(component, Component::Normal(_)));
+
+  if inside {
+    Ok(file)
+  } else {
+    Err(RemoteError::BadRequest(format!(
+      "'{}' is not a path inside the asset folder",
+      file.display()
+    )))
+  }
+}
+
+fn writable(world: &World) -> Result<(), RemoteError> {
+  if ReadOnly::writable(world) {
+    Ok(())
+  } else {
+    Err(RemoteError::ReadOnly)
+  }
+}
+
+fn entities_json(world: &World, entities: Vec<Entity>) -> Value {
+  let entities = entities
+    .into_iter()
+    .map(|entity| {
+      let name = world.get::<Name>(entity).map(Name::as_str);
+      json!({ "entity": entity.to_bits(), "name": name })
+    })
+    .collect::<Vec<_>>();
+
+  json!({ "entities": entities })
+}
+
+/// A parsed request waiting for the editor to answer it
+struct Pending {
+  request: RemoteRequest,
+  reply: Sender<RemoteResult>,
+}
+
+#[derive(Resource)]
+struct RemoteRequests(Mutex<Receiver<Pending>>);
+
+impl RemoteRequests {
+  fn handle(world: &mut World) {
+    let pending = world
+      .resource::<Self>()
+      .0
+      .lock()
+      .try_iter()
+      .collect::<Vec<_>>();
+
+    for Pending { request, reply } in pending {
+      // the connection may have timed out in the meantime
+      let _ = reply.send(request.handle(world));
+    }
+  }
+}
+
+fn listen(address: SocketAddr, sender: Sender<Pending>) {
+  let listener = match TcpListener::bind(address) {
+    Ok(listener) => listener,
+    Err(err) => {
+      error!("Failed to start remote control on {address}: {err}");
+      return;
+    }
+  };
+
+  info!("Remote control listening on http://{address}");
+
+  for stream in listener.incoming() {
+    let result = stream.and_then(|stream| serve(stream, address, &sender));
+    if let Err(err) = result {
+      warn!("Remote control connection failed: {err}");
+    }
+  }
+}
+
+fn serve(
+  mut stream: TcpStream,
+  address: SocketAddr,
+  sender: &Sender<Pending>,
+) -> std::io::Result<()> {
+  stream.set_read_timeout(Some(READ_TIMEOUT))?;
+
+  let result = read_request(&stream, address).and_then(|request| {
+    let (reply, response) = mpsc::channel();
+    sender
+      .send(Pending { request, reply })
+      .map_err(|_| RemoteError::Unavailable)?;
+    response
+      .recv_timeout(REPLY_TIMEOUT)
+      .map_err(|_| RemoteError::Unavailable)?
+  });
+
+  let (status, body) = match result {
+    Ok(value) => ("200 OK", value),
+    Err(err) => (err.status(), json!({ "error": err.to_string() })),
+  };
+
+  let body = body.to_string();
+  write!(
+    stream,
+    "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+    body.len()
+  )?;
+  stream.flush()
+}
+
+fn read_request(stream: &TcpStream, address: SocketAddr) -> Result<RemoteRequest, RemoteError> {
+  let bad_request = |err: std::io::Error| RemoteError::BadRequest(err.to_string());
+  let mut reader = BufReader::new(stream);
+
+  let mut request_line = String::new();
+  reader.read_line(&mut request_line).map_err(bad_request)?;
+  let mut parts = request_line.split_whitespace();
+  let method = parts.next().unwrap_or_default().to_string();
+  let target = parts.next().unwrap_or_default().to_string();
+
+  let port = address.port();
+  let allowed_hosts = [format!("127.0.0.1:{port}"), format!("localhost:{port}")];
+
+  let mut content_length = 0;
+  let mut local_host = false;
+  let mut has_origin = false;
+  loop {
+    let mut header = String::new();
+    reader.read_line(&mut header).map_err(bad_request)?;
+    let header = header.trim();
+    if header.is_empty() {
+      break;
+    }
+
+    let Some((name, value)) = header.split_once(':') else {
+      continue;
+    };
+    let value = value.trim();
+
+    if name.eq_ignore_ascii_case("content-length") {
+      content_length = value.parse().unwrap_or_default();
+    } else if name.eq_ignore_ascii_case("host") {
+      // a page rebinding its own domain to localhost still sends its domain as the host
+      local_host = allowed_hosts
+        .iter()
+        .any(|host| host.eq_ignore_ascii_case(value));
+    } else if name.eq_ignore_ascii_case("origin") {
+      has_origin = true;
+    }
+  }
+
+  if has_origin {
+    return Err(RemoteError::Forbidden(
+      "requests from web pages are not accepted",
+    ));
+  }
+
+  if !local_host {
+    return Err(RemoteError::Forbidden("the host must be localhost"));
+  }
+
+  if content_length > MAX_BODY_LEN {
+    return Err(RemoteError::TooLarge);
+  }
+
+  let mut body = vec![0; content_length];
+  reader.read_exact(&mut body).map_err(bad_request)?;
+
+  RemoteRequest::parse(&method, &target, &body)
+}
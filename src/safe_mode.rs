@@ -0,0 +1,220 @@
+use crate::{
+  cache::{self, Cache, Saveable},
+  session::LastSession,
+  settings::EditorSettings,
+  ui::{selection::SavedSelection, LayoutState},
+  view::{self, ActiveEditorCamera},
+};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+const FLAG: &str = "--safe-mode";
+
+/// Frames the editor has to keep running after startup finishes before the launch counts as
+/// successful
+const STABLE_FRAMES: u32 = 120;
+
+fn marker_path() -> PathBuf {
+  const FILE: &str = concat!(env!("CARGO_PKG_NAME"), ".starting");
+  cache::cache_dir().join(FILE)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SafeModeReason {
+  /// Launched with `--safe-mode`
+  Requested,
+  /// The previous launch never finished starting up
+  Crashed,
+}
+
+/// Starts the editor without restoring the saved layout, editor cameras, selection or last scene,
+/// and without running any of the project's extensions
+///
+/// Enabled with `--safe-mode`, or automatically when the previous launch exited before it finished
+/// starting up. Nothing is lost, whatever was skipped is written back to the cache when the editor
+/// exits so the next normal launch restores it
+#[derive(Resource, Default)]
+pub struct SafeMode {
+  reason: Option<SafeModeReason>,
+  /// Left behind by launches that have not yet finished starting up
+  marker: Option<PathBuf>,
+  prompt_open: bool,
+  /// Cached values that weren't restored, by key
+  skipped: Vec<(&'static str, serde_json::Value)>,
+}
+
+impl SafeMode {
+  /// Checks the command line and the previous launch, then marks this launch as starting up
+  pub(crate) fn requested() -> Self {
+    let marker = marker_path();
+
+    let reason = if std::env::args().any(|arg| arg == FLAG) {
+      Some(SafeModeReason::Requested)
+    } else if marker.exists() {
+      Some(SafeModeReason::Crashed)
+    } else {
+      None
+    };
+
+    if let Some(reason) = reason {
+      warn!("Starting in safe mode: {reason:?}");
+    }
+
+    if let Err(err) = std::fs::write(&marker, []) {
+      warn!("Failed to write {}: {err}", marker.display());
+    }
+
+    Self {
+      reason,
+      marker: Some(marker),
+      prompt_open: reason.is_some(),
+      skipped: Vec::new(),
+    }
+  }
+
+  pub fn enabled(&self) -> bool {
+    self.reason.is_some()
+  }
+
+  pub fn reason(&self) -> Option<SafeModeReason> {
+    self.reason
+  }
+
+  pub(crate) fn enabled_in(world: &World) -> bool {
+    world
+      .get_resource::<Self>()
+      .is_some_and(|safe_mode| safe_mode.enabled())
+  }
+
+  pub(crate) fn prompt_open(&self) -> bool {
+    self.prompt_open
+  }
+
+  pub(crate) fn close_prompt(&mut self) {
+    self.prompt_open = false;
+  }
+
+  /// Sets aside what safe mode skips from the cache before anything reads it
+  pub(crate) fn strip(&mut self, cache: &mut Cache) {
+    if !self.enabled() {
+      return;
+    }
+
+    self.skip::<ActiveEditorCamera>(cache.remove::<ActiveEditorCamera>());
+    self.skip::<view::view2d::CameraSaveData>(cache.remove::<view::view2d::CameraSaveData>());
+    self.skip::<view::view3d::CameraSaveData>(cache.remove::<view::view3d::CameraSaveData>());
+    self.skip::<SavedSelection>(cache.remove::<SavedSelection>());
+    self.skip::<LastSession>(cache.remove::<LastSession>());
+    // the ui still reads the named layouts from it, only the dock is left unrestored
+    self.skip::<LayoutState>(cache.get_raw::<LayoutState>());
+  }
+
+  fn skip<S: Saveable>(&mut self, value: Option<serde_json::Value>) {
+    if let Some(value) = value {
+      self.skipped.push((S::KEY, value));
+    }
+  }
+
+  pub(crate) fn is_starting(safe_mode: Res<Self>) -> bool {
+    safe_mode.marker.is_some()
+  }
+
+  /// Removes the marker once the editor has been running for a while after startup
+  pub(crate) fn confirm_startup(mut safe_mode: ResMut<Self>, mut frames: Local<u32>) {
+    *frames += 1;
+    if *frames >= STABLE_FRAMES {
+      safe_mode.finish_startup();
+    }
+  }
+
+  /// Runs on exit after everything else is stored, so the skipped values replace what this
+  /// session would have saved in their place. An editor closed before startup settled did not
+  /// crash either
+  pub(crate) fn on_app_exit(mut safe_mode: ResMut<Self>, mut cache: ResMut<Cache>) {
+    for (key, value) in safe_mode.skipped.drain(..) {
+      cache.insert_raw(key, value);
+    }
+    safe_mode.finish_startup();
+  }
+
+  fn finish_startup(&mut self) {
+    if let Some(marker) = self.marker.take() {
+      if let Err(err) = std::fs::remove_file(&marker) {
+        warn!("Failed to remove {}: {err}", marker.display());
+      }
+    }
+  }
+}
+
+/// Whether the project's extensions, such as a hot reloaded game library, may run
+///
+/// Asked once per project the first time an extension wants to run
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone, Default)]
+#[reflect(Resource)]
+pub struct ProjectTrust {
+  pub trusted: bool,
+  /// Turn off to be asked again on the next launch
+  pub answered: bool,
+}
+
+impl Saveable for ProjectTrust {
+  const KEY: &str = "project_trust";
+}
+
+impl EditorSettings for ProjectTrust {
+  const NAME: &str = "Project Trust";
+}
+
+impl ProjectTrust {
+  /// Adds a reason to trust the project to the prompt, call while building the app
+  pub fn request(app: &mut App, reason: impl Into<String>) {
+    app
+      .world_mut()
+      .get_resource_or_init::<TrustPrompt>()
+      .reasons
+      .push(reason.into());
+  }
+
+  /// Run condition for systems that run code from the project
+  pub fn allows_extensions(trust: Res<Self>, safe_mode: Res<SafeMode>) -> bool {
+    trust.trusted && !safe_mode.enabled()
+  }
+}
+
+/// The question asked by [`ProjectTrust`], with what trusting the project would run
+#[derive(Resource, Default)]
+pub(crate) struct TrustPrompt {
+  reasons: Vec<String>,
+  open: bool,
+}
+
+impl TrustPrompt {
+  pub(crate) fn reasons(&self) -> &[String] {
+    &self.reasons
+  }
+
+  pub(crate) fn is_open(&self) -> bool {
+    self.open
+  }
+
+  /// Settings are restored on startup, so this waits until after to know whether to ask
+  pub(crate) fn open_if_unanswered(
+    mut prompt: ResMut<Self>,
+    trust: Res<ProjectTrust>,
+    safe_mode: Res<SafeMode>,
+  ) {
+    prompt.open = !prompt.reasons.is_empty() && !trust.answered && !safe_mode.enabled();
+  }
+
+  pub(crate) fn answer(world: &mut World, trusted: bool) {
+    info!(
+      "Project {}",
+      if trusted { "trusted" } else { "not trusted" }
+    );
+    world.resource_mut::<Self>().open = false;
+    let mut trust = world.resource_mut::<ProjectTrust>();
+    trust.trusted = trusted;
+    trust.answered = true;
+  }
+}
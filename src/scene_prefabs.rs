@@ -0,0 +1,226 @@
+use crate::{
+  assets::Prefabs, scenes::SceneTypeRegistry, startup::StartupProgress, ui::PersistentEntityId,
+  util,
+};
+use bevy::{asset::LoadedFolder, prelude::*, scene::SceneFilter};
+use std::any::TypeId;
+
+/// Folder in the asset directory that prefabs converted from the scene are written to
+const DIR: &str = "prefabs";
+const EXTENSION: &str = "scn.ron";
+
+#[derive(thiserror::Error, Debug)]
+pub enum ConvertError {
+  #[error("'{0}' is not a valid prefab name, use letters, numbers, '-' and '_'")]
+  InvalidName(String),
+  #[error("a prefab named '{0}' already exists")]
+  Exists(String),
+  #[error("{0} no longer exists")]
+  MissingEntity(Entity),
+  #[error(transparent)]
+  Serialize(#[from] ron::Error),
+  #[error(transparent)]
+  Io(#[from] std::io::Error),
+}
+
+/// Prefabs stored as scenes, made with [`convert_to_prefab`]
+pub struct ScenePrefabPlugin;
+
+impl Plugin for ScenePrefabPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .init_resource::<ScenePrefabFolder>()
+      .add_systems(Startup, Self::load_folder)
+      .add_systems(Update, Self::on_folder_loaded);
+  }
+}
+
+#[derive(Resource, Default)]
+struct ScenePrefabFolder(Option<Handle<LoadedFolder>>);
+
+impl ScenePrefabPlugin {
+  fn load_folder(
+    assets: Res<AssetServer>,
+    mut folder: ResMut<ScenePrefabFolder>,
+    mut startup_progress: ResMut<StartupProgress>,
+  ) {
+    // nothing has been converted yet
+    if !prefab_dir().exists() {
+      return;
+    }
+
+    let handle = assets.load_folder(DIR);
+    startup_progress.track(format!("Scene prefabs ({DIR})"), handle.clone());
+    folder.0 = Some(handle);
+  }
+
+  fn on_folder_loaded(
+    mut event_reader: EventReader<AssetEvent<LoadedFolder>>,
+    folder: Res<ScenePrefabFolder>,
+    loaded_folders: Res<Assets<LoadedFolder>>,
+    assets: Res<AssetServer>,
+    mut prefabs: ResMut<Prefabs>,
+  ) {
+    let Some(handle) = folder.0.as_ref() else {
+      return;
+    };
+
+    for event in event_reader.read() {
+      if !event.is_loaded_with_dependencies(handle) {
+        continue;
+      }
+
+      let Some(loaded) = loaded_folders.get(handle) else {
+        continue;
+      };
+
+      for handle in &loaded.handles {
+        let Ok(scene) = handle.clone().try_typed::<DynamicScene>() else {
+          continue;
+        };
+
+        if let Some(name) = assets.get_path(&scene).as_ref().and_then(name_of) {
+          register(&mut prefabs, name, scene);
+        }
+      }
+    }
+  }
+}
+
+fn prefab_dir() -> std::path::PathBuf {
+  util::asset_root().join(DIR)
+}
+
+fn name_of(path: &bevy::asset::AssetPath) -> Option<String> {
+  let file_name = path.path().file_name()?.to_str()?;
+  file_name
+    .strip_suffix(EXTENSION)
+    .and_then(|name| name.strip_suffix('.'))
+    .map(str::to_string)
+}
+
+fn register(prefabs: &mut Prefabs, name: String, scene: Handle<DynamicScene>) {
+  info!("Registered scene prefab '{name}'");
+  let instance_name = name.clone();
+  prefabs.insert(
+    name,
+    Box::new(move |world: &mut World| {
+      world
+        .spawn((
+          Name::new(instance_name.clone()),
+          DynamicSceneRoot(scene.clone()),
+        ))
+        .id()
+    }),
+  );
+}
+
+/// Entity and every one of its descendants
+fn subtree(root: Entity, world: &World) -> Vec<Entity> {
+  let mut entities = vec![root];
+  let mut i = 0;
+  while let Some(entity) = entities.get(i).copied() {
+    if let Some(children) = world.get::<Children>(entity) {
+      entities.extend(children.iter().copied());
+    }
+    i += 1;
+  }
+  entities
+}
+
+/// Writes the entity and its children into a new scene prefab, then replaces them with an instance
+/// of it
+///
+/// Only components of types registered for scenes are kept. Returns the new instance
+pub fn convert_to_prefab(
+  root: Entity,
+  name: &str,
+  world: &mut World,
+) -> Result<Entity, ConvertError> {
+  let valid_name = !name.is_empty()
+    && name
+      .chars()
+      .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+  if !valid_name {
+    return Err(ConvertError::InvalidName(name.to_string()));
+  }
+
+  let path = prefab_dir().join(format!("{name}.{EXTENSION}"));
+  if world.resource::<Prefabs>().contains_key(name) || path.exists() {
+    return Err(ConvertError::Exists(name.to_string()));
+  }
+
+  if world.get_entity(root).is_err() {
+    return Err(ConvertError::MissingEntity(root));
+  }
+
+  let filter = world
+    .resource::<SceneTypeRegistry>()
+    .read()
+    .iter()
+    .map(|registration| registration.type_id())
+    .filter(|type_id| *type_id != TypeId::of::<PersistentEntityId>())
+    .fold(
+      SceneFilter::deny_all()
+        .allow::<Parent>()
+        .allow::<Children>(),
+      SceneFilter::allow_by_id,
+    );
+
+  let mut scene = DynamicSceneBuilder::from_world(world)
+    .with_component_filter(filter)
+    .extract_entities(subtree(root, world).into_iter())
+    .build();
+
+  // the root is placed wherever the instance is spawned and is no longer attached to its parent
+  if let Some(root) = scene
+    .entities
+    .iter_mut()
+    .find(|entity| entity.entity == root)
+  {
+    root.components.retain(|component| {
+      component
+        .get_represented_type_info()
+        .is_none_or(|info| info.type_id() != TypeId::of::<Parent>())
+    });
+    for component in &mut root.components {
+      if component
+        .get_represented_type_info()
+        .is_some_and(|info| info.type_id() == TypeId::of::<Transform>())
+      {
+        *component = Box::new(Transform::IDENTITY);
+      }
+    }
+  }
+
+  let serialized = scene.serialize(&world.resource::<AppTypeRegistry>().read())?;
+
+  std::fs::create_dir_all(prefab_dir())?;
+  std::fs::write(&path, serialized)?;
+  info!("Wrote prefab '{name}' to {}", path.display());
+
+  let scene = world
+    .resource::<AssetServer>()
+    .load(format!("{DIR}/{name}.{EXTENSION}"));
+  register(
+    &mut world.resource_mut::<Prefabs>(),
+    name.to_string(),
+    scene,
+  );
+
+  let transform = world.get::<Transform>(root).copied().unwrap_or_default();
+  let parent = world.get::<Parent>(root).map(Parent::get);
+
+  let instance = world
+    .resource_scope(|world, mut prefabs: Mut<Prefabs>| prefabs.spawn(name, world))
+    .ok_or(ConvertError::MissingEntity(root))?;
+
+  world.entity_mut(root).despawn_recursive();
+
+  world.entity_mut(instance).insert(transform);
+  if let Some(parent) = parent {
+    world.entity_mut(parent).add_child(instance);
+  }
+
+  Ok(instance)
+}
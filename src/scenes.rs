@@ -1,6 +1,25 @@
-use bevy::{asset::ReflectHandle, prelude::*, reflect::TypeRegistryArc, tasks::IoTaskPool};
+use crate::{
+  events::{self, EditorDidLoadScene, EditorWillLoadScene, EditorWillSaveScene},
+  read_only::ReadOnly,
+  tasks::EditorTasks,
+  ui::{component_toggle::DisabledComponents, status_bar::StatusMessage},
+  view::EditorCamera,
+};
+use bevy::{
+  asset::ReflectHandle,
+  ecs::{entity::EntityHashMap, event::EventCursor},
+  prelude::*,
+  reflect::{FromReflect, TypeRegistryArc},
+  scene::DynamicEntity,
+};
+use integrity::SceneIntegritySettings;
 use std::path::PathBuf;
 
+pub mod integrity;
+
+/// How many entities of a loading scene get spawned each frame
+const ENTITIES_PER_FRAME: usize = 256;
+
 #[derive(Event)]
 pub struct SaveEvent(PathBuf);
 
@@ -24,9 +43,18 @@ impl SaveEvent {
     let scene_type_registry = world.resource::<SceneTypeRegistry>().clone();
     let scene_type_registry = scene_type_registry.read();
 
+    let settings = world.resource::<SceneIntegritySettings>().clone();
+    if settings.remap_by_persistent_id {
+      let mut q_scene = world.query_filtered::<Entity, With<SceneMarker>>();
+      let entities = q_scene.iter(world).collect::<Vec<_>>();
+      integrity::assign_ids(world, entities);
+    }
+
     let scene_marker_id = world.component_id::<SceneMarker>().unwrap();
     let components = world.components();
 
+    let mut saved = EntityHashMap::default();
+
     for archetype in world
       .archetypes()
       .iter()
@@ -34,6 +62,7 @@ impl SaveEvent {
     {
       for entity in archetype.entities() {
         let new_entity_id = scene_world.spawn_empty().id();
+        saved.insert(entity.id(), new_entity_id);
 
         for comp_id in archetype.components() {
           let Some(comp_info) = components.get_info(comp_id) else {
@@ -81,6 +110,33 @@ impl SaveEvent {
             );
           }
         }
+
+        // components turned off in the inspector are still part of the scene
+        if let Some(disabled) = world.get::<DisabledComponents>(entity.id()) {
+          disabled.apply_to(
+            &mut scene_world.entity_mut(new_entity_id),
+            &world_type_registry,
+            |type_id| scene_type_registry.contains(type_id),
+          );
+        }
+      }
+    }
+
+    let broken = integrity::check(
+      world,
+      &mut scene_world,
+      &saved,
+      &world_type_registry,
+      &settings,
+    );
+
+    if !broken.is_empty() {
+      warn!(
+        "{} entity references point outside of the saved scene:",
+        broken.len()
+      );
+      for reference in &broken {
+        warn!("  {reference}");
       }
     }
 
@@ -88,8 +144,19 @@ impl SaveEvent {
 
     let serialization = scene.serialize(&scene_type_registry).unwrap();
     let filename = self.file().clone();
-    IoTaskPool::get()
-      .spawn(async move {
+    let task_name = format!("Save {}", filename.display());
+    world.send_event(if broken.is_empty() {
+      StatusMessage::info(format!("Saving {}", filename.display()))
+    } else {
+      StatusMessage::warning(format!(
+        "Saving {} with {} broken entity references, see the log",
+        filename.display(),
+        broken.len()
+      ))
+    });
+    world
+      .resource_mut::<EditorTasks>()
+      .spawn(task_name, |task| async move {
         let printable_filename = filename.display().to_string();
 
         info!("saving scene to {}...", printable_filename);
@@ -99,14 +166,21 @@ impl SaveEvent {
           }
         }
 
+        task.set_progress(0.5);
+
+        if task.is_cancelled() {
+          return Ok(());
+        }
+
         if let Err(err) = async_std::fs::write(filename, serialization).await {
-          error!("failed to save scene to '{}': {err}", printable_filename);
-          return;
+          return Err(format!(
+            "failed to save scene to '{printable_filename}': {err}"
+          ));
         }
 
         info!("finished saving");
-      })
-      .detach();
+        Ok(())
+      });
   }
 }
 
@@ -129,20 +203,217 @@ pub struct SceneMarker;
 #[derive(Default, Deref, DerefMut, Clone, Resource)]
 pub struct SceneTypeRegistry(TypeRegistryArc);
 
-pub fn check_for_saves(world: &mut World) {
+/// The cursor is kept between runs so every save is only handled once
+pub fn check_for_saves(world: &mut World, mut cursor: Local<EventCursor<SaveEvent>>) {
   world.resource_scope(|world, save_events: Mut<Events<SaveEvent>>| {
-    save_events.get_cursor().read(&save_events).for_each(|e| {
+    cursor.read(&save_events).for_each(|e| {
+      if !ReadOnly::writable(world) {
+        warn!(
+          "Not saving '{}', the editor is read-only",
+          e.file().display()
+        );
+        world.send_event(StatusMessage::warning(format!(
+          "Not saving {}, the editor is read-only",
+          e.file().display()
+        )));
+        return;
+      }
+
+      events::notify(world, EditorWillSaveScene(e.file().clone()));
       e.handler(world);
     });
   });
 }
 
+/// Sent every frame while a scene is being streamed into the world
+#[derive(Event, Clone, Debug)]
+pub struct SceneLoadProgress {
+  pub file: PathBuf,
+  pub spawned: usize,
+  pub total: usize,
+}
+
+impl SceneLoadProgress {
+  pub fn finished(&self) -> bool {
+    self.spawned >= self.total
+  }
+}
+
+/// Scenes that are loading or partially spawned
+#[derive(Resource, Default)]
+pub struct StreamingScenes(Vec<StreamingScene>);
+
+struct StreamingScene {
+  file: PathBuf,
+  handle: Handle<DynamicScene>,
+  spawning: Option<SpawningScene>,
+}
+
+struct SpawningScene {
+  resources: Vec<Box<dyn PartialReflect>>,
+  /// Ordered furthest first so the closest entities can be popped off the end
+  entities: Vec<DynamicEntity>,
+  entity_map: EntityHashMap<Entity>,
+  total: usize,
+}
+
+impl SpawningScene {
+  fn new(scene: DynamicScene, world: &mut World) -> Self {
+    let DynamicScene {
+      resources,
+      mut entities,
+    } = scene;
+
+    let mut q_cam = world.query_filtered::<&GlobalTransform, With<EditorCamera>>();
+    let focus = q_cam
+      .iter(world)
+      .next()
+      .map(GlobalTransform::translation)
+      .unwrap_or_default();
+
+    entities.sort_by_cached_key(|entity| {
+      let distance = Self::translation_of(entity)
+        .map(|translation| translation.distance_squared(focus))
+        .unwrap_or(f32::MAX);
+      std::cmp::Reverse(distance.to_bits())
+    });
+
+    // reserve every entity up front so references between chunks resolve to the right entity
+    let entity_map = entities
+      .iter()
+      .map(|entity| (entity.entity, world.spawn_empty().id()))
+      .collect();
+
+    Self {
+      resources,
+      total: entities.len(),
+      entities,
+      entity_map,
+    }
+  }
+
+  fn translation_of(entity: &DynamicEntity) -> Option<Vec3> {
+    entity
+      .components
+      .iter()
+      .find(|component| {
+        component
+          .get_represented_type_info()
+          .is_some_and(|info| info.type_id() == std::any::TypeId::of::<Transform>())
+      })
+      .and_then(|component| Transform::from_reflect(component.as_partial_reflect()))
+      .map(|transform| transform.translation)
+  }
+
+  /// Returns true once every entity has been spawned
+  fn spawn_chunk(&mut self, world: &mut World) -> bool {
+    let at = self.entities.len().saturating_sub(ENTITIES_PER_FRAME);
+    let chunk = DynamicScene {
+      resources: std::mem::take(&mut self.resources),
+      entities: self.entities.split_off(at),
+    };
+
+    if let Err(err) = chunk.write_to_world(world, &mut self.entity_map) {
+      error!("Failed to spawn scene chunk: {err}");
+    }
+
+    self.entities.is_empty()
+  }
+}
+
+impl StreamingScenes {
+  pub fn update(world: &mut World) {
+    world.resource_scope(|world, mut streaming: Mut<StreamingScenes>| {
+      streaming.0.retain_mut(|scene| {
+        if scene.spawning.is_none() {
+          match world.resource::<AssetServer>().load_state(&scene.handle) {
+            bevy::asset::LoadState::Failed(err) => {
+              error!("Failed to load scene '{}': {err}", scene.file.display());
+              return false;
+            }
+            bevy::asset::LoadState::Loaded => {
+              // loads of the same file share the handle and the asset, each spawns its own copy
+              let dynamic_scene = world
+                .resource::<Assets<DynamicScene>>()
+                .get(&scene.handle)
+                .map(Self::copy_scene);
+
+              let Some(dynamic_scene) = dynamic_scene else {
+                error!(
+                  "Scene '{}' was unloaded before it spawned",
+                  scene.file.display()
+                );
+                return false;
+              };
+              scene.spawning = Some(SpawningScene::new(dynamic_scene, world));
+            }
+            _ => return true,
+          }
+        }
+
+        let Some(spawning) = scene.spawning.as_mut() else {
+          return true;
+        };
+
+        let done = spawning.spawn_chunk(world);
+
+        world.send_event(SceneLoadProgress {
+          file: scene.file.clone(),
+          spawned: spawning.total - spawning.entities.len(),
+          total: spawning.total,
+        });
+
+        if done {
+          info!("Finished loading scene '{}'", scene.file.display());
+          world.send_event(StatusMessage::info(format!(
+            "Loaded {}",
+            scene.file.display()
+          )));
+          events::notify(world, EditorDidLoadScene(scene.file.clone()));
+        }
+
+        !done
+      });
+    });
+  }
+
+  fn copy_scene(scene: &DynamicScene) -> DynamicScene {
+    DynamicScene {
+      resources: scene
+        .resources
+        .iter()
+        .map(|resource| resource.clone_value())
+        .collect(),
+      entities: scene
+        .entities
+        .iter()
+        .map(|entity| DynamicEntity {
+          entity: entity.entity,
+          components: entity
+            .components
+            .iter()
+            .map(|component| component.clone_value())
+            .collect(),
+        })
+        .collect(),
+    }
+  }
+}
+
 pub fn check_for_loads(
   mut commands: Commands,
+  mut streaming: ResMut<StreamingScenes>,
   mut load_events: EventReader<LoadEvent>,
   asset_server: Res<AssetServer>,
 ) {
   load_events.read().for_each(|e| {
-    commands.spawn(DynamicSceneRoot(asset_server.load(e.file().clone())));
+    info!("Loading scene '{}'", e.file().display());
+    let event = EditorWillLoadScene(e.file().clone());
+    commands.queue(move |world: &mut World| events::notify(world, event));
+    streaming.0.push(StreamingScene {
+      file: e.file().clone(),
+      handle: asset_server.load(e.file().clone()),
+      spawning: None,
+    });
   });
 }
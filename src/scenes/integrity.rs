@@ -0,0 +1,242 @@
+use crate::{cache::Saveable, settings::EditorSettings, ui::PersistentEntityId};
+use bevy::{
+  ecs::entity::EntityHashMap,
+  prelude::*,
+  reflect::{ReflectMut, TypeRegistry},
+  utils::HashMap,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+/// How references to entities that are left out of a saved scene are handled
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone, Default)]
+#[reflect(Resource)]
+pub struct SceneIntegritySettings {
+  /// Point references to missing entities at the saved entity that the field pointed at when
+  /// the scene was last saved, found through [`ReferenceTargets`], instead of only reporting them
+  pub remap_by_persistent_id: bool,
+}
+
+impl Saveable for SceneIntegritySettings {
+  const KEY: &str = "scene_integrity";
+}
+
+impl EditorSettings for SceneIntegritySettings {
+  const NAME: &str = "Scene Integrity";
+}
+
+/// [`PersistentEntityId`] of the entity each field of the entity's components pointed at when the
+/// scene was last saved, by component and field path
+///
+/// Written on save while remapping is on, so a reference whose target was since despawned and
+/// replaced can be pointed at the entity now carrying the same id
+#[derive(Component, Reflect, Default, Clone, Debug)]
+#[reflect(Component)]
+pub struct ReferenceTargets(pub HashMap<String, PersistentEntityId>);
+
+/// Gives every saved entity a [`PersistentEntityId`] so references to it can be recorded
+pub fn assign_ids(world: &mut World, saved: impl IntoIterator<Item = Entity>) {
+  for entity in saved {
+    PersistentEntityId::get_or_insert(entity, world);
+  }
+}
+
+/// Reference in a saved component to an entity that is not part of the scene
+pub struct BrokenReference {
+  pub entity: String,
+  pub component: String,
+  pub field: String,
+  pub target: Entity,
+}
+
+impl Display for BrokenReference {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{} {}{} -> {}",
+      self.entity, self.component, self.field, self.target
+    )
+  }
+}
+
+/// Points entity references in the scene world at the scene's own entities
+///
+/// `saved` maps entities of the editor world to their copies in the scene world. References to
+/// anything else would point at a random entity once the scene is loaded, those are returned
+///
+/// While remapping, the targets of the references are recorded in [`ReferenceTargets`] on both
+/// the entity and its copy
+pub fn check(
+  world: &mut World,
+  scene_world: &mut World,
+  saved: &EntityHashMap<Entity>,
+  type_registry: &TypeRegistry,
+  settings: &SceneIntegritySettings,
+) -> Vec<BrokenReference> {
+  let remap = settings.remap_by_persistent_id;
+
+  let by_persistent_id = saved
+    .iter()
+    .filter_map(|(entity, copy)| Some((*world.get::<PersistentEntityId>(*entity)?, *copy)))
+    .collect::<HashMap<_, _>>();
+
+  let mut broken = Vec::new();
+  let mut recorded = Vec::new();
+
+  for (entity, copy) in saved {
+    let previous = world
+      .get::<ReferenceTargets>(*entity)
+      .map(|targets| targets.0.clone())
+      .unwrap_or_default();
+    let mut targets = ReferenceTargets::default();
+
+    let entity_name = world
+      .get::<Name>(*entity)
+      .map(|name| format!("'{name}'"))
+      .unwrap_or_else(|| entity.to_string());
+
+    let type_ids = scene_world
+      .entity(*copy)
+      .archetype()
+      .components()
+      .filter_map(|id| scene_world.components().get_info(id)?.type_id())
+      .collect::<Vec<_>>();
+
+    for type_id in type_ids {
+      let Some(registration) = type_registry.get(type_id) else {
+        continue;
+      };
+      let Some(reflect_component) = registration.data::<ReflectComponent>() else {
+        continue;
+      };
+      let mut entity_mut = scene_world.entity_mut(*copy);
+      let Some(mut value) = reflect_component.reflect_mut(&mut entity_mut) else {
+        continue;
+      };
+
+      let component = registration
+        .type_info()
+        .type_path_table()
+        .short_path()
+        .to_string();
+
+      let mut path = String::new();
+      visit_entities(
+        value.bypass_change_detection().as_partial_reflect_mut(),
+        &mut path,
+        &mut |field, target| {
+          let key = format!("{component}{field}");
+
+          let resolved = match saved.get(target) {
+            Some(copy) => {
+              if let Some(id) = world.get::<PersistentEntityId>(*target).filter(|_| remap) {
+                targets.0.insert(key, *id);
+              }
+              Some(*copy)
+            }
+            None if remap => previous.get(&key).and_then(|id| {
+              targets.0.insert(key, *id);
+              by_persistent_id.get(id).copied()
+            }),
+            None => None,
+          };
+
+          match resolved {
+            Some(resolved) => *target = resolved,
+            None => broken.push(BrokenReference {
+              entity: entity_name.clone(),
+              component: component.clone(),
+              field: field.to_string(),
+              target: *target,
+            }),
+          }
+        },
+      );
+    }
+
+    if remap {
+      if targets.0.is_empty() {
+        scene_world.entity_mut(*copy).remove::<ReferenceTargets>();
+      } else {
+        scene_world.entity_mut(*copy).insert(targets.clone());
+      }
+      recorded.push((*entity, targets));
+    }
+  }
+
+  for (entity, targets) in recorded {
+    if targets.0.is_empty() {
+      world.entity_mut(entity).remove::<ReferenceTargets>();
+    } else {
+      world.entity_mut(entity).insert(targets);
+    }
+  }
+
+  broken
+}
+
+/// Calls `f` with the path and value of every `Entity` nested in `value`
+fn visit_entities(
+  value: &mut dyn PartialReflect,
+  path: &mut String,
+  f: &mut impl FnMut(&str, &mut Entity),
+) {
+  if let Some(entity) = value.try_downcast_mut::<Entity>() {
+    f(path, entity);
+    return;
+  }
+
+  let len = path.len();
+  let mut visit = |segment: fmt::Arguments, field: &mut dyn PartialReflect| {
+    fmt::Write::write_fmt(path, segment).unwrap();
+    visit_entities(field, path, f);
+    path.truncate(len);
+  };
+
+  match value.reflect_mut() {
+    ReflectMut::Struct(value) => {
+      for i in 0..value.field_len() {
+        let name = value.name_at(i).unwrap_or_default().to_string();
+        visit(format_args!(".{name}"), value.field_at_mut(i).unwrap());
+      }
+    }
+    ReflectMut::TupleStruct(value) => {
+      for i in 0..value.field_len() {
+        visit(format_args!(".{i}"), value.field_mut(i).unwrap());
+      }
+    }
+    ReflectMut::Tuple(value) => {
+      for i in 0..value.field_len() {
+        visit(format_args!(".{i}"), value.field_mut(i).unwrap());
+      }
+    }
+    ReflectMut::List(value) => {
+      for i in 0..value.len() {
+        visit(format_args!("[{i}]"), value.get_mut(i).unwrap());
+      }
+    }
+    ReflectMut::Array(value) => {
+      for i in 0..value.len() {
+        visit(format_args!("[{i}]"), value.get_mut(i).unwrap());
+      }
+    }
+    ReflectMut::Map(value) => {
+      for i in 0..value.len() {
+        let (key, field) = value.get_at_mut(i).unwrap();
+        let key = format!("{key:?}");
+        visit(format_args!("[{key}]"), field);
+      }
+    }
+    ReflectMut::Enum(value) => {
+      for i in 0..value.field_len() {
+        let name = value
+          .name_at(i)
+          .map(str::to_string)
+          .unwrap_or_else(|| i.to_string());
+        visit(format_args!(".{name}"), value.field_at_mut(i).unwrap());
+      }
+    }
+    // set values can't be changed in place and opaque values can't be looked into
+    ReflectMut::Set(_) | ReflectMut::Opaque(_) => (),
+  }
+}
@@ -0,0 +1,284 @@
+use crate::{
+  cache::{Cache, Saveable},
+  events::{EditorDidLoadScene, EditorWillSaveScene},
+  prefab_editor::PrefabEditor,
+  scenes::{self, LoadEvent, SceneMarker, SceneTypeRegistry, StreamingScenes},
+  settings::{EditorSettings, SettingsAppExt},
+  tasks::EditorTasks,
+  EditorState,
+};
+use bevy::{
+  ecs::{
+    component::{ComponentId, Tick},
+    entity::EntityHashMap,
+  },
+  prelude::*,
+  utils::HashSet,
+};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// What happens when the editor is closed and opened again
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone)]
+#[reflect(Resource)]
+pub struct SessionSettings {
+  /// Ask before exiting, listing what would be lost
+  pub confirm_exit: bool,
+  /// Offer to reopen the last scene on launch
+  pub offer_restore: bool,
+}
+
+impl Default for SessionSettings {
+  fn default() -> Self {
+    Self {
+      confirm_exit: true,
+      offer_restore: true,
+    }
+  }
+}
+
+impl Saveable for SessionSettings {
+  const KEY: &str = "session_settings";
+}
+
+impl EditorSettings for SessionSettings {
+  const NAME: &str = "Session";
+}
+
+/// The scene that was open when the editor last exited
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub(crate) struct LastSession {
+  scene: Option<PathBuf>,
+}
+
+impl Saveable for LastSession {
+  const KEY: &str = "last_session";
+}
+
+/// Whether the open scene or the edited prefab changed since it was last saved or loaded
+///
+/// Found by comparing the saved components of their entities against the last check, so edits
+/// made by any panel or tool count
+#[derive(Resource, Default)]
+pub(crate) struct UnsavedChanges {
+  scene: bool,
+  prefab: bool,
+  /// Whether the next check can compare against the last one, otherwise it only takes a new
+  /// baseline
+  synced: bool,
+  checked: Tick,
+  /// Saved components of every checked entity, sorted, so added and removed ones count too
+  components: EntityHashMap<Vec<ComponentId>>,
+  prefab_root: Option<Entity>,
+}
+
+impl UnsavedChanges {
+  fn scene_saved(&mut self) {
+    self.scene = false;
+    self.synced = false;
+  }
+
+  /// Testing writes its snapshot back over the scene on the way out, that isn't an edit either
+  fn resync(mut unsaved: ResMut<Self>) {
+    unsaved.synced = false;
+  }
+
+  pub(crate) fn prefab_saved(&mut self) {
+    self.prefab = false;
+    self.synced = false;
+  }
+
+  fn track(world: &mut World) {
+    let this_run = world.change_tick();
+    let prefab_root = world.resource::<PrefabEditor>().root();
+
+    let saved_components = world
+      .resource::<SceneTypeRegistry>()
+      .read()
+      .iter()
+      .filter_map(|registration| world.components().get_id(registration.type_id()))
+      .collect::<HashSet<_>>();
+
+    let mut q_scene = world.query_filtered::<Entity, With<SceneMarker>>();
+    let mut entities = q_scene
+      .iter(world)
+      .map(|entity| (entity, false))
+      .collect::<Vec<_>>();
+
+    let mut prefab = prefab_root.into_iter().collect::<Vec<_>>();
+    while let Some(entity) = prefab.pop() {
+      entities.push((entity, true));
+      if let Some(children) = world.get::<Children>(entity) {
+        prefab.extend(children.iter().copied());
+      }
+    }
+
+    world.resource_scope(|world, mut this: Mut<Self>| {
+      // prefab sessions hide the scene, that isn't an edit
+      let resync = !std::mem::replace(&mut this.synced, true) || prefab_root != this.prefab_root;
+      if prefab_root != this.prefab_root {
+        this.prefab = false;
+        this.prefab_root = prefab_root;
+      }
+
+      let checked = this.checked;
+      let mut previous = std::mem::take(&mut this.components);
+
+      for (entity, in_prefab) in entities {
+        let entity = world.entity(entity);
+
+        let mut components = entity
+          .archetype()
+          .components()
+          .filter(|id| saved_components.contains(id))
+          .collect::<Vec<_>>();
+        components.sort_unstable();
+
+        let changed = previous.remove(&entity.id()).as_ref() != Some(&components)
+          || components
+            .iter()
+            .filter_map(|id| entity.get_change_ticks_by_id(*id))
+            .any(|ticks| ticks.is_changed(checked, this_run));
+
+        if changed && !resync {
+          if in_prefab {
+            this.prefab = true;
+          } else {
+            this.scene = true;
+          }
+        }
+
+        this.components.insert(entity.id(), components);
+      }
+
+      // anything left over was despawned
+      if !previous.is_empty() && !resync {
+        this.scene = true;
+      }
+
+      this.checked = this_run;
+    });
+  }
+}
+
+/// Dialogs shown by the ui around exiting and launching
+#[derive(Resource, Default)]
+pub(crate) struct SessionPrompts {
+  confirm_exit: bool,
+  restore: Option<PathBuf>,
+}
+
+impl SessionPrompts {
+  pub(crate) fn confirm_exit(&mut self) {
+    self.confirm_exit = true;
+  }
+
+  pub(crate) fn confirming_exit(&self) -> bool {
+    self.confirm_exit
+  }
+
+  pub(crate) fn cancel_exit(&mut self) {
+    self.confirm_exit = false;
+  }
+
+  pub(crate) fn restorable_scene(&self) -> Option<&PathBuf> {
+    self.restore.as_ref()
+  }
+
+  pub(crate) fn dismiss_restore(&mut self) {
+    self.restore = None;
+  }
+
+  /// Reasons to think twice about exiting right now
+  pub(crate) fn exit_warnings(world: &World) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let unsaved = world.resource::<UnsavedChanges>();
+
+    if unsaved.scene {
+      warnings.push(match &world.resource::<LastSession>().scene {
+        Some(scene) => format!("Unsaved changes to {} will be lost", scene.display()),
+        None => String::from("Unsaved changes to the scene will be lost"),
+      });
+    }
+
+    if let Some(prefab) = world
+      .resource::<PrefabEditor>()
+      .editing()
+      .filter(|_| unsaved.prefab)
+    {
+      warnings.push(format!(
+        "Unsaved changes to the prefab {prefab} will be lost"
+      ));
+    }
+
+    for task in world.resource::<EditorTasks>().active_names() {
+      warnings.push(format!("{task} is still running"));
+    }
+
+    warnings
+  }
+
+  pub(crate) fn restore(world: &mut World) {
+    if let Some(scene) = world.resource_mut::<Self>().restore.take() {
+      world.send_event(LoadEvent::_new(scene));
+    }
+  }
+}
+
+pub(crate) struct SessionPlugin;
+
+impl Plugin for SessionPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .register_settings::<SessionSettings>()
+      .init_resource::<LastSession>()
+      .init_resource::<SessionPrompts>()
+      .init_resource::<UnsavedChanges>()
+      .add_systems(PostStartup, offer_restore)
+      .add_systems(OnEnter(EditorState::Editing), UnsavedChanges::resync)
+      .add_systems(
+        Update,
+        (
+          track_scene,
+          UnsavedChanges::track.run_if(in_state(EditorState::Editing)),
+        )
+          .chain()
+          .after(scenes::check_for_saves)
+          .after(StreamingScenes::update),
+      );
+  }
+}
+
+/// Settings are restored on startup, so this waits until after to know whether to ask
+fn offer_restore(
+  cache: Res<Cache>,
+  settings: Res<SessionSettings>,
+  mut prompts: ResMut<SessionPrompts>,
+) {
+  if settings.offer_restore {
+    prompts.restore = cache.get::<LastSession>().and_then(|session| session.scene);
+  }
+}
+
+fn track_scene(
+  mut session: ResMut<LastSession>,
+  mut unsaved: ResMut<UnsavedChanges>,
+  mut loaded: EventReader<EditorDidLoadScene>,
+  mut saved: EventReader<EditorWillSaveScene>,
+) {
+  let latest = loaded
+    .read()
+    .map(|event| &event.0)
+    .chain(saved.read().map(|event| &event.0))
+    .last();
+
+  if let Some(scene) = latest {
+    session.scene = Some(scene.clone());
+    unsaved.scene_saved();
+  }
+}
+
+/// Runs on exit before the cache is written
+pub(crate) fn save_session(session: Res<LastSession>, mut cache: ResMut<Cache>) {
+  cache.store(&*session);
+}
@@ -0,0 +1,105 @@
+use crate::cache::{Cache, Saveable};
+use bevy::{prelude::*, reflect::GetTypeRegistration};
+use std::any::TypeId;
+
+/// A resource that shows up in the Settings panel and is persisted in the cache
+pub trait EditorSettings:
+  Resource + Reflect + GetTypeRegistration + Saveable + Default + Clone
+{
+  const NAME: &str;
+
+  /// Named values that can be applied with a single click from the Settings panel
+  fn presets() -> Vec<(&'static str, Self)> {
+    Vec::new()
+  }
+}
+
+pub trait SettingsAppExt {
+  fn register_settings<T: EditorSettings>(&mut self) -> &mut Self;
+}
+
+impl SettingsAppExt for App {
+  fn register_settings<T: EditorSettings>(&mut self) -> &mut Self {
+    self
+      .world_mut()
+      .get_resource_or_init::<SettingsRegistry>()
+      .entries
+      .push(SettingsEntry::new::<T>());
+
+    self
+      .register_type::<T>()
+      .init_resource::<T>()
+      .add_systems(Startup, SettingsRegistry::restore::<T>)
+  }
+}
+
+#[derive(Resource, Default)]
+pub struct SettingsRegistry {
+  entries: Vec<SettingsEntry>,
+}
+
+impl SettingsRegistry {
+  pub fn entries(&self) -> &[SettingsEntry] {
+    &self.entries
+  }
+
+  fn restore<T: EditorSettings>(mut commands: Commands, cache: Res<Cache>) {
+    if let Some(settings) = cache.get::<T>() {
+      commands.insert_resource(settings);
+    }
+  }
+
+  pub fn on_app_exit(world: &mut World) {
+    world.resource_scope(|world, registry: Mut<SettingsRegistry>| {
+      world.resource_scope(|world, mut cache: Mut<Cache>| {
+        for entry in &registry.entries {
+          (entry.save)(world, &mut cache);
+        }
+      });
+    });
+  }
+}
+
+pub struct SettingsEntry {
+  name: &'static str,
+  type_id: TypeId,
+  save: fn(&World, &mut Cache),
+  presets: fn() -> Vec<&'static str>,
+  apply_preset: fn(&mut World, &str),
+}
+
+impl SettingsEntry {
+  fn new<T: EditorSettings>() -> Self {
+    Self {
+      name: T::NAME,
+      type_id: TypeId::of::<T>(),
+      save: |world, cache| {
+        if let Some(settings) = world.get_resource::<T>() {
+          cache.store(settings);
+        }
+      },
+      presets: || T::presets().into_iter().map(|(name, _)| name).collect(),
+      apply_preset: |world, preset| {
+        if let Some((_, settings)) = T::presets().into_iter().find(|(name, _)| *name == preset) {
+          world.insert_resource(settings);
+        }
+      },
+    }
+  }
+
+  pub fn name(&self) -> &'static str {
+    self.name
+  }
+
+  pub fn type_id(&self) -> TypeId {
+    self.type_id
+  }
+
+  pub fn presets(&self) -> Vec<&'static str> {
+    (self.presets)()
+  }
+
+  pub fn apply_preset(&self, world: &mut World, preset: &str) {
+    (self.apply_preset)(world, preset);
+  }
+}
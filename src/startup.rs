@@ -0,0 +1,124 @@
+use crate::ui::prebuilt::components;
+use bevy::{asset::RecursiveDependencyLoadState, prelude::*};
+use bevy_egui::egui;
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Tracks everything the editor waits on before revealing the full ui
+#[derive(Resource)]
+pub struct StartupProgress {
+  pending: Vec<(String, UntypedHandle)>,
+  loaded: usize,
+  errors: Vec<String>,
+  timeout: Timer,
+  finished: bool,
+  show_errors: bool,
+}
+
+impl Default for StartupProgress {
+  fn default() -> Self {
+    Self {
+      pending: default(),
+      loaded: 0,
+      errors: default(),
+      timeout: Timer::new(TIMEOUT, TimerMode::Once),
+      finished: false,
+      show_errors: false,
+    }
+  }
+}
+
+impl StartupProgress {
+  pub fn track(&mut self, name: impl Into<String>, handle: impl Into<UntypedHandle>) {
+    self.pending.push((name.into(), handle.into()));
+  }
+
+  pub fn finished(&self) -> bool {
+    self.finished
+  }
+
+  fn progress(&self) -> f32 {
+    let total = self.loaded + self.errors.len() + self.pending.len();
+    if total == 0 {
+      1.0
+    } else {
+      (self.loaded + self.errors.len()) as f32 / total as f32
+    }
+  }
+
+  pub fn is_finished(progress: Res<Self>) -> bool {
+    progress.finished
+  }
+
+  pub fn update(mut progress: ResMut<Self>, assets: Res<AssetServer>, time: Res<Time<Real>>) {
+    let mut loaded = 0;
+    let mut errors = Vec::new();
+
+    progress.pending.retain(|(name, handle)| {
+      match assets.get_recursive_dependency_load_state(handle.id()) {
+        Some(RecursiveDependencyLoadState::Loaded) => {
+          loaded += 1;
+          false
+        }
+        Some(RecursiveDependencyLoadState::Failed(err)) => {
+          errors.push(format!("{name}: {err}"));
+          false
+        }
+        _ => true,
+      }
+    });
+
+    progress.loaded += loaded;
+    progress.errors.extend(errors);
+
+    progress.timeout.tick(time.delta());
+
+    if progress.timeout.finished() {
+      let timed_out = progress
+        .pending
+        .drain(..)
+        .map(|(name, _)| format!("{name}: timed out after {}s", TIMEOUT.as_secs()))
+        .collect::<Vec<_>>();
+      progress.errors.extend(timed_out);
+    }
+
+    if progress.pending.is_empty() {
+      info!(
+        "Finished startup loading, {} loaded, {} failed",
+        progress.loaded,
+        progress.errors.len()
+      );
+      progress.finished = true;
+      progress.show_errors = !progress.errors.is_empty();
+    }
+  }
+
+  pub fn ui(&mut self, ctx: &egui::Context) {
+    egui::CentralPanel::default().show(ctx, |ui| {
+      ui.vertical_centered(|ui| {
+        ui.add_space(ui.available_height() / 3.0);
+        ui.heading("Loading");
+        ui.add(
+          egui::ProgressBar::new(self.progress())
+            .desired_width(ui.available_width() / 2.0)
+            .show_percentage(),
+        );
+        for (name, _) in &self.pending {
+          ui.label(name);
+        }
+      });
+    });
+  }
+
+  pub fn errors_ui(&mut self, ctx: &egui::Context) {
+    self.show_errors =
+      components::Dialog::new("Startup Errors").open(ctx, self.show_errors, |ui| {
+        egui::ScrollArea::vertical().show(ui, |ui| {
+          for error in &self.errors {
+            ui.label(error);
+          }
+        });
+      });
+  }
+}
@@ -0,0 +1,230 @@
+use bevy::{
+  prelude::*,
+  tasks::{block_on, poll_once, IoTaskPool, Task},
+};
+use bevy_egui::egui;
+use std::{
+  collections::VecDeque,
+  fmt::Display,
+  future::Future,
+  sync::{
+    atomic::{AtomicBool, AtomicU32, Ordering},
+    Arc,
+  },
+  time::{Duration, Instant},
+};
+
+/// How many finished tasks are kept around to be listed
+const MAX_RECENT: usize = 10;
+/// How long a failure keeps the indicator highlighted
+const FAILURE_HIGHLIGHT: Duration = Duration::from_secs(30);
+
+pub struct EditorTasksPlugin;
+
+impl Plugin for EditorTasksPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .init_resource::<EditorTasks>()
+      .add_systems(Update, EditorTasks::poll);
+  }
+}
+
+#[derive(Default)]
+struct TaskState {
+  /// `f32` bits, so the task can report progress without locking
+  progress: AtomicU32,
+  cancelled: AtomicBool,
+}
+
+/// Given to a background job to report its progress and check whether it was cancelled
+#[derive(Clone, Default)]
+pub struct TaskHandle(Arc<TaskState>);
+
+impl TaskHandle {
+  /// Fraction of the job that is done, from 0 to 1
+  pub fn set_progress(&self, progress: f32) {
+    self
+      .0
+      .progress
+      .store(progress.clamp(0.0, 1.0).to_bits(), Ordering::Relaxed);
+  }
+
+  pub fn progress(&self) -> f32 {
+    f32::from_bits(self.0.progress.load(Ordering::Relaxed))
+  }
+
+  /// Jobs should check this between steps and stop early when it is set
+  pub fn is_cancelled(&self) -> bool {
+    self.0.cancelled.load(Ordering::Relaxed)
+  }
+
+  fn cancel(&self) {
+    self.0.cancelled.store(true, Ordering::Relaxed);
+  }
+}
+
+struct ActiveTask {
+  name: String,
+  handle: TaskHandle,
+  task: Task<Result<(), String>>,
+}
+
+enum Outcome {
+  Succeeded,
+  Cancelled,
+  Failed(String),
+}
+
+struct FinishedTask {
+  name: String,
+  outcome: Outcome,
+  at: Instant,
+}
+
+/// Named background jobs the editor is running, listed in the menu bar with their progress
+#[derive(Resource, Default)]
+pub struct EditorTasks {
+  active: Vec<ActiveTask>,
+  recent: VecDeque<FinishedTask>,
+}
+
+impl EditorTasks {
+  /// Runs the job on the [`IoTaskPool`], it reports progress and cancellation through the handle
+  pub fn spawn<F, Fut, E>(&mut self, name: impl Into<String>, job: F) -> TaskHandle
+  where
+    F: FnOnce(TaskHandle) -> Fut,
+    Fut: Future<Output = Result<(), E>> + Send + 'static,
+    E: Display,
+  {
+    let name = name.into();
+    let handle = TaskHandle::default();
+    let job = job(handle.clone());
+
+    debug!("Started task '{name}'");
+
+    let task = IoTaskPool::get().spawn(async move { job.await.map_err(|err| err.to_string()) });
+
+    self.active.push(ActiveTask {
+      name,
+      handle: handle.clone(),
+      task,
+    });
+
+    handle
+  }
+
+  pub fn is_busy(&self) -> bool {
+    !self.active.is_empty()
+  }
+
+  /// Names of the jobs that haven't finished yet
+  pub fn active_names(&self) -> impl Iterator<Item = &str> {
+    self.active.iter().map(|active| active.name.as_str())
+  }
+
+  fn recent_failures(&self) -> usize {
+    self
+      .recent
+      .iter()
+      .filter(|task| matches!(task.outcome, Outcome::Failed(_)))
+      .filter(|task| task.at.elapsed() < FAILURE_HIGHLIGHT)
+      .count()
+  }
+
+  fn poll(mut tasks: ResMut<Self>) {
+    let mut finished = Vec::new();
+
+    tasks.active.retain_mut(|active| {
+      let Some(result) = block_on(poll_once(&mut active.task)) else {
+        return true;
+      };
+
+      let outcome = match result {
+        Err(err) => {
+          error!("Task '{}' failed: {err}", active.name);
+          Outcome::Failed(err)
+        }
+        Ok(()) if active.handle.is_cancelled() => {
+          info!("Task '{}' was cancelled", active.name);
+          Outcome::Cancelled
+        }
+        Ok(()) => {
+          debug!("Finished task '{}'", active.name);
+          Outcome::Succeeded
+        }
+      };
+
+      finished.push(FinishedTask {
+        name: std::mem::take(&mut active.name),
+        outcome,
+        at: Instant::now(),
+      });
+
+      false
+    });
+
+    for task in finished {
+      tasks.recent.push_front(task);
+    }
+    tasks.recent.truncate(MAX_RECENT);
+  }
+
+  /// Indicator for the menu bar, hidden while nothing has run yet
+  pub fn ui(&mut self, ui: &mut egui::Ui) {
+    if self.active.is_empty() && self.recent.is_empty() {
+      return;
+    }
+
+    let failures = self.recent_failures();
+
+    let label = match (self.active.len(), failures) {
+      (0, 0) => "Tasks".to_string(),
+      (0, failures) => format!("⚠ {failures} Failed"),
+      (active, _) => format!("⏳ {active} Running"),
+    };
+
+    ui.menu_button(label, |ui| {
+      for active in &self.active {
+        ui.horizontal(|ui| {
+          ui.label(&active.name);
+          ui.add(
+            egui::ProgressBar::new(active.handle.progress())
+              .desired_width(100.0)
+              .show_percentage(),
+          );
+
+          let cancelled = active.handle.is_cancelled();
+          if ui
+            .add_enabled(!cancelled, egui::Button::new("Cancel"))
+            .clicked()
+          {
+            active.handle.cancel();
+          }
+        });
+      }
+
+      if !self.active.is_empty() && !self.recent.is_empty() {
+        ui.separator();
+      }
+
+      for finished in &self.recent {
+        let ago = finished.at.elapsed().as_secs();
+        match &finished.outcome {
+          Outcome::Succeeded => {
+            ui.label(format!("✔ {} ({ago}s ago)", finished.name));
+          }
+          Outcome::Cancelled => {
+            ui.weak(format!("✖ {} cancelled ({ago}s ago)", finished.name));
+          }
+          Outcome::Failed(err) => {
+            ui.colored_label(
+              ui.visuals().error_fg_color,
+              format!("⚠ {} ({ago}s ago)", finished.name),
+            )
+            .on_hover_text(err);
+          }
+        }
+      }
+    });
+  }
+}
@@ -0,0 +1,458 @@
+use crate::{
+  cache::Saveable,
+  input::EditorActions,
+  scenes::SceneTypeRegistry,
+  settings::EditorSettings,
+  ui::{prebuilt::material_editor::MaterialPreview, InspectorSelection, PersistentId, UiPanels},
+  view::{camera_preview::CameraPreview, ActiveEditorCamera, EditorCamera, FocusedPane},
+  EditorState,
+};
+use bevy::{
+  core::FrameCount,
+  ecs::{component::ComponentId, entity::EntityHashMap},
+  prelude::*,
+  scene::SceneFilter,
+  utils::HashSet,
+};
+use bevy_egui::egui;
+use leafwing_input_manager::prelude::ActionState;
+use serde::{Deserialize, Serialize};
+use std::{
+  any::TypeId,
+  time::{Duration, SystemTime},
+};
+use uuid::Uuid;
+
+mod monitor;
+mod recorder;
+
+pub use monitor::{TestMonitor, TestMonitorSettings};
+pub use recorder::{TestRecorder, TestRecorderSettings};
+
+/// Entities owned by the editor itself, these are never captured nor restored
+pub(crate) type EditorOwned = Or<(
+  With<Window>,
+  With<EditorCamera>,
+  With<PersistentId>,
+  With<UiPanels>,
+  With<ActionState<EditorActions>>,
+  With<MaterialPreview>,
+  With<CameraPreview>,
+)>;
+
+/// Copy of the game world taken when entering [`EditorState::Testing`](crate::EditorState::Testing)
+///
+/// When testing ends the game world is put back the way it was so play testing can't leave the
+/// scene being edited in a modified state. The game still runs in the editor's world, only what
+/// can be reflected is captured: the entities' reflected components and the resources registered
+/// for scenes. Components without reflection that changed during the run keep their changes,
+/// though ones added during the run are removed
+#[derive(Resource)]
+pub struct TestingSnapshot {
+  scene: DynamicScene,
+  /// Components each entity had, including those the scene can't capture
+  entities: EntityHashMap<HashSet<ComponentId>>,
+  /// Scene resources that didn't exist when the snapshot was taken
+  missing_resources: Vec<ReflectResource>,
+}
+
+impl TestingSnapshot {
+  pub fn take(world: &mut World) {
+    let mut q_entities = world.query::<Entity>();
+    let mut q_editor = world.query_filtered::<Entity, EditorOwned>();
+
+    let editor_entities = q_editor.iter(world).collect::<HashSet<_>>();
+    let entities = q_entities
+      .iter(world)
+      .filter(|entity| !editor_entities.contains(entity))
+      .map(|entity| {
+        let components = world.entity(entity).archetype().components().collect();
+        (entity, components)
+      })
+      .collect::<EntityHashMap<_>>();
+
+    let resources = Self::scene_resources(world);
+    let missing_resources = resources
+      .iter()
+      .filter(|(_, resource)| resource.reflect(&*world).is_none())
+      .map(|(_, resource)| resource.clone())
+      .collect();
+
+    let resource_filter = resources
+      .iter()
+      .fold(SceneFilter::deny_all(), |filter, (type_id, _)| {
+        filter.allow_by_id(*type_id)
+      });
+
+    let scene = DynamicSceneBuilder::from_world(world)
+      .with_resource_filter(resource_filter)
+      .extract_entities(entities.keys().copied())
+      .extract_resources()
+      .build();
+
+    info!(
+      "Took snapshot of {} entities and {} resources for testing",
+      entities.len(),
+      scene.resources.len()
+    );
+
+    world.insert_resource(Self {
+      scene,
+      entities,
+      missing_resources,
+    });
+  }
+
+  pub fn restore(world: &mut World) {
+    let Some(snapshot) = world.remove_resource::<Self>() else {
+      return;
+    };
+
+    let mut q_entities = world.query::<Entity>();
+    let mut q_editor = world.query_filtered::<Entity, EditorOwned>();
+
+    let editor_entities = q_editor.iter(world).collect::<HashSet<_>>();
+    let spawned_while_testing = q_entities
+      .iter(world)
+      .filter(|entity| !editor_entities.contains(entity) && !snapshot.entities.contains_key(entity))
+      .collect::<Vec<_>>();
+
+    for entity in spawned_while_testing {
+      if let Ok(entity) = world.get_entity_mut(entity) {
+        entity.despawn_recursive();
+      }
+    }
+
+    Self::remove_added_components(world, &snapshot.entities);
+
+    for resource in &snapshot.missing_resources {
+      resource.remove(world);
+    }
+
+    // entities that were despawned while testing are left out of the map so they get respawned
+    let mut entity_map = snapshot
+      .entities
+      .keys()
+      .filter(|entity| world.get_entity(**entity).is_ok())
+      .map(|entity| (*entity, *entity))
+      .collect::<EntityHashMap<_>>();
+
+    match snapshot.scene.write_to_world(world, &mut entity_map) {
+      Ok(_) => info!("Restored snapshot of {} entities", entity_map.len()),
+      Err(err) => error!("Failed to restore world after testing: {err}"),
+    }
+  }
+
+  /// Resources registered for scenes, these belong to the game rather than the editor
+  fn scene_resources(world: &World) -> Vec<(TypeId, ReflectResource)> {
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+    world
+      .resource::<SceneTypeRegistry>()
+      .read()
+      .iter()
+      .filter_map(|registration| {
+        let resource = type_registry
+          .get_type_data::<ReflectResource>(registration.type_id())?
+          .clone();
+        Some((registration.type_id(), resource))
+      })
+      .collect()
+  }
+
+  /// Writing the scene back only overwrites, so components added to existing entities while
+  /// testing are removed first
+  fn remove_added_components(world: &mut World, entities: &EntityHashMap<HashSet<ComponentId>>) {
+    let parent_id = world.register_component::<Parent>();
+    let children_id = world.register_component::<Children>();
+
+    for (entity, components) in entities {
+      let Ok(mut entity) = world.get_entity_mut(*entity) else {
+        continue;
+      };
+
+      let added = entity
+        .archetype()
+        .components()
+        .filter(|id| !components.contains(id))
+        .collect::<Vec<_>>();
+
+      for id in added {
+        if id == parent_id {
+          // keeps the children of the new parent in sync
+          entity.remove_parent();
+        } else if id != children_id {
+          // children gained while testing are despawned or taken back by their own parent above
+          entity.remove_by_id(id);
+        }
+      }
+    }
+  }
+}
+
+/// Marks the player or camera that is moved to the editor camera when playing from here
+///
+/// Saved with the scene so the mark is kept between sessions
+#[derive(Component, Reflect, Default, Clone, Copy)]
+#[reflect(Component)]
+pub struct PlayStart;
+
+/// How each play test run is set up
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone, Default)]
+#[reflect(Resource)]
+pub struct TestRunSettings {
+  /// Use `seed` for every run instead of picking a new one, so runs can be reproduced
+  pub fixed_seed: bool,
+  pub seed: u64,
+  /// Start game time from zero on every run instead of continuing where editing left it
+  pub reset_time: bool,
+}
+
+impl Saveable for TestRunSettings {
+  const KEY: &str = "test_runs";
+}
+
+impl EditorSettings for TestRunSettings {
+  const NAME: &str = "Test Runs";
+}
+
+/// The current or last play test run
+///
+/// Games should seed their random number generators from [`TestRunConfig::seed`] when
+/// [`EditorDidEnterTesting`](crate::EditorDidEnterTesting) is sent so a run can be repeated by
+/// fixing the seed in the play options
+#[derive(Resource, Default, Clone, Debug)]
+pub struct TestRunConfig {
+  pub seed: u64,
+  /// Counts the runs since the editor was launched, starting at 1
+  pub run: u32,
+  pub started_at: Option<SystemTime>,
+  /// How long the last run lasted in real time, `None` while it is still running
+  pub duration: Option<Duration>,
+  /// Frames rendered during the last run, `None` while it is still running
+  pub frames: Option<u32>,
+  /// Whether [`PlayStart`] entities were moved to the editor camera when the run started
+  pub from_here: bool,
+  start_elapsed: Duration,
+  start_frame: u32,
+  from_here_requested: bool,
+}
+
+impl TestRunConfig {
+  /// Starts a run with the [`PlayStart`] entities moved to where the editor camera is
+  pub fn play_from_here(world: &mut World) {
+    world.resource_mut::<Self>().from_here_requested = true;
+    world
+      .resource_mut::<NextState<EditorState>>()
+      .set(EditorState::Testing);
+  }
+
+  pub(crate) fn begin(world: &mut World) {
+    let settings = world.resource::<TestRunSettings>().clone();
+
+    let seed = if settings.fixed_seed {
+      settings.seed
+    } else {
+      Uuid::new_v4().as_u64_pair().0
+    };
+
+    if settings.reset_time {
+      Self::reset_time(world);
+    }
+
+    let start_elapsed = world.resource::<Time<Real>>().elapsed();
+    let start_frame = world.resource::<FrameCount>().0;
+
+    let from_here = world.resource::<Self>().from_here_requested;
+    if from_here {
+      Self::move_to_editor_camera(world);
+    }
+
+    let mut config = world.resource_mut::<Self>();
+    *config = Self {
+      seed,
+      run: config.run + 1,
+      started_at: Some(SystemTime::now()),
+      duration: None,
+      frames: None,
+      from_here,
+      start_elapsed,
+      start_frame,
+      from_here_requested: false,
+    };
+
+    info!("Starting test run {} with seed {seed}", config.run);
+  }
+
+  /// Moves the [`PlayStart`] entities to the focused editor camera, the snapshot is taken before
+  /// this so they are back in place once testing ends
+  fn move_to_editor_camera(world: &mut World) {
+    let mut q_cameras =
+      world.query_filtered::<(&GlobalTransform, Has<FocusedPane>), With<EditorCamera>>();
+    let Some(pose) = q_cameras
+      .iter(world)
+      .max_by_key(|(_, focused)| *focused)
+      .map(|(transform, _)| *transform)
+    else {
+      warn!("Playing from the start, there is no editor camera to play from");
+      return;
+    };
+
+    // the 2d editor camera sits far in front of the scene, only its position on screen is used
+    let flat = *world.resource::<State<ActiveEditorCamera>>().get() == ActiveEditorCamera::Cam2D;
+
+    let mut q_starts = world.query_filtered::<(Entity, Option<&Parent>), With<PlayStart>>();
+    let starts = q_starts
+      .iter(world)
+      .map(|(entity, parent)| (entity, parent.map(Parent::get)))
+      .collect::<Vec<_>>();
+
+    if starts.is_empty() {
+      warn!("Playing from the start, no entity is marked as the play start");
+    }
+
+    for (entity, parent) in starts {
+      let parent_transform = parent
+        .and_then(|parent| world.get::<GlobalTransform>(parent).copied())
+        .unwrap_or_default();
+      let moved = pose.reparented_to(&parent_transform);
+
+      let Some(mut transform) = world.get_mut::<Transform>(entity) else {
+        continue;
+      };
+
+      if flat {
+        transform.translation.x = moved.translation.x;
+        transform.translation.y = moved.translation.y;
+      } else {
+        transform.translation = moved.translation;
+        transform.rotation = moved.rotation;
+      }
+    }
+  }
+
+  pub(crate) fn end(world: &mut World) {
+    let elapsed = world.resource::<Time<Real>>().elapsed();
+    let frame = world.resource::<FrameCount>().0;
+
+    let mut config = world.resource_mut::<Self>();
+    if config.run == 0 {
+      return;
+    }
+
+    let duration = elapsed.saturating_sub(config.start_elapsed);
+    let frames = frame.wrapping_sub(config.start_frame);
+    config.duration = Some(duration);
+    config.frames = Some(frames);
+
+    info!(
+      "Test run {} with seed {} lasted {:.2}s over {frames} frames",
+      config.run,
+      config.seed,
+      duration.as_secs_f32()
+    );
+  }
+
+  /// Puts the game clocks back to zero, keeping their speed and timestep
+  fn reset_time(world: &mut World) {
+    let (max_delta, relative_speed) = {
+      let virt = world.resource::<Time<Virtual>>();
+      (virt.max_delta(), virt.relative_speed_f64())
+    };
+    let mut virt = Time::<Virtual>::from_max_delta(max_delta);
+    virt.set_relative_speed_f64(relative_speed);
+
+    let timestep = world.resource::<Time<Fixed>>().timestep();
+
+    world.insert_resource(virt);
+    world.insert_resource(Time::<Fixed>::from_duration(timestep));
+    world.insert_resource(Time::<()>::default());
+  }
+
+  fn play_start_ui(ui: &mut egui::Ui, world: &mut World) {
+    let selected = match world.resource::<InspectorSelection>() {
+      InspectorSelection::Entities(selected) => selected.iter().collect::<Vec<_>>(),
+      _ => Vec::new(),
+    };
+
+    let starts = world
+      .query_filtered::<(), With<PlayStart>>()
+      .iter(world)
+      .count();
+    ui.label(format!("Play start entities: {starts}"));
+
+    ui.horizontal(|ui| {
+      ui.add_enabled_ui(!selected.is_empty(), |ui| {
+        if ui
+          .button("Mark Selection")
+          .on_hover_text("Move the selected entities to the editor camera when playing from here")
+          .clicked()
+        {
+          for entity in &selected {
+            if let Ok(mut entity) = world.get_entity_mut(*entity) {
+              entity.insert(PlayStart);
+            }
+          }
+        }
+
+        if ui.button("Unmark Selection").clicked() {
+          for entity in &selected {
+            if let Ok(mut entity) = world.get_entity_mut(*entity) {
+              entity.remove::<PlayStart>();
+            }
+          }
+        }
+      });
+    });
+  }
+
+  /// Options for the next run, shown next to the play button
+  pub(crate) fn options_ui(ui: &mut egui::Ui, world: &mut World) {
+    let last_seed = {
+      let config = world.resource::<Self>();
+      (config.run > 0).then_some(config.seed)
+    };
+
+    let mut settings = world.resource_mut::<TestRunSettings>();
+
+    ui.checkbox(&mut settings.fixed_seed, "Fixed Seed")
+      .on_hover_text("Use the same random seed for every run");
+    ui.add_enabled_ui(settings.fixed_seed, |ui| {
+      ui.horizontal(|ui| {
+        ui.add(egui::DragValue::new(&mut settings.seed));
+        if let Some(seed) = last_seed {
+          if ui
+            .button("Use Last")
+            .on_hover_text(format!("Repeat the last run's seed, {seed}"))
+            .clicked()
+          {
+            settings.seed = seed;
+          }
+        }
+      });
+    });
+
+    ui.checkbox(&mut settings.reset_time, "Reset Time")
+      .on_hover_text("Start game time from zero on every run");
+
+    ui.separator();
+    Self::play_start_ui(ui, world);
+
+    let config = world.resource::<Self>();
+    if config.run == 0 {
+      return;
+    }
+
+    ui.separator();
+    ui.label(format!("Last run: #{}", config.run));
+    ui.label(format!("Seed: {}", config.seed));
+    if config.from_here {
+      ui.label("Started from the editor camera");
+    }
+    if let (Some(duration), Some(frames)) = (config.duration, config.frames) {
+      ui.label(format!(
+        "Lasted {:.2}s over {frames} frames",
+        duration.as_secs_f32()
+      ));
+    }
+  }
+}
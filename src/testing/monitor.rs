@@ -0,0 +1,273 @@
+use crate::{cache::Saveable, settings::EditorSettings, ui::status_bar::StatusMessage, util};
+use bevy::{
+  core::FrameCount,
+  ecs::{archetype::ArchetypeId, entity::EntityHashMap},
+  prelude::*,
+};
+use bevy_egui::egui;
+use serde::{Deserialize, Serialize};
+use std::{collections::VecDeque, path::PathBuf};
+
+/// Frames kept for the chart and the report
+const HISTORY_LEN: usize = 600;
+
+/// Frames skipped before the entity count baseline is taken, games usually spawn their world
+/// over the first few frames
+const WARMUP_FRAMES: usize = 60;
+
+/// Frames averaged when checking for archetype thrash
+const MOVES_WINDOW: usize = 60;
+
+const CHART_SIZE: egui::Vec2 = egui::vec2(240.0, 48.0);
+
+/// Watches entity counts and archetype moves while testing, off by default since it visits
+/// every entity each frame
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone)]
+#[reflect(Resource)]
+pub struct TestMonitorSettings {
+  pub enabled: bool,
+  /// Growth of the entity count over the baseline that is flagged, `0.5` flags 50% more entities
+  pub entity_growth: f32,
+  /// Average archetype moves per frame that are flagged as thrashing
+  pub max_moves_per_frame: f32,
+}
+
+impl Default for TestMonitorSettings {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      entity_growth: 0.5,
+      max_moves_per_frame: 100.0,
+    }
+  }
+}
+
+impl Saveable for TestMonitorSettings {
+  const KEY: &str = "test_monitor";
+}
+
+impl EditorSettings for TestMonitorSettings {
+  const NAME: &str = "Test Monitor";
+}
+
+#[derive(Clone, Copy, Serialize)]
+struct MonitorSample {
+  frame: u32,
+  entities: usize,
+  /// Entities that changed archetype since the last frame
+  moves: usize,
+  archetypes: usize,
+}
+
+#[derive(Serialize)]
+struct MonitorFlag {
+  frame: u32,
+  message: String,
+}
+
+/// Samples and findings of the current or last monitored test run
+#[derive(Resource, Default, Serialize)]
+pub struct TestMonitor {
+  /// The most recent frames, oldest first
+  samples: VecDeque<MonitorSample>,
+  flags: Vec<MonitorFlag>,
+  /// Entity count once the run warmed up
+  baseline: Option<usize>,
+  peak_entities: usize,
+  total_moves: usize,
+  #[serde(skip)]
+  frames: usize,
+  /// Entity count that raises the next growth flag
+  #[serde(skip)]
+  growth_threshold: Option<usize>,
+  #[serde(skip)]
+  thrashing: bool,
+  #[serde(skip)]
+  archetypes: EntityHashMap<ArchetypeId>,
+}
+
+impl TestMonitor {
+  pub(crate) fn reset(mut monitor: ResMut<Self>) {
+    *monitor = default();
+  }
+
+  pub(crate) fn sample(world: &mut World) {
+    let settings = world.resource::<TestMonitorSettings>().clone();
+    if !settings.enabled {
+      return;
+    }
+
+    let frame = world.resource::<FrameCount>().0;
+
+    let mut archetypes = EntityHashMap::default();
+    for archetype in world.archetypes().iter() {
+      for entity in archetype.entities() {
+        archetypes.insert(entity.id(), archetype.id());
+      }
+    }
+    let archetype_count = world.archetypes().len();
+
+    let mut flags = Vec::new();
+    let mut monitor = world.resource_mut::<Self>();
+
+    // spawned and despawned entities aren't moves, only those seen in both frames
+    let moves = archetypes
+      .iter()
+      .filter(|(entity, id)| {
+        monitor
+          .archetypes
+          .get(*entity)
+          .is_some_and(|last| last != *id)
+      })
+      .count();
+
+    let sample = MonitorSample {
+      frame,
+      entities: archetypes.len(),
+      moves,
+      archetypes: archetype_count,
+    };
+
+    monitor.archetypes = archetypes;
+    monitor.frames += 1;
+    monitor.total_moves += moves;
+    monitor.peak_entities = monitor.peak_entities.max(sample.entities);
+    if monitor.samples.len() == HISTORY_LEN {
+      monitor.samples.pop_front();
+    }
+    monitor.samples.push_back(sample);
+
+    if monitor.frames == WARMUP_FRAMES {
+      monitor.baseline = Some(sample.entities);
+      monitor.growth_threshold = Some(Self::grown(sample.entities, settings.entity_growth));
+    }
+
+    if let (Some(baseline), Some(threshold)) = (monitor.baseline, monitor.growth_threshold) {
+      if sample.entities >= threshold {
+        // the next flag waits for the same growth again so a steady leak isn't flagged every frame
+        monitor.growth_threshold = Some(Self::grown(sample.entities, settings.entity_growth));
+        flags.push(format!(
+          "Entity count grew from {baseline} to {}, possible leak",
+          sample.entities
+        ));
+      }
+    }
+
+    if monitor.frames > WARMUP_FRAMES {
+      let average = monitor.average_moves();
+      if average > settings.max_moves_per_frame && !monitor.thrashing {
+        monitor.thrashing = true;
+        flags.push(format!(
+          "{average:.0} archetype moves per frame, components may be inserted and removed every frame"
+        ));
+      } else if average <= settings.max_moves_per_frame {
+        monitor.thrashing = false;
+      }
+    }
+
+    for message in &flags {
+      warn!("{message}");
+      monitor.flags.push(MonitorFlag {
+        frame,
+        message: message.clone(),
+      });
+    }
+
+    for message in flags {
+      world.send_event(StatusMessage::warning(message));
+    }
+  }
+
+  fn grown(entities: usize, growth: f32) -> usize {
+    entities + ((entities as f32 * growth.max(0.0)).ceil() as usize).max(1)
+  }
+
+  fn average_moves(&self) -> f32 {
+    let window = self.samples.iter().rev().take(MOVES_WINDOW);
+    let count = window.len().max(1);
+    window.map(|sample| sample.moves).sum::<usize>() as f32 / count as f32
+  }
+
+  fn export(&self) -> Result<PathBuf, String> {
+    util::export_json("test_monitor", self)
+  }
+
+  fn chart(ui: &mut egui::Ui, label: &str, values: impl Iterator<Item = usize> + Clone) {
+    let max = values.clone().max().unwrap_or_default().max(1);
+
+    ui.label(format!("{label} (max {max})"));
+    let (rect, _) = ui.allocate_exact_size(CHART_SIZE, egui::Sense::hover());
+
+    let step = rect.width() / (HISTORY_LEN - 1) as f32;
+    let points = values
+      .enumerate()
+      .map(|(i, value)| {
+        let t = value as f32 / max as f32;
+        egui::pos2(
+          rect.left() + i as f32 * step,
+          rect.bottom() - t * rect.height(),
+        )
+      })
+      .collect::<Vec<_>>();
+
+    let painter = ui.painter_at(rect);
+    painter.rect_stroke(rect, 0.0, ui.visuals().widgets.noninteractive.bg_stroke);
+    painter.add(egui::Shape::line(
+      points,
+      egui::Stroke::new(1.0, ui.visuals().selection.bg_fill),
+    ));
+  }
+
+  /// Drawn in the debug panel
+  pub(crate) fn ui(
+    &self,
+    ui: &mut egui::Ui,
+    settings: &mut TestMonitorSettings,
+    status: &mut EventWriter<StatusMessage>,
+  ) {
+    ui.checkbox(&mut settings.enabled, "Monitor Test Runs")
+      .on_hover_text("Record entity counts and archetype moves every frame while testing");
+
+    if self.samples.is_empty() {
+      ui.weak("No monitored test run yet");
+      return;
+    }
+
+    Self::chart(
+      ui,
+      "Entities",
+      self.samples.iter().map(|sample| sample.entities),
+    );
+    Self::chart(
+      ui,
+      "Archetype Moves",
+      self.samples.iter().map(|sample| sample.moves),
+    );
+
+    if let Some(baseline) = self.baseline {
+      ui.label(format!(
+        "Baseline {baseline} entities, peak {}",
+        self.peak_entities
+      ));
+    }
+
+    for flag in &self.flags {
+      ui.colored_label(
+        ui.visuals().warn_fg_color,
+        format!("Frame {}: {}", flag.frame, flag.message),
+      );
+    }
+
+    if ui.button("Export Report").clicked() {
+      status.send(match self.export() {
+        Ok(file) => StatusMessage::info(format!(
+          "Exported the test monitor report to {}",
+          file.display()
+        )),
+        Err(err) => {
+          StatusMessage::error(format!("Failed to export the test monitor report: {err}"))
+        }
+      });
+    }
+  }
+}
@@ -0,0 +1,317 @@
+use super::EditorOwned;
+use crate::{cache::Saveable, settings::EditorSettings, ui::status_bar::StatusMessage};
+use bevy::{prelude::*, utils::HashSet};
+use bevy_egui::egui;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Records components of the game every few frames while testing, off by default
+///
+/// Stepping back to a snapshot pauses virtual time and writes the recorded values over the world.
+/// Nothing else is rewound, so a resumed run is not guaranteed to play out the same way again
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone)]
+#[reflect(Resource)]
+pub struct TestRecorderSettings {
+  pub enabled: bool,
+  /// Frames between snapshots
+  pub interval: u32,
+  /// Snapshots kept, the oldest are dropped first
+  pub capacity: usize,
+  /// Short or full type paths of the recorded components, read when a run starts
+  pub components: Vec<String>,
+}
+
+impl Default for TestRecorderSettings {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      interval: 5,
+      capacity: 600,
+      components: vec![String::from("Transform")],
+    }
+  }
+}
+
+impl Saveable for TestRecorderSettings {
+  const KEY: &str = "test_recorder";
+}
+
+impl EditorSettings for TestRecorderSettings {
+  const NAME: &str = "Test Recorder";
+}
+
+struct Snapshot {
+  /// Frames into the run
+  frame: u32,
+  /// Seconds of virtual time into the run
+  time: f32,
+  /// Entity, index into the recorded components and the value
+  values: Vec<(Entity, usize, Box<dyn PartialReflect>)>,
+}
+
+enum RecorderRequest {
+  Seek(usize),
+  Resume,
+}
+
+/// Snapshots of the current or last recorded test run
+#[derive(Resource, Default)]
+pub struct TestRecorder {
+  /// Oldest first
+  snapshots: VecDeque<Snapshot>,
+  components: Vec<ReflectComponent>,
+  /// Frames recorded into the run
+  frame: u32,
+  start_time: f32,
+  /// Snapshot the world was stepped back to, nothing is recorded while set
+  viewing: Option<usize>,
+  request: Option<RecorderRequest>,
+  /// The component list as it is being typed
+  components_text: Option<String>,
+}
+
+impl TestRecorder {
+  pub fn is_viewing(&self) -> bool {
+    self.viewing.is_some()
+  }
+
+  pub(crate) fn reset(
+    mut recorder: ResMut<Self>,
+    settings: Res<TestRecorderSettings>,
+    type_registry: Res<AppTypeRegistry>,
+    time: Res<Time<Virtual>>,
+    mut status: EventWriter<StatusMessage>,
+  ) {
+    let type_registry = type_registry.read();
+
+    let mut components = Vec::new();
+    for name in &settings.components {
+      let name = name.trim();
+      let component = type_registry
+        .get_with_short_type_path(name)
+        .or_else(|| type_registry.get_with_type_path(name))
+        .and_then(|registration| registration.data::<ReflectComponent>());
+
+      match component {
+        Some(component) => components.push(component.clone()),
+        None if settings.enabled => {
+          status.send(StatusMessage::warning(format!(
+            "Can't record {name}, it is not a registered component"
+          )));
+        }
+        None => (),
+      }
+    }
+
+    *recorder = Self {
+      components,
+      start_time: time.elapsed_secs(),
+      ..default()
+    };
+  }
+
+  pub(crate) fn record(world: &mut World) {
+    let settings = world.resource::<TestRecorderSettings>().clone();
+    let recorder = world.resource::<Self>();
+    if !settings.enabled || recorder.viewing.is_some() || recorder.components.is_empty() {
+      return;
+    }
+
+    let frame = recorder.frame;
+    world.resource_mut::<Self>().frame += 1;
+    if frame % settings.interval.max(1) != 0 {
+      return;
+    }
+
+    let time = world.resource::<Time<Virtual>>().elapsed_secs();
+
+    world.resource_scope(|world, mut recorder: Mut<Self>| {
+      let editor_entities = world
+        .query_filtered::<Entity, EditorOwned>()
+        .iter(world)
+        .collect::<HashSet<_>>();
+
+      let mut values = Vec::new();
+      for entity in world.iter_entities() {
+        if editor_entities.contains(&entity.id()) {
+          continue;
+        }
+
+        for (index, component) in recorder.components.iter().enumerate() {
+          if let Some(value) = component.reflect(entity) {
+            values.push((entity.id(), index, value.clone_value()));
+          }
+        }
+      }
+
+      while recorder.snapshots.len() >= settings.capacity.max(1) {
+        recorder.snapshots.pop_front();
+      }
+
+      let start_time = recorder.start_time;
+      recorder.snapshots.push_back(Snapshot {
+        frame,
+        time: time - start_time,
+        values,
+      });
+    });
+  }
+
+  /// Steps the world to the snapshot or resumes the run, as asked for by the ui
+  pub(crate) fn apply_request(world: &mut World) {
+    let Some(request) = world.resource_mut::<Self>().request.take() else {
+      return;
+    };
+
+    match request {
+      RecorderRequest::Seek(index) => {
+        world.resource_scope(|world, mut recorder: Mut<Self>| {
+          let Some(snapshot) = recorder.snapshots.get(index) else {
+            return;
+          };
+
+          for (entity, component, value) in &snapshot.values {
+            let Ok(entity) = world.get_entity_mut(*entity) else {
+              // despawned since the snapshot
+              continue;
+            };
+
+            if let Some(mut current) = recorder.components[*component].reflect_mut(entity) {
+              current.apply(value.as_ref());
+            }
+          }
+
+          recorder.viewing = Some(index);
+        });
+
+        world.resource_mut::<Time<Virtual>>().pause();
+      }
+      RecorderRequest::Resume => {
+        let mut recorder = world.resource_mut::<Self>();
+        let Some(index) = recorder.viewing.take() else {
+          return;
+        };
+
+        // the run continues from here, the snapshots after it never happen
+        recorder.snapshots.truncate(index + 1);
+        world.resource_mut::<Time<Virtual>>().unpause();
+      }
+    }
+  }
+
+  /// Runs when testing ends so the editor isn't left with game time paused
+  pub(crate) fn end(mut recorder: ResMut<Self>, mut time: ResMut<Time<Virtual>>) {
+    recorder.request = None;
+    if recorder.viewing.take().is_some() {
+      time.unpause();
+    }
+  }
+
+  fn settings_ui(ui: &mut egui::Ui, settings: &mut TestRecorderSettings, components: &mut String) {
+    ui.checkbox(&mut settings.enabled, "Record Test Runs")
+      .on_hover_text("Snapshot components while testing so the run can be stepped back through");
+
+    ui.add_enabled_ui(settings.enabled, |ui| {
+      ui.horizontal(|ui| {
+        ui.label("Every");
+        ui.add(
+          egui::DragValue::new(&mut settings.interval)
+            .range(1..=600)
+            .suffix(" frames"),
+        );
+        ui.label("Keep");
+        ui.add(
+          egui::DragValue::new(&mut settings.capacity)
+            .range(1..=10_000)
+            .suffix(" snapshots"),
+        );
+      });
+
+      ui.horizontal(|ui| {
+        ui.label("Components");
+        if ui
+          .text_edit_singleline(components)
+          .on_hover_text("Comma separated component types, used from the next run")
+          .changed()
+        {
+          settings.components = components
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(String::from)
+            .collect();
+        }
+      });
+    });
+  }
+
+  /// Drawn in the debug panel
+  pub(crate) fn ui(
+    &mut self,
+    ui: &mut egui::Ui,
+    settings: &mut TestRecorderSettings,
+    testing: bool,
+  ) {
+    let components = self
+      .components_text
+      .get_or_insert_with(|| settings.components.join(", "));
+    Self::settings_ui(ui, settings, components);
+
+    let Some(last) = self.snapshots.len().checked_sub(1) else {
+      ui.weak("No recorded test run yet");
+      return;
+    };
+
+    let mut index = self.viewing.unwrap_or(last).min(last);
+    let snapshot = &self.snapshots[index];
+    let label = format!("Frame {} at {:.2}s", snapshot.frame, snapshot.time);
+
+    ui.add_enabled_ui(testing, |ui| {
+      let mut seek = false;
+
+      ui.horizontal(|ui| {
+        if ui
+          .add_enabled(index > 0, egui::Button::new("⏮"))
+          .on_hover_text("Step back a snapshot")
+          .clicked()
+        {
+          index -= 1;
+          seek = true;
+        }
+        if ui
+          .add_enabled(index < last, egui::Button::new("⏭"))
+          .on_hover_text("Step forward a snapshot")
+          .clicked()
+        {
+          index += 1;
+          seek = true;
+        }
+        seek |= ui
+          .add(egui::Slider::new(&mut index, 0..=last).show_value(false))
+          .changed();
+        ui.label(&label);
+      });
+
+      if seek {
+        self.request = Some(RecorderRequest::Seek(index));
+      }
+
+      if self.viewing.is_some() {
+        ui.horizontal(|ui| {
+          ui.colored_label(ui.visuals().warn_fg_color, "Game time is paused");
+          if ui
+            .button("Resume")
+            .on_hover_text(
+              "Continue the run from this snapshot, the snapshots after it are dropped",
+            )
+            .clicked()
+          {
+            self.request = Some(RecorderRequest::Resume);
+          }
+        });
+      }
+    })
+    .response
+    .on_disabled_hover_text("Snapshots can only be stepped to while testing");
+  }
+}
@@ -1,31 +1,69 @@
+pub mod component_toggle;
+pub mod curves;
+pub mod enum_editors;
 pub mod events;
 pub mod managers;
+pub mod menus;
 pub mod misc;
 pub mod prebuilt;
-
-use crate::cache::{Cache, Saveable};
+pub mod selection;
+pub mod status_bar;
+pub mod type_search;
+
+use crate::{
+  cache::{Cache, Saveable},
+  startup::StartupProgress,
+  util::WorldExtensions,
+  view, Editing, EditorState,
+};
 use bevy::{
-  asset::UntypedAssetId, ecs::system::SystemParam, prelude::*, reflect::GetTypeRegistration,
+  asset::UntypedAssetId,
+  ecs::{event::EventCursor, system::SystemParam},
+  prelude::*,
+  reflect::GetTypeRegistration,
   utils::HashMap,
+  window::WindowCloseRequested,
 };
 use bevy_egui::{
   egui::{self},
   EguiPlugin,
 };
-use bevy_inspector_egui::bevy_inspector;
+use bevy_inspector_egui::{bevy_inspector, inspector_egui_impls::InspectorEguiImpl};
+use component_toggle::DisabledComponents;
+use curves::{ColorGradient, FloatCurve};
 use derive_more::derive::From;
 use egui_dock::{DockState, NodeIndex, SurfaceIndex};
+use enum_editors::InspectorAppExt;
 use events::{AddUiEvent, RemoveUiEvent, SaveLayoutEvent};
 use itertools::{Either, Itertools};
 use managers::UiManager;
-use misc::{MissingUi, UiExtensions, UiInfo};
+use menus::MenuRegistry;
+use misc::{MissingUi, UiExtensions, UiInfo, UiParamStates};
 use parking_lot::Mutex;
 use prebuilt::{
-  assets::Assets, debug::DebugMenu, editor_view::EditorView, hierarchy::Hierarchy,
-  inspector::Inspector, prefabs::Prefabs, resources::Resources,
+  array_tool::ArrayTool,
+  asset_reloads::AssetReloads,
+  assets::Assets,
+  debug::DebugMenu,
+  editor_view::EditorView,
+  find_in_world::FindInWorld,
+  hierarchy::Hierarchy,
+  inspector::{Inspector, InspectorLayout},
+  material_editor::MaterialEditor,
+  prefabs::Prefabs,
+  render_layers::RenderLayersEditor,
+  resources::Resources,
+  session_stats::{SessionStats, SessionStatsPanel},
+  settings::Settings,
+  tile_brush::{Tile, TileBrush},
+  timeline::Timeline,
+  watches::Watches,
 };
+use selection::PendingSelection;
 use serde::{Deserialize, Serialize};
+use status_bar::{StatusBar, StatusMessage};
 use std::{any::TypeId, borrow::BorrowMut, cell::RefCell, collections::BTreeMap};
+use type_search::TypeSearch;
 use uuid::Uuid;
 
 pub(crate) struct UiPlugin(pub Mutex<RefCell<Option<UiManager>>>);
@@ -47,12 +85,37 @@ impl Plugin for UiPlugin {
       .register_type::<Prefabs>()
       .register_type::<Resources>()
       .register_type::<Assets>()
+      .register_type::<RenderLayersEditor>()
+      .register_type::<Settings>()
+      .register_type::<Watches>()
+      .register_type::<FindInWorld>()
+      .register_type::<Timeline>()
+      .register_type::<SessionStatsPanel>()
+      .register_type::<AssetReloads>()
+      .register_type::<ArrayTool>()
+      .register_type::<MaterialEditor>()
+      .register_type::<TileBrush>()
+      .register_type::<Tile>()
+      .register_type::<PersistentEntityId>()
+      .register_type::<DisabledComponents>()
+      .register_type::<FloatCurve>()
+      .register_type_data::<FloatCurve, InspectorEguiImpl>()
+      .register_type::<ColorGradient>()
+      .register_type_data::<ColorGradient, InspectorEguiImpl>()
+      .register_searchable_enum::<KeyCode>()
+      .register_searchable_enum::<GamepadButton>()
       .add_event::<AddUiEvent>()
       .add_event::<RemoveUiEvent>()
       .add_event::<SaveLayoutEvent>()
+      .add_event::<StatusMessage>()
+      .init_resource::<StatusBar>()
+      .init_resource::<MenuRegistry>()
       .init_resource::<InspectorSelection>()
+      .init_resource::<PendingSelection>()
+      .init_resource::<TypeSearch>()
+      .init_resource::<UiParamStates>()
       .add_plugins(EguiPlugin)
-      .add_systems(Startup, Self::init_resources)
+      .add_systems(Startup, (Self::init_resources, PendingSelection::load))
       .add_systems(
         Update,
         (
@@ -66,9 +129,24 @@ impl Plugin for UiPlugin {
               .chain(),
             AddUiEvent::on_event,
           ),
+          UiParamStates::apply,
         )
           .chain(),
       )
+      .add_systems(
+        Update,
+        (
+          (
+            PendingSelection::on_scene_loaded,
+            PendingSelection::restore.run_if(StartupProgress::is_finished),
+          )
+            .chain()
+            .run_if(PendingSelection::is_pending),
+          selection::tag_selected.run_if(resource_changed::<InspectorSelection>),
+        )
+          .in_set(Editing),
+      )
+      .add_systems(Update, (StatusBar::receive, Self::close_workspaces))
       .add_systems(FixedUpdate, SaveLayoutEvent::on_event);
 
     for vtable in ui_manager.vtables() {
@@ -87,6 +165,20 @@ impl UiPlugin {
     });
   }
 
+  /// Closing a workspace window closes its panels, the primary window exits the editor instead
+  fn close_workspaces(world: &mut World, mut cursor: Local<EventCursor<WindowCloseRequested>>) {
+    let windows = cursor
+      .read(world.resource::<Events<WindowCloseRequested>>())
+      .map(|request| request.window)
+      .collect::<Vec<_>>();
+
+    world.resource_scope(|world, mut ui_manager: Mut<UiManager>| {
+      for window in windows {
+        ui_manager.close_workspace(window, world);
+      }
+    });
+  }
+
   pub fn reset_ui_info(mut q_ui_infos: Query<&mut UiInfo>) {
     q_ui_infos.par_iter_mut().for_each(|mut ui_info| {
       ui_info.rendered = false;
@@ -123,17 +215,20 @@ impl UiPlugin {
     });
   }
 
-  pub fn on_app_exit(
-    mut cache: ResMut<Cache>,
-    ui_manager: Res<UiManager>,
-    q_uuids: Query<&PersistentId, Without<MissingUi>>,
-    q_missing: Query<&MissingUi>,
-  ) {
-    let new_state = ui_manager.save_current_layout(&q_uuids, &q_missing);
-    cache.store(&LayoutState {
-      dock: new_state,
-      layouts: ui_manager.saved_layouts().clone(),
+  pub fn on_app_exit(world: &mut World) {
+    world.resource_scope(|world, ui_manager: Mut<UiManager>| {
+      let new_state = ui_manager.save_current_layout(world);
+      let layouts = ui_manager.saved_layouts().clone();
+      let workspaces = ui_manager.save_workspaces(world);
+      world.resource_mut::<Cache>().store(&LayoutState {
+        dock: new_state,
+        layouts,
+        workspaces,
+      });
     });
+
+    selection::on_app_exit(world);
+    InspectorLayout::on_app_exit(world);
   }
 }
 
@@ -150,6 +245,17 @@ pub trait RawUi: Component + GetTypeRegistration + Send + Sync + Sized {
   #[allow(unused_variables)]
   fn on_despawn(entity: Entity, world: &mut World) {}
 
+  /// Per instance state that is stored alongside the layout
+  ///
+  /// Whatever is returned here is handed back to `restore_state` when the layout is restored
+  #[allow(unused_variables)]
+  fn save_state(entity: Entity, world: &mut World) -> Option<serde_json::Value> {
+    None
+  }
+
+  #[allow(unused_variables)]
+  fn restore_state(entity: Entity, world: &mut World, state: serde_json::Value) {}
+
   #[allow(unused_variables)]
   fn title(entity: Entity, world: &mut World) -> egui::WidgetText {
     Self::NAME.into()
@@ -200,6 +306,21 @@ pub trait RawUi: Component + GetTypeRegistration + Send + Sync + Sized {
   fn popout() -> bool {
     true
   }
+
+  /// Skips drawing the panel while the editor view is being navigated, for panels that are
+  /// expensive to draw, see [`LowLatencySettings`](crate::view::LowLatencySettings)
+  fn pausable() -> bool {
+    false
+  }
+
+  /// Whether the panel's tab is shown while the editor is in `state`
+  ///
+  /// Tabs are taken out of the dock when the editor enters a state they aren't shown in and put
+  /// back in the same slot once it leaves it, i.e. for panels that only make sense while testing
+  #[allow(unused_variables)]
+  fn visible_in(state: EditorState) -> bool {
+    true
+  }
 }
 
 pub trait Ui: RawUi {
@@ -250,6 +371,17 @@ pub trait Ui: RawUi {
   #[allow(unused_variables)]
   fn on_despawn(&mut self, params: Self::Params<'_, '_>) {}
 
+  /// Per instance state that is stored alongside the layout
+  ///
+  /// Whatever is returned here is handed back to `restore_state` when the layout is restored
+  #[allow(unused_variables)]
+  fn save_state(&self, params: Self::Params<'_, '_>) -> Option<serde_json::Value> {
+    None
+  }
+
+  #[allow(unused_variables)]
+  fn restore_state(&mut self, params: Self::Params<'_, '_>, state: serde_json::Value) {}
+
   /// Used to prevent this Ui from appearing in the view menu
   ///
   /// Typically for Ui's that are programmatically created
@@ -269,6 +401,27 @@ pub trait Ui: RawUi {
   fn popout() -> bool {
     true
   }
+
+  /// Skips drawing the panel while the editor view is being navigated, for panels that are
+  /// expensive to draw, see [`LowLatencySettings`](crate::view::LowLatencySettings)
+  fn pausable() -> bool {
+    false
+  }
+
+  /// Whether the panel's tab is shown while the editor is in `state`, see
+  /// [`RawUi::visible_in`]
+  #[allow(unused_variables)]
+  fn visible_in(state: EditorState) -> bool {
+    true
+  }
+
+  /// Borrows the panel and its params from the world without checking that the params can't reach
+  /// the panel, for panels drawn every frame
+  ///
+  /// The params must not access the panel's own component
+  fn fast_path() -> bool {
+    false
+  }
 }
 
 impl<T> RawUi for T
@@ -325,6 +478,16 @@ where
     Self::get_entity_mut(entity, world, <Self as Ui>::on_despawn)
   }
 
+  fn save_state(entity: Entity, world: &mut World) -> Option<serde_json::Value> {
+    Self::get_entity(entity, world, <Self as Ui>::save_state)
+  }
+
+  fn restore_state(entity: Entity, world: &mut World, state: serde_json::Value) {
+    Self::get_entity_mut(entity, world, |this, params| {
+      this.restore_state(params, state);
+    })
+  }
+
   fn handle_tab_response(entity: Entity, world: &mut World, response: &egui::Response) {
     Self::get_entity_mut(entity, world, |this, params| {
       this.handle_tab_response(params, response);
@@ -346,6 +509,14 @@ where
   fn popout() -> bool {
     <Self as Ui>::popout()
   }
+
+  fn pausable() -> bool {
+    <Self as Ui>::pausable()
+  }
+
+  fn visible_in(state: EditorState) -> bool {
+    <Self as Ui>::visible_in(state)
+  }
 }
 
 #[derive(Clone)]
@@ -354,6 +525,8 @@ struct VTable {
   init: fn(&mut App),
   spawn: fn(&mut World) -> Entity,
   despawn: fn(Entity, &mut World),
+  save_state: fn(Entity, &mut World) -> Option<serde_json::Value>,
+  restore_state: fn(Entity, &mut World, serde_json::Value),
   title: fn(Entity, &mut World) -> egui::WidgetText,
   render: fn(Entity, &mut egui::Ui, &mut World),
   when_rendered: fn(Entity, &mut World),
@@ -365,6 +538,8 @@ struct VTable {
   can_clear: fn(Entity, &mut World) -> bool,
   unique: fn() -> bool,
   popout: fn() -> bool,
+  pausable: fn() -> bool,
+  visible_in: fn(EditorState) -> bool,
   count: fn(&mut World) -> usize,
 }
 
@@ -378,6 +553,8 @@ impl VTable {
       init: T::init,
       spawn: Self::spawn::<T>,
       despawn: Self::despawn::<T>,
+      save_state: T::save_state,
+      restore_state: T::restore_state,
       title: T::title,
       render: T::render,
       when_rendered: T::when_rendered,
@@ -389,6 +566,8 @@ impl VTable {
       can_clear: T::can_clear,
       unique: T::unique,
       popout: T::popout,
+      pausable: T::pausable,
+      visible_in: T::visible_in,
       count: Self::count::<T>,
     }
   }
@@ -425,6 +604,8 @@ impl VTable {
 struct TabViewer<'a> {
   world: RefCell<&'a mut World>,
   vtables: &'a mut HashMap<PersistentId, VTable>,
+  /// Workspace window the dock area is in, `None` for the primary window
+  window: Option<Entity>,
 }
 
 impl TabViewer<'_> {
@@ -451,26 +632,51 @@ impl egui_dock::TabViewer for TabViewer<'_> {
 
   fn title(&mut self, tab: &mut Self::Tab) -> egui::WidgetText {
     let vtable = self.vtable_of(*tab);
-    (vtable.title)(*tab, &mut self.world.borrow_mut())
+    let mut world = self.world.borrow_mut();
+    let title = (vtable.title)(*tab, &mut world);
+    let time = world.resource::<Time<Real>>().elapsed_secs_f64();
+    match world.get::<UiInfo>(*tab) {
+      Some(ui_info) => ui_info.decorate_title(title, time),
+      None => title,
+    }
   }
 
   #[profiling::function]
   fn ui(&mut self, ui: &mut egui::Ui, tab: &mut Self::Tab) {
     let vtable = self.vtable_of(*tab);
-    (vtable.render)(*tab, ui, &mut self.world.borrow_mut());
+    if (vtable.pausable)() && view::navigating_with_low_latency(&self.world.borrow()) {
+      ui.weak("Paused while the view is navigated");
+    } else {
+      (vtable.render)(*tab, ui, &mut self.world.borrow_mut());
+    }
+
+    let hovered = ui.ui_contains_pointer();
+    let clicked = hovered && ui.input(|input| input.pointer.any_click());
+    SessionStats::record_panel(
+      &mut self.world.borrow_mut(),
+      (vtable.name)(),
+      hovered,
+      clicked,
+    );
 
     self.ui_info(*tab, |ui_info| {
-      ui_info.hovered = ui.ui_contains_pointer();
+      ui_info.hovered = hovered;
       ui_info.rendered = true;
+      if ui_info.hovered {
+        ui_info.attention = false;
+      }
     });
   }
 
   fn add_popup(&mut self, ui: &mut egui::Ui, surface: SurfaceIndex, node: NodeIndex) {
     ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+    let editor_state = self.world.borrow().get_state::<EditorState>();
     let unique_tabs = self
       .vtables
       .iter()
       .filter(|(_, vtable)| (vtable.unique)() && !(vtable.hidden)())
+      .filter(|(_, vtable)| self.window.is_none() || (vtable.popout)())
+      .filter(|(_, vtable)| (vtable.visible_in)(editor_state))
       .map(|(id, vtable)| (id, (vtable.name)()))
       .sorted_by(|(_, a), (_, b)| a.cmp(b));
 
@@ -485,7 +691,7 @@ impl egui_dock::TabViewer for TabViewer<'_> {
       ui.add_enabled_ui(enabled, |ui| {
         if ui.checkbox(&mut exists, name).clicked() {
           let entity = (vtable.spawn)(&mut world);
-          world.send_event(AddUiEvent::new(surface, node, entity));
+          world.send_event(AddUiEvent::new(self.window, surface, node, entity));
         }
       });
     }
@@ -494,6 +700,8 @@ impl egui_dock::TabViewer for TabViewer<'_> {
       .vtables
       .iter()
       .filter(|(_, vtable)| !(vtable.unique)())
+      .filter(|(_, vtable)| self.window.is_none() || (vtable.popout)())
+      .filter(|(_, vtable)| (vtable.visible_in)(editor_state))
       .map(|(id, vtable)| (id, (vtable.name)()))
       .sorted_by(|(_, a), (_, b)| a.cmp(b));
 
@@ -503,7 +711,7 @@ impl egui_dock::TabViewer for TabViewer<'_> {
         if ui.button(name).clicked() {
           let mut world = self.world.borrow_mut();
           let entity = (vtable.spawn)(&mut world);
-          world.send_event(AddUiEvent::new(surface, node, entity));
+          world.send_event(AddUiEvent::new(self.window, surface, node, entity));
           ui.memory_mut(|mem| mem.close_popup());
         }
       }
@@ -553,9 +761,56 @@ impl egui_dock::TabViewer for TabViewer<'_> {
 }
 
 #[derive(Serialize, Deserialize)]
-struct LayoutState {
-  dock: DockState<Uuid>,
-  layouts: BTreeMap<String, DockState<Uuid>>,
+pub(crate) struct LayoutState {
+  dock: DockState<LayoutInfo>,
+  layouts: BTreeMap<String, DockState<LayoutInfo>>,
+  /// Layouts of the open workspace windows by name
+  #[serde(default)]
+  workspaces: BTreeMap<String, DockState<LayoutInfo>>,
+}
+
+/// What gets stored for each tab of a saved layout
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(from = "LayoutInfoRepr")]
+pub(crate) struct LayoutInfo {
+  id: Uuid,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  state: Option<serde_json::Value>,
+}
+
+impl LayoutInfo {
+  pub fn new(id: Uuid, state: Option<serde_json::Value>) -> Self {
+    Self { id, state }
+  }
+
+  pub fn id(&self) -> &Uuid {
+    &self.id
+  }
+
+  pub fn state(&self) -> Option<&serde_json::Value> {
+    self.state.as_ref()
+  }
+}
+
+/// Layouts saved before per instance state existed only stored the uuid of the tab
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LayoutInfoRepr {
+  Id(Uuid),
+  Info {
+    id: Uuid,
+    #[serde(default)]
+    state: Option<serde_json::Value>,
+  },
+}
+
+impl From<LayoutInfoRepr> for LayoutInfo {
+  fn from(value: LayoutInfoRepr) -> Self {
+    match value {
+      LayoutInfoRepr::Id(id) => Self::new(id, None),
+      LayoutInfoRepr::Info { id, state } => Self::new(id, state),
+    }
+  }
 }
 
 impl Saveable for LayoutState {
@@ -593,6 +848,61 @@ pub struct SelectedEntities(bevy_inspector::hierarchy::SelectedEntities);
 #[derive(Default, Deref, DerefMut, Component, Clone, Copy, Hash, PartialEq, Eq, Reflect, From)]
 pub struct PersistentId(#[reflect(ignore)] pub Uuid);
 
+/// Identifies a scene entity across scene reloads and editor restarts
+///
+/// Saved with the scene, assigned the first time a panel keeps a reference to the entity
+#[derive(Component, Clone, Copy, Hash, PartialEq, Eq, Debug, Reflect, Serialize, Deserialize)]
+#[reflect(Component)]
+pub struct PersistentEntityId(u128);
+
+impl PersistentEntityId {
+  /// The entity's id, assigning it one if it has none yet
+  pub fn get_or_insert(entity: Entity, world: &mut World) -> Self {
+    match world.get::<Self>(entity) {
+      Some(id) => *id,
+      None => {
+        let id = Self(Uuid::new_v4().as_u128());
+        world.entity_mut(entity).insert(id);
+        id
+      }
+    }
+  }
+}
+
+/// Reference to an entity held by a panel that outlives the current `Entity` of its target
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct EntityTarget {
+  id: PersistentEntityId,
+  #[serde(skip)]
+  entity: Option<Entity>,
+}
+
+impl EntityTarget {
+  pub fn new(entity: Entity, world: &mut World) -> Self {
+    Self {
+      id: PersistentEntityId::get_or_insert(entity, world),
+      entity: Some(entity),
+    }
+  }
+
+  /// The entity currently carrying the id, `None` if it is not in the world
+  pub fn resolve(&mut self, world: &mut World) -> Option<Entity> {
+    let cached = self
+      .entity
+      .filter(|entity| world.get::<PersistentEntityId>(*entity) == Some(&self.id));
+
+    if cached.is_none() {
+      let mut q_ids = world.query::<(Entity, &PersistentEntityId)>();
+      self.entity = q_ids
+        .iter(world)
+        .find(|(_, id)| **id == self.id)
+        .map(|(entity, _)| entity);
+    }
+
+    self.entity
+  }
+}
+
 /// Component that stores all ui components as children for organization
 #[derive(Component)]
 pub struct UiPanels;
@@ -0,0 +1,277 @@
+use crate::util;
+use bevy::{
+  ecs::component::ComponentId,
+  prelude::*,
+  reflect::{
+    serde::{TypedReflectDeserializer, TypedReflectSerializer},
+    TypeRegistry,
+  },
+};
+use bevy_egui::egui;
+use serde::de::DeserializeSeed;
+use std::any::TypeId;
+
+/// A component that was turned off in the inspector, kept as RON so it can be put back
+#[derive(Reflect, Clone, Debug)]
+pub struct StashedComponent {
+  pub type_path: String,
+  pub ron: String,
+}
+
+/// Components of the entity that are turned off, they are removed from the entity while stashed
+/// here and inserted again when turned back on
+#[derive(Component, Reflect, Default, Clone, Debug)]
+#[reflect(Component)]
+pub struct DisabledComponents {
+  pub stashed: Vec<StashedComponent>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ComponentToggleError {
+  #[error("'{0}' is not a registered component")]
+  NotAComponent(String),
+  #[error("'{0}' is part of the entity hierarchy and can't be turned off")]
+  Hierarchy(String),
+  #[error("entity {0} does not exist")]
+  MissingEntity(Entity),
+  #[error("'{0}' is not present")]
+  MissingComponent(String),
+  #[error("failed to serialize '{0}': {1}")]
+  Serialize(String, ron::Error),
+  #[error("failed to deserialize '{0}': {1}")]
+  Deserialize(String, ron::Error),
+}
+
+impl StashedComponent {
+  fn deserialize(
+    &self,
+    type_registry: &TypeRegistry,
+  ) -> Result<Box<dyn PartialReflect>, ComponentToggleError> {
+    let registration = type_registry
+      .get_with_type_path(&self.type_path)
+      .ok_or_else(|| ComponentToggleError::NotAComponent(self.type_path.clone()))?;
+
+    let mut deserializer = ron::de::Deserializer::from_str(&self.ron)
+      .map_err(|err| ComponentToggleError::Deserialize(self.type_path.clone(), err.code))?;
+
+    TypedReflectDeserializer::new(registration, type_registry)
+      .deserialize(&mut deserializer)
+      .map_err(|err| ComponentToggleError::Deserialize(self.type_path.clone(), err))
+  }
+
+  fn insert_into(
+    &self,
+    entity_mut: &mut EntityWorldMut,
+    type_registry: &TypeRegistry,
+  ) -> Result<(), ComponentToggleError> {
+    let reflect_component = type_registry
+      .get_with_type_path(&self.type_path)
+      .and_then(|registration| registration.data::<ReflectComponent>())
+      .ok_or_else(|| ComponentToggleError::NotAComponent(self.type_path.clone()))?;
+
+    let value = self.deserialize(type_registry)?;
+    reflect_component.insert(entity_mut, value.as_ref(), type_registry);
+
+    Ok(())
+  }
+}
+
+impl DisabledComponents {
+  /// Serializes the component into the entity's stash and removes it
+  pub fn disable(
+    world: &mut World,
+    entity: Entity,
+    type_id: TypeId,
+  ) -> Result<(), ComponentToggleError> {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+
+    let (registration, reflect_component) = type_registry
+      .get(type_id)
+      .and_then(|registration| Some((registration, registration.data::<ReflectComponent>()?)))
+      .ok_or_else(|| ComponentToggleError::NotAComponent(format!("{type_id:?}")))?;
+    let type_path = registration.type_info().type_path().to_string();
+
+    if util::is_hierarchy_component(type_id) {
+      return Err(ComponentToggleError::Hierarchy(type_path));
+    }
+
+    let Ok(entity_ref) = world.get_entity(entity) else {
+      return Err(ComponentToggleError::MissingEntity(entity));
+    };
+
+    let value = reflect_component
+      .reflect(entity_ref)
+      .ok_or_else(|| ComponentToggleError::MissingComponent(type_path.clone()))?;
+
+    let serializer = TypedReflectSerializer::new(value.as_partial_reflect(), &type_registry);
+    let ron = ron::to_string(&serializer)
+      .map_err(|err| ComponentToggleError::Serialize(type_path.clone(), err))?;
+
+    let mut entity_mut = world.entity_mut(entity);
+    reflect_component.remove(&mut entity_mut);
+
+    if !entity_mut.contains::<Self>() {
+      entity_mut.insert(Self::default());
+    }
+
+    let mut disabled = entity_mut.get_mut::<Self>().unwrap();
+    disabled
+      .stashed
+      .retain(|stashed| stashed.type_path != type_path);
+    disabled.stashed.push(StashedComponent { type_path, ron });
+
+    Ok(())
+  }
+
+  /// Inserts a stashed component back onto the entity, the stash is removed once it is empty
+  pub fn enable(
+    world: &mut World,
+    entity: Entity,
+    type_path: &str,
+  ) -> Result<(), ComponentToggleError> {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+
+    let Ok(mut entity_mut) = world.get_entity_mut(entity) else {
+      return Err(ComponentToggleError::MissingEntity(entity));
+    };
+
+    let Some(stashed) = entity_mut.get::<Self>().and_then(|disabled| {
+      disabled
+        .stashed
+        .iter()
+        .find(|stashed| stashed.type_path == type_path)
+        .cloned()
+    }) else {
+      return Err(ComponentToggleError::MissingComponent(
+        type_path.to_string(),
+      ));
+    };
+
+    stashed.insert_into(&mut entity_mut, &type_registry)?;
+
+    let mut disabled = entity_mut.get_mut::<Self>().unwrap();
+    disabled
+      .stashed
+      .retain(|stashed| stashed.type_path != type_path);
+    if disabled.stashed.is_empty() {
+      entity_mut.remove::<Self>();
+    }
+
+    Ok(())
+  }
+
+  /// Inserts the stashed components that pass `filter` into another entity, i.e. a scene being
+  /// saved, without changing this stash
+  pub(crate) fn apply_to(
+    &self,
+    entity_mut: &mut EntityWorldMut,
+    type_registry: &TypeRegistry,
+    filter: impl Fn(TypeId) -> bool,
+  ) {
+    for stashed in &self.stashed {
+      let included = type_registry
+        .get_with_type_path(&stashed.type_path)
+        .is_some_and(|registration| filter(registration.type_id()));
+
+      if !included {
+        continue;
+      }
+
+      if let Err(err) = stashed.insert_into(entity_mut, type_registry) {
+        error!("{err}");
+      }
+    }
+  }
+}
+
+/// Checkbox per reflected component of the entity, unchecking one stashes it in
+/// [`DisabledComponents`] and checking it again puts it back with the same values
+///
+/// Hierarchy components aren't listed, removing them would detach the entity from its family
+pub(crate) fn ui(ui: &mut egui::Ui, world: &mut World, entity: Entity) {
+  let Ok(entity_ref) = world.get_entity(entity) else {
+    return;
+  };
+
+  let type_registry = world.resource::<AppTypeRegistry>().clone();
+  let type_registry = type_registry.read();
+
+  let stash_id = world.component_id::<DisabledComponents>();
+
+  let mut enabled = entity_ref
+    .archetype()
+    .components()
+    .filter(|id| Some(*id) != stash_id)
+    .filter_map(|id: ComponentId| world.components().get_info(id)?.type_id())
+    .filter(|type_id| !util::is_hierarchy_component(*type_id))
+    .filter_map(|type_id| {
+      let registration = type_registry.get(type_id)?;
+      registration.data::<ReflectComponent>()?;
+      Some((
+        registration
+          .type_info()
+          .type_path_table()
+          .short_path()
+          .to_string(),
+        type_id,
+      ))
+    })
+    .collect::<Vec<_>>();
+  enabled.sort_by(|a, b| a.0.cmp(&b.0));
+
+  let disabled = entity_ref
+    .get::<DisabledComponents>()
+    .map(|disabled| {
+      disabled
+        .stashed
+        .iter()
+        .map(|stashed| {
+          let name = type_registry
+            .get_with_type_path(&stashed.type_path)
+            .map(|registration| registration.type_info().type_path_table().short_path())
+            .unwrap_or(&stashed.type_path);
+          (name.to_string(), stashed.type_path.clone())
+        })
+        .collect::<Vec<_>>()
+    })
+    .unwrap_or_default();
+
+  let mut to_disable = None;
+  let mut to_enable = None;
+
+  egui::CollapsingHeader::new("Toggle Components")
+    .id_salt(("toggle_components", entity))
+    .show(ui, |ui| {
+      for (name, type_id) in &enabled {
+        let mut checked = true;
+        if ui.checkbox(&mut checked, name).changed() {
+          to_disable = Some(*type_id);
+        }
+      }
+
+      for (name, type_path) in &disabled {
+        let mut checked = false;
+        if ui
+          .checkbox(&mut checked, name)
+          .on_hover_text("Disabled, check to put it back")
+          .changed()
+        {
+          to_enable = Some(type_path.clone());
+        }
+      }
+    });
+
+  drop(type_registry);
+
+  let result = match (to_disable, to_enable) {
+    (Some(type_id), _) => DisabledComponents::disable(world, entity, type_id),
+    (None, Some(type_path)) => DisabledComponents::enable(world, entity, &type_path),
+    (None, None) => Ok(()),
+  };
+
+  if let Err(err) = result {
+    error!("Failed to toggle component: {err}");
+  }
+}
@@ -0,0 +1,352 @@
+use bevy::prelude::*;
+use bevy_egui::egui;
+use bevy_inspector_egui::{
+  inspector_egui_impls::InspectorPrimitive, reflect_inspector::InspectorUi,
+};
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+
+const CURVE_HEIGHT: f32 = 120.0;
+const GRADIENT_HEIGHT: f32 = 24.0;
+const HANDLE_RADIUS: f32 = 5.0;
+const GRID_LINES: usize = 4;
+
+const HELP: &str = "Double-click to add a point, drag to move it, right-click to remove it";
+
+/// Piecewise linear curve through control points sorted by x, edited as a graph in the inspector
+#[derive(Reflect, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct FloatCurve {
+  pub points: Vec<Vec2>,
+}
+
+impl Default for FloatCurve {
+  fn default() -> Self {
+    Self {
+      points: vec![Vec2::ZERO, Vec2::ONE],
+    }
+  }
+}
+
+impl FloatCurve {
+  /// Value of the curve at `x`, clamped to the first and last point
+  pub fn sample(&self, x: f32) -> f32 {
+    let (Some(first), Some(last)) = (self.points.first(), self.points.last()) else {
+      return 0.0;
+    };
+
+    if x <= first.x {
+      return first.y;
+    }
+
+    self
+      .points
+      .windows(2)
+      .find(|pair| x <= pair[1].x)
+      .map(|pair| {
+        let span = pair[1].x - pair[0].x;
+        let t = if span > 0.0 {
+          (x - pair[0].x) / span
+        } else {
+          1.0
+        };
+        pair[0].y.lerp(pair[1].y, t)
+      })
+      .unwrap_or(last.y)
+  }
+
+  fn sort(&mut self) {
+    self.points.sort_by(|a, b| a.x.total_cmp(&b.x));
+  }
+}
+
+impl InspectorPrimitive for FloatCurve {
+  fn ui(&mut self, ui: &mut egui::Ui, _: &dyn Any, id: egui::Id, _: InspectorUi<'_, '_>) -> bool {
+    curve_ui(ui, id, self)
+  }
+
+  fn ui_readonly(&self, ui: &mut egui::Ui, _: &dyn Any, id: egui::Id, _: InspectorUi<'_, '_>) {
+    let mut copy = self.clone();
+    ui.add_enabled_ui(false, |ui| curve_ui(ui, id, &mut copy));
+  }
+}
+
+/// Colors at positions between 0 and 1, blended linearly between stops
+#[derive(Reflect, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ColorGradient {
+  pub stops: Vec<(f32, Color)>,
+}
+
+impl Default for ColorGradient {
+  fn default() -> Self {
+    Self {
+      stops: vec![(0.0, Color::BLACK), (1.0, Color::WHITE)],
+    }
+  }
+}
+
+impl ColorGradient {
+  /// Color of the gradient at `t`, clamped to the first and last stop
+  pub fn sample(&self, t: f32) -> Color {
+    let (Some(first), Some(last)) = (self.stops.first(), self.stops.last()) else {
+      return Color::NONE;
+    };
+
+    if t <= first.0 {
+      return first.1;
+    }
+
+    self
+      .stops
+      .windows(2)
+      .find(|pair| t <= pair[1].0)
+      .map(|pair| {
+        let span = pair[1].0 - pair[0].0;
+        let f = if span > 0.0 {
+          (t - pair[0].0) / span
+        } else {
+          1.0
+        };
+        let from = LinearRgba::from(pair[0].1);
+        let to = LinearRgba::from(pair[1].1);
+        Color::from(from.mix(&to, f))
+      })
+      .unwrap_or(last.1)
+  }
+
+  fn sort(&mut self) {
+    self.stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+  }
+}
+
+impl InspectorPrimitive for ColorGradient {
+  fn ui(&mut self, ui: &mut egui::Ui, _: &dyn Any, id: egui::Id, _: InspectorUi<'_, '_>) -> bool {
+    gradient_ui(ui, id, self)
+  }
+
+  fn ui_readonly(&self, ui: &mut egui::Ui, _: &dyn Any, id: egui::Id, _: InspectorUi<'_, '_>) {
+    let mut copy = self.clone();
+    ui.add_enabled_ui(false, |ui| gradient_ui(ui, id, &mut copy));
+  }
+}
+
+/// Range of the curve's points, always covering 0 to 1 on both axes
+fn curve_bounds(curve: &FloatCurve) -> egui::Rect {
+  let (min, max) = curve
+    .points
+    .iter()
+    .fold((Vec2::ZERO, Vec2::ONE), |(min, max), point| {
+      (min.min(*point), max.max(*point))
+    });
+  egui::Rect::from_min_max(egui::pos2(min.x, min.y), egui::pos2(max.x, max.y))
+}
+
+/// Graph of the curve with draggable control points, returns whether it changed
+pub fn curve_ui(ui: &mut egui::Ui, id: egui::Id, curve: &mut FloatCurve) -> bool {
+  let size = egui::vec2(ui.available_width(), CURVE_HEIGHT);
+  let (rect, response) = ui.allocate_exact_size(size, egui::Sense::click());
+  let response = response.on_hover_text(HELP);
+
+  // y grows upwards in the curve and downwards on screen
+  let bounds = curve_bounds(curve);
+  let to_screen = egui::emath::RectTransform::from_to(
+    egui::Rect::from_x_y_ranges(bounds.x_range(), bounds.max.y..=bounds.min.y),
+    rect.shrink(HANDLE_RADIUS),
+  );
+  let from_screen = to_screen.inverse();
+
+  let visuals = ui.style().interact(&response);
+  let painter = ui.painter_at(rect);
+  painter.rect(
+    rect,
+    visuals.rounding,
+    ui.visuals().extreme_bg_color,
+    visuals.bg_stroke,
+  );
+
+  let grid_stroke = ui.visuals().widgets.noninteractive.bg_stroke;
+  for i in 1..GRID_LINES {
+    let f = i as f32 / GRID_LINES as f32;
+    let x = egui::lerp(rect.x_range(), f);
+    let y = egui::lerp(rect.y_range(), f);
+    painter.vline(x, rect.y_range(), grid_stroke);
+    painter.hline(rect.x_range(), y, grid_stroke);
+  }
+
+  let mut changed = false;
+  let mut remove = None;
+
+  for (i, point) in curve.points.iter_mut().enumerate() {
+    let center = to_screen * egui::pos2(point.x, point.y);
+    let handle = egui::Rect::from_center_size(center, egui::Vec2::splat(HANDLE_RADIUS * 2.0));
+    let handle_response = ui.interact(
+      handle,
+      id.with(("curve_point", i)),
+      egui::Sense::click_and_drag(),
+    );
+
+    if handle_response.dragged() {
+      let dragged = from_screen * (center + handle_response.drag_delta());
+      *point = Vec2::new(dragged.x, dragged.y);
+      changed = true;
+    }
+
+    if handle_response.secondary_clicked() {
+      remove = Some(i);
+    }
+
+    handle_response.on_hover_text(format!("{:.2}, {:.2}", point.x, point.y));
+  }
+
+  // the curve needs two points to have a shape
+  if let Some(i) = remove.filter(|_| curve.points.len() > 2) {
+    curve.points.remove(i);
+    changed = true;
+  }
+
+  if response.double_clicked() {
+    if let Some(pos) = response.interact_pointer_pos() {
+      let pos = from_screen * pos;
+      curve.points.push(Vec2::new(pos.x, pos.y));
+      changed = true;
+    }
+  }
+
+  if changed {
+    curve.sort();
+  }
+
+  let line = curve
+    .points
+    .iter()
+    .map(|point| to_screen * egui::pos2(point.x, point.y))
+    .collect::<Vec<_>>();
+
+  painter.add(egui::Shape::line(
+    line.clone(),
+    ui.visuals().selection.stroke,
+  ));
+
+  for center in line {
+    painter.circle(center, HANDLE_RADIUS, visuals.bg_fill, visuals.fg_stroke);
+  }
+
+  changed
+}
+
+fn to_egui(color: Color) -> egui::Color32 {
+  let [r, g, b, a] = color.to_srgba().to_u8_array();
+  egui::Color32::from_rgba_unmultiplied(r, g, b, a)
+}
+
+/// Bar showing the gradient with a draggable handle per stop and a color picker for the selected
+/// stop, returns whether it changed
+pub fn gradient_ui(ui: &mut egui::Ui, id: egui::Id, gradient: &mut ColorGradient) -> bool {
+  let selected_id = id.with("gradient_selected");
+  let mut selected = ui.data(|data| data.get_temp::<usize>(selected_id));
+
+  let size = egui::vec2(ui.available_width(), GRADIENT_HEIGHT + HANDLE_RADIUS * 2.0);
+  let (rect, response) = ui.allocate_exact_size(size, egui::Sense::click());
+  let response = response.on_hover_text(HELP);
+
+  let bar = egui::Rect::from_min_size(rect.min, egui::vec2(rect.width(), GRADIENT_HEIGHT));
+  let x_of = |t: f32| egui::lerp(bar.x_range(), t.clamp(0.0, 1.0));
+  let t_of = |x: f32| egui::remap_clamp(x, bar.x_range(), 0.0..=1.0);
+
+  let mut mesh = egui::Mesh::default();
+  let mut stops = gradient.stops.clone();
+  if let (Some(first), Some(last)) = (stops.first().copied(), stops.last().copied()) {
+    stops.insert(0, (0.0, first.1));
+    stops.push((1.0, last.1));
+  }
+  for pair in stops.windows(2) {
+    let (left, right) = (to_egui(pair[0].1), to_egui(pair[1].1));
+    let index = mesh.vertices.len() as u32;
+    mesh.colored_vertex(egui::pos2(x_of(pair[0].0), bar.top()), left);
+    mesh.colored_vertex(egui::pos2(x_of(pair[0].0), bar.bottom()), left);
+    mesh.colored_vertex(egui::pos2(x_of(pair[1].0), bar.top()), right);
+    mesh.colored_vertex(egui::pos2(x_of(pair[1].0), bar.bottom()), right);
+    mesh.add_triangle(index, index + 1, index + 2);
+    mesh.add_triangle(index + 2, index + 1, index + 3);
+  }
+
+  let painter = ui.painter_at(rect);
+  painter.add(mesh);
+  painter.rect_stroke(bar, 0.0, ui.visuals().widgets.noninteractive.bg_stroke);
+
+  let mut changed = false;
+  let mut remove = None;
+
+  for (i, (t, color)) in gradient.stops.iter_mut().enumerate() {
+    let center = egui::pos2(x_of(*t), bar.bottom() + HANDLE_RADIUS);
+    let handle = egui::Rect::from_center_size(center, egui::Vec2::splat(HANDLE_RADIUS * 2.0));
+    let handle_response = ui.interact(
+      handle,
+      id.with(("gradient_stop", i)),
+      egui::Sense::click_and_drag(),
+    );
+
+    if handle_response.clicked() || handle_response.drag_started() {
+      selected = Some(i);
+    }
+
+    if handle_response.dragged() {
+      *t = t_of(center.x + handle_response.drag_delta().x);
+      changed = true;
+    }
+
+    if handle_response.secondary_clicked() {
+      remove = Some(i);
+    }
+
+    let stroke = if selected == Some(i) {
+      ui.visuals().selection.stroke
+    } else {
+      ui.visuals().widgets.inactive.fg_stroke
+    };
+    painter.circle(center, HANDLE_RADIUS, to_egui(*color), stroke);
+  }
+
+  // a gradient without stops has no color at all
+  if let Some(i) = remove.filter(|_| gradient.stops.len() > 1) {
+    gradient.stops.remove(i);
+    selected = None;
+    changed = true;
+  }
+
+  if response.double_clicked() {
+    if let Some(pos) = response.interact_pointer_pos() {
+      let t = t_of(pos.x);
+      gradient.stops.push((t, gradient.sample(t)));
+      selected = Some(gradient.stops.len() - 1);
+      changed = true;
+    }
+  }
+
+  if let Some((t, color)) = selected.and_then(|i| gradient.stops.get_mut(i)) {
+    ui.horizontal(|ui| {
+      let mut rgba = color.to_srgba().to_f32_array();
+      if ui.color_edit_button_rgba_unmultiplied(&mut rgba).changed() {
+        *color = Srgba::from_f32_array(rgba).into();
+        changed = true;
+      }
+      changed |= ui
+        .add(egui::DragValue::new(t).range(0.0..=1.0).speed(0.01))
+        .changed();
+    });
+  }
+
+  if changed {
+    // keep the same stop selected once they are back in order
+    let selected_stop = selected.and_then(|i| gradient.stops.get(i).copied());
+    gradient.sort();
+    selected =
+      selected_stop.and_then(|stop| gradient.stops.iter().position(|other| *other == stop));
+  }
+
+  ui.data_mut(|data| match selected {
+    Some(i) => data.insert_temp(selected_id, i),
+    None => data.remove::<usize>(selected_id),
+  });
+
+  changed
+}
@@ -0,0 +1,268 @@
+use bevy::{
+  prelude::*,
+  reflect::{
+    DynamicEnum, DynamicStruct, DynamicTuple, DynamicVariant, Enum, TypeInfo, TypeRegistry, Typed,
+    VariantInfo,
+  },
+};
+use bevy_egui::egui;
+use bevy_inspector_egui::{
+  inspector_egui_impls::InspectorEguiImpl,
+  reflect_inspector::{InspectorUi, ProjectorReflect},
+};
+use std::any::Any;
+
+const MAX_LIST_HEIGHT: f32 = 300.0;
+
+/// Types edited as a set of named flags with a checkbox each, e.g. a `bitflags` struct
+pub trait InspectorFlags: Reflect + Typed {
+  /// Name and bits of every flag
+  const FLAGS: &'static [(&'static str, u64)];
+
+  fn bits(&self) -> u64;
+
+  fn set_bits(&mut self, bits: u64);
+}
+
+pub trait InspectorAppExt {
+  /// Edits the enum with a combo box that can be searched by variant name instead of the default
+  /// dropdown, for enums with a lot of variants like key codes
+  fn register_searchable_enum<T: Enum + Reflect + Typed>(&mut self) -> &mut Self;
+
+  /// Edits the type with a checkbox per flag
+  fn register_flags<T: InspectorFlags>(&mut self) -> &mut Self;
+}
+
+impl InspectorAppExt for App {
+  fn register_searchable_enum<T: Enum + Reflect + Typed>(&mut self) -> &mut Self {
+    insert_impl::<T>(
+      self,
+      InspectorEguiImpl::new(
+        searchable_enum_ui::<T>,
+        searchable_enum_ui_readonly::<T>,
+        no_multi_edit,
+      ),
+    )
+  }
+
+  fn register_flags<T: InspectorFlags>(&mut self) -> &mut Self {
+    insert_impl::<T>(
+      self,
+      InspectorEguiImpl::new(flags_ui::<T>, flags_ui_readonly::<T>, no_multi_edit),
+    )
+  }
+}
+
+fn insert_impl<T: Reflect + Typed>(app: &mut App, inspector_impl: InspectorEguiImpl) -> &mut App {
+  let type_registry = app.world().resource::<AppTypeRegistry>().clone();
+  let mut type_registry = type_registry.write();
+
+  match type_registry.get_mut(std::any::TypeId::of::<T>()) {
+    Some(registration) => registration.insert(inspector_impl),
+    None => warn!(
+      "{} must be registered before its inspector editor",
+      T::type_path()
+    ),
+  }
+
+  app
+}
+
+fn no_multi_edit(
+  ui: &mut egui::Ui,
+  _: &dyn Any,
+  _: egui::Id,
+  _: InspectorUi<'_, '_>,
+  _: &mut [&mut dyn PartialReflect],
+  _: &dyn ProjectorReflect,
+) -> bool {
+  ui.weak("Editing multiple values is not supported");
+  false
+}
+
+/// Value of the variant with every field set to its default, `None` if a field has no default
+fn default_variant(variant: &VariantInfo, type_registry: &TypeRegistry) -> Option<DynamicEnum> {
+  let default_of = |type_id| {
+    type_registry
+      .get_type_data::<ReflectDefault>(type_id)
+      .map(|default| default.default().into_partial_reflect())
+  };
+
+  let dynamic_variant = match variant {
+    VariantInfo::Unit(_) => DynamicVariant::Unit,
+    VariantInfo::Tuple(info) => {
+      let mut tuple = DynamicTuple::default();
+      for field in info.iter() {
+        tuple.insert_boxed(default_of(field.type_id())?);
+      }
+      DynamicVariant::Tuple(tuple)
+    }
+    VariantInfo::Struct(info) => {
+      let mut dynamic_struct = DynamicStruct::default();
+      for field in info.iter() {
+        dynamic_struct.insert_boxed(field.name(), default_of(field.type_id())?);
+      }
+      DynamicVariant::Struct(dynamic_struct)
+    }
+  };
+
+  Some(DynamicEnum::new(variant.name(), dynamic_variant))
+}
+
+fn searchable_enum_ui<T: Enum + Reflect + Typed>(
+  value: &mut dyn Any,
+  ui: &mut egui::Ui,
+  _: &dyn Any,
+  id: egui::Id,
+  mut env: InspectorUi<'_, '_>,
+) -> bool {
+  let value = value.downcast_mut::<T>().unwrap();
+  let TypeInfo::Enum(info) = T::type_info() else {
+    return false;
+  };
+
+  let search_id = id.with("enum_search");
+  let current = value.variant_name().to_string();
+  let mut selected = None;
+
+  egui::ComboBox::from_id_salt(id)
+    .selected_text(&current)
+    .show_ui(ui, |ui| {
+      let mut search = ui
+        .data(|data| data.get_temp::<String>(search_id))
+        .unwrap_or_default();
+
+      ui.add(egui::TextEdit::singleline(&mut search).hint_text("Search"))
+        .request_focus();
+
+      let needle = search.to_lowercase();
+      egui::ScrollArea::vertical()
+        .max_height(MAX_LIST_HEIGHT)
+        .show(ui, |ui| {
+          for variant in info
+            .iter()
+            .filter(|variant| variant.name().to_lowercase().contains(&needle))
+          {
+            let constructed = default_variant(variant, env.type_registry);
+            let response = ui
+              .add_enabled(
+                constructed.is_some(),
+                egui::SelectableLabel::new(variant.name() == current, variant.name()),
+              )
+              .on_disabled_hover_text("Some of this variant's fields have no default value");
+
+            if response.clicked() {
+              selected = constructed;
+            }
+          }
+        });
+
+      ui.data_mut(|data| data.insert_temp(search_id, search));
+    });
+
+  let mut changed = false;
+
+  if let Some(variant) = selected.filter(|variant| variant.variant_name() != current) {
+    match value.try_apply(&variant) {
+      Ok(()) => changed = true,
+      Err(err) => error!("Failed to change variant: {err}"),
+    }
+  }
+
+  for i in 0..value.field_len() {
+    let name = value
+      .name_at(i)
+      .map(str::to_string)
+      .unwrap_or_else(|| i.to_string());
+    let Some(field) = value.field_at_mut(i) else {
+      continue;
+    };
+    ui.horizontal(|ui| {
+      ui.label(name);
+      changed |= env.ui_for_reflect_with_options(field, ui, id.with(i), &());
+    });
+  }
+
+  changed
+}
+
+fn searchable_enum_ui_readonly<T: Enum + Reflect + Typed>(
+  value: &dyn Any,
+  ui: &mut egui::Ui,
+  _: &dyn Any,
+  id: egui::Id,
+  mut env: InspectorUi<'_, '_>,
+) {
+  let value = value.downcast_ref::<T>().unwrap();
+
+  ui.add_enabled_ui(false, |ui| {
+    egui::ComboBox::from_id_salt(id)
+      .selected_text(value.variant_name())
+      .show_ui(ui, |_| ());
+  });
+
+  for i in 0..value.field_len() {
+    let name = value
+      .name_at(i)
+      .map(str::to_string)
+      .unwrap_or_else(|| i.to_string());
+    let Some(field) = value.field_at(i) else {
+      continue;
+    };
+    ui.horizontal(|ui| {
+      ui.label(name);
+      env.ui_for_reflect_readonly_with_options(field, ui, id.with(i), &());
+    });
+  }
+}
+
+fn flags_checkboxes(ui: &mut egui::Ui, flags: &[(&str, u64)], bits: &mut u64) -> bool {
+  let mut changed = false;
+
+  ui.horizontal_wrapped(|ui| {
+    for (name, flag) in flags {
+      let mut set = *bits & flag == *flag;
+      if ui.checkbox(&mut set, *name).changed() {
+        if set {
+          *bits |= flag;
+        } else {
+          *bits &= !flag;
+        }
+        changed = true;
+      }
+    }
+  });
+
+  changed
+}
+
+fn flags_ui<T: InspectorFlags>(
+  value: &mut dyn Any,
+  ui: &mut egui::Ui,
+  _: &dyn Any,
+  _: egui::Id,
+  _: InspectorUi<'_, '_>,
+) -> bool {
+  let value = value.downcast_mut::<T>().unwrap();
+
+  let mut bits = value.bits();
+  let changed = flags_checkboxes(ui, T::FLAGS, &mut bits);
+  if changed {
+    value.set_bits(bits);
+  }
+
+  changed
+}
+
+fn flags_ui_readonly<T: InspectorFlags>(
+  value: &dyn Any,
+  ui: &mut egui::Ui,
+  _: &dyn Any,
+  _: egui::Id,
+  _: InspectorUi<'_, '_>,
+) {
+  let value = value.downcast_ref::<T>().unwrap();
+
+  let mut bits = value.bits();
+  ui.add_enabled_ui(false, |ui| flags_checkboxes(ui, T::FLAGS, &mut bits));
+}
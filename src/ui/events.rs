@@ -1,8 +1,4 @@
-use super::{
-  managers::UiManager,
-  misc::{DockExtensions, MissingUi},
-  PersistentId,
-};
+use super::managers::UiManager;
 use bevy::prelude::*;
 use derive_new::new;
 use egui_dock::{DockState, NodeIndex, SurfaceIndex};
@@ -14,28 +10,30 @@ pub struct SaveLayoutEvent {
 }
 
 impl SaveLayoutEvent {
-  pub fn on_event(
-    mut events: EventReader<Self>,
-    mut ui_manager: ResMut<UiManager>,
-    q_uuids: Query<&PersistentId, Without<MissingUi>>,
-    q_missing: Query<&MissingUi>,
-  ) {
-    for save_event in events.read() {
-      let dock = save_event.dock.decouple(&q_uuids, &q_missing);
-      ui_manager.save_layout(&save_event.name, dock);
-    }
+  pub fn on_event(world: &mut World) {
+    let save_events = world
+      .resource_mut::<Events<Self>>()
+      .drain()
+      .collect::<Vec<_>>();
+
+    world.resource_scope(|world, mut ui_manager: Mut<UiManager>| {
+      for save_event in save_events {
+        let dock = ui_manager.decouple(&save_event.dock, world);
+        ui_manager.save_layout(save_event.name, dock);
+      }
+    });
   }
 }
 
 #[derive(Event, new, Clone, Copy)]
-pub struct AddUiEvent(SurfaceIndex, NodeIndex, Entity);
+pub struct AddUiEvent(Option<Entity>, SurfaceIndex, NodeIndex, Entity);
 
 impl AddUiEvent {
   pub fn on_event(mut events: EventReader<Self>, mut ui_manager: ResMut<UiManager>) {
     for event in events.read() {
-      let AddUiEvent(surface, node, tab) = *event;
+      let AddUiEvent(window, surface, node, tab) = *event;
 
-      let Some(surface) = ui_manager.surface_mut(surface) else {
+      let Some(surface) = ui_manager.surface_mut(window, surface) else {
         continue;
       };
 
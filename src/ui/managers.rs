@@ -1,31 +1,75 @@
 use super::{
   events::SaveLayoutEvent,
-  misc::{DockExtensions, MissingUi, UiComponentExtensions},
+  menus::{self, MenuRegistry},
+  misc::{DockExtensions, MissingUi, UiComponentExtensions, UiInfo},
   prebuilt::{
-    assets::Assets, components, debug::DebugMenu, editor_view::EditorView, hierarchy::Hierarchy,
-    inspector::Inspector, prefabs::Prefabs, resources::Resources,
+    array_tool::ArrayTool,
+    asset_reloads::AssetReloads,
+    assets::Assets,
+    components,
+    debug::DebugMenu,
+    editor_view::EditorView,
+    find_in_world::FindInWorld,
+    hierarchy::Hierarchy,
+    inspector::Inspector,
+    material_editor::MaterialEditor,
+    prefabs::Prefabs,
+    render_layers::RenderLayersEditor,
+    resources::Resources,
+    session_stats::{SessionStats, SessionStatsPanel},
+    settings::Settings,
+    tile_brush::TileBrush,
+    timeline::Timeline,
+    watches::Watches,
   },
-  InspectorSelection, LayoutState, PersistentId, RawUi, TabViewer, VTable,
+  status_bar::{StatusBar, StatusMessage},
+  InspectorSelection, LayoutInfo, LayoutState, PersistentId, RawUi, TabViewer, VTable,
 };
 use crate::{
-  cache::Cache,
+  assets,
+  cache::{Cache, CacheRecovery},
+  docs::Reference,
+  prefab_editor::PrefabEditor,
+  read_only::{self, ReadOnly},
+  safe_mode::{SafeMode, SafeModeReason, TrustPrompt},
+  session::SessionPrompts,
+  startup::StartupProgress,
+  testing::TestRunConfig,
   util::WorldExtensions,
-  view::{self, ActiveEditorCamera, EditorCamera},
+  view::{
+    self,
+    camera_preview::GamePreviewSettings,
+    name_labels::{self, NameLabelSettings},
+    placement::{self, PlacementSettings},
+    rulers::{self, GuideSettings},
+    view3d::OrbitSettings,
+    ActiveEditorCamera, FocusedPane,
+  },
   EditorState,
 };
 use bevy::{
   prelude::*,
   utils::{hashbrown::hash_map, HashMap},
+  window::PrimaryWindow,
 };
 use bevy_egui::egui::{self, TextBuffer};
-use egui_dock::{DockArea, DockState, NodeIndex, Surface, SurfaceIndex};
-use std::{any::TypeId, cell::RefCell, collections::BTreeMap};
+use egui_dock::{DockArea, DockState, NodeIndex, Surface, SurfaceIndex, TabIndex};
+use itertools::Itertools;
+use std::{any::TypeId, cell::RefCell, collections::BTreeMap, path::Path, time::SystemTime};
 use uuid::Uuid;
 
 #[derive(Resource)]
 pub(crate) struct UiManager {
   state: DockState<Entity>,
 
+  workspaces: Vec<Workspace>,
+
+  /// Tabs taken out of their dock while their panels aren't shown in the editor's state
+  hidden_tabs: Vec<HiddenTab>,
+
+  /// Editor state the docks were last filtered for, `None` when a dock was replaced since
+  shown_for: Option<EditorState>,
+
   vtables: HashMap<PersistentId, VTable>,
 
   layout_manager: LayoutManager,
@@ -37,6 +81,9 @@ impl Default for UiManager {
   fn default() -> Self {
     let mut this = Self {
       state: DockState::new(Vec::new()),
+      workspaces: Vec::new(),
+      hidden_tabs: Vec::new(),
+      shown_for: None,
       vtables: default(),
       id: egui::Id::new(TypeId::of::<Self>()),
       layout_manager: default(),
@@ -50,6 +97,16 @@ impl Default for UiManager {
     this.register::<Prefabs>();
     this.register::<Resources>();
     this.register::<Assets>();
+    this.register::<RenderLayersEditor>();
+    this.register::<Settings>();
+    this.register::<Watches>();
+    this.register::<FindInWorld>();
+    this.register::<Timeline>();
+    this.register::<SessionStatsPanel>();
+    this.register::<AssetReloads>();
+    this.register::<ArrayTool>();
+    this.register::<MaterialEditor>();
+    this.register::<TileBrush>();
 
     this
   }
@@ -57,19 +114,32 @@ impl Default for UiManager {
 
 impl UiManager {
   pub fn restore_or_init(&mut self, world: &mut World) {
-    let (state, layouts) = world
+    let (state, layouts, workspaces) = world
       .resource_scope(|world, cache: Mut<Cache>| {
         cache.get::<LayoutState>().map(|layout| {
           (
             DockState::restore(&layout.dock, &self.vtables, world),
             layout.layouts,
+            layout.workspaces,
           )
         })
       })
-      .unwrap_or_else(|| (self.default_dock_state(world), default()));
+      .unwrap_or_else(|| (self.default_dock_state(world), default(), default()));
+
+    // named layouts are only applied when picked, so they're kept
+    let (state, workspaces) = if SafeMode::enabled_in(world) {
+      (self.default_dock_state(world), default())
+    } else {
+      (state, workspaces)
+    };
 
     self.state = state;
     self.layout_manager.layouts = layouts;
+
+    for (name, dock) in workspaces {
+      let state = DockState::restore(&dock, &self.vtables, world);
+      self.open_workspace(name, state, world);
+    }
   }
 
   pub fn register<T: RawUi>(&mut self) {
@@ -78,14 +148,29 @@ impl UiManager {
 
   pub fn render(&mut self, world: &mut World) {
     let Ok(ctx) = world
-      .query::<&mut bevy_egui::EguiContext>()
+      .query_filtered::<&mut bevy_egui::EguiContext, With<PrimaryWindow>>()
       .get_single_mut(world)
       .map(|ctx| ctx.get().clone())
     else {
       return;
     };
 
+    let finished = world.resource_scope(|_, mut progress: Mut<StartupProgress>| {
+      if progress.finished() {
+        progress.errors_ui(&ctx);
+      } else {
+        progress.ui(&ctx);
+      }
+      progress.finished()
+    });
+
+    if !finished {
+      return;
+    }
+
     self.modal_ui(&ctx, world);
+    self.update_tab_visibility(world);
+    self.focus_requested_tabs(world);
 
     egui::CentralPanel::default()
       .frame(
@@ -98,9 +183,14 @@ impl UiManager {
           self.menu_bar_ui(ui, world);
         });
 
+        egui::TopBottomPanel::bottom("status_bar").show_inside(ui, |ui| {
+          StatusBar::ui(ui, world);
+        });
+
         let mut tab_viewer = TabViewer {
           vtables: &mut self.vtables,
           world: RefCell::new(world),
+          window: None,
         };
 
         DockArea::new(&mut self.state)
@@ -109,30 +199,344 @@ impl UiManager {
           .show_add_popup(true)
           .show_inside(ui, &mut tab_viewer);
       });
+
+    self.render_workspaces(world);
+  }
+
+  fn render_workspaces(&mut self, world: &mut World) {
+    let mut closed = Vec::new();
+
+    for workspace in &mut self.workspaces {
+      // the window gets its egui context a frame after it is spawned
+      let Some(ctx) = world
+        .get_mut::<bevy_egui::EguiContext>(workspace.window)
+        .map(|mut ctx| ctx.get_mut().clone())
+      else {
+        continue;
+      };
+
+      egui::CentralPanel::default()
+        .frame(egui::Frame::central_panel(&ctx.style()).inner_margin(0.0))
+        .show(&ctx, |ui| {
+          egui::menu::bar(ui, |ui| {
+            Self::panels_menu(ui, &self.vtables, &mut workspace.state, world);
+
+            if ui.button("Close Window").clicked() {
+              closed.push(workspace.window);
+            }
+          });
+
+          if workspace.state.iter_all_tabs().next().is_none() {
+            ui.centered_and_justified(|ui| {
+              ui.weak("Add panels from the Panels menu");
+            });
+            return;
+          }
+
+          let mut tab_viewer = TabViewer {
+            vtables: &mut self.vtables,
+            world: RefCell::new(world),
+            window: Some(workspace.window),
+          };
+
+          DockArea::new(&mut workspace.state)
+            .id(self.id.with(&workspace.name))
+            .show_add_buttons(true)
+            .show_add_popup(true)
+            .show_inside(ui, &mut tab_viewer);
+        });
+    }
+
+    for window in closed {
+      self.close_workspace(window, world);
+    }
+  }
+
+  /// Panels that can be added to a workspace window, views that render through a camera only work
+  /// in the primary window
+  fn panels_menu(
+    ui: &mut egui::Ui,
+    vtables: &HashMap<PersistentId, VTable>,
+    state: &mut DockState<Entity>,
+    world: &mut World,
+  ) {
+    ui.menu_button("Panels", |ui| {
+      let editor_state = world.get_state::<EditorState>();
+      let panels = vtables
+        .values()
+        .filter(|vtable| (vtable.popout)() && !(vtable.hidden)())
+        .filter(|vtable| (vtable.visible_in)(editor_state))
+        .sorted_by_key(|vtable| (vtable.name)());
+
+      for vtable in panels {
+        let exists = (vtable.unique)() && (vtable.count)(world) > 0;
+        if ui
+          .add_enabled(!exists, egui::Button::new((vtable.name)()))
+          .clicked()
+        {
+          let entity = (vtable.spawn)(world);
+          state.push_to_focused_leaf(entity);
+          ui.close_menu();
+        }
+      }
+    });
+  }
+
+  fn focus_requested_tabs(&mut self, world: &mut World) {
+    let mut q_ui_infos = world.query::<(Entity, &mut UiInfo)>();
+    for (entity, mut ui_info) in q_ui_infos.iter_mut(world) {
+      if !std::mem::take(&mut ui_info.focus_requested) {
+        continue;
+      }
+
+      let docks = std::iter::once(&mut self.state).chain(
+        self
+          .workspaces
+          .iter_mut()
+          .map(|workspace| &mut workspace.state),
+      );
+
+      for dock in docks {
+        if let Some(location) = dock.find_tab(&entity) {
+          dock.set_active_tab(location);
+          break;
+        }
+      }
+    }
+  }
+
+  /// Takes the tabs of panels that aren't shown in the editor's current state out of their docks
+  /// and puts back the ones that are, in the slot they were taken from
+  fn update_tab_visibility(&mut self, world: &mut World) {
+    let editor_state = world.get_state::<EditorState>();
+    if self.shown_for == Some(editor_state) {
+      return;
+    }
+    self.shown_for = Some(editor_state);
+
+    // shown first so they return to the indices they were hidden from
+    for hidden in std::mem::take(&mut self.hidden_tabs) {
+      if !(self.vtable_of(hidden.entity, world).visible_in)(editor_state) {
+        self.hidden_tabs.push(hidden);
+        continue;
+      }
+
+      if let Some(dock) = self.dock_mut(hidden.window) {
+        hidden.insert_into(dock);
+      }
+    }
+
+    let windows = std::iter::once(None)
+      .chain(
+        self
+          .workspaces
+          .iter()
+          .map(|workspace| Some(workspace.window)),
+      )
+      .collect::<Vec<_>>();
+
+    for window in windows {
+      let Some(dock) = self.dock(window) else {
+        continue;
+      };
+
+      let mut q_ids = world.query::<&PersistentId>();
+      let mut hidden = Vec::new();
+      for ((surface, node), entity) in dock.iter_all_tabs() {
+        let shown = q_ids
+          .get(world, *entity)
+          .map_or(true, |id| (self.vtables[id].visible_in)(editor_state));
+        if shown {
+          continue;
+        }
+
+        let tab = dock[surface][node]
+          .tabs()
+          .and_then(|tabs| tabs.iter().position(|tab| tab == entity));
+        if let Some(tab) = tab {
+          hidden.push(HiddenTab {
+            window,
+            surface,
+            node,
+            tab: TabIndex(tab),
+            entity: *entity,
+          });
+        }
+      }
+
+      let Some(dock) = self.dock_mut(window) else {
+        continue;
+      };
+
+      // removed from the node rather than the tree so an emptied leaf keeps its place in the
+      // layout, last first so the indices of the others stay valid
+      for hidden in hidden.iter().rev() {
+        dock[hidden.surface][hidden.node].remove_tab(hidden.tab);
+      }
+
+      self.hidden_tabs.extend(hidden);
+    }
+  }
+
+  /// The dock with its hidden tabs in place, for saving its layout
+  fn with_hidden_tabs(&self, window: Option<Entity>) -> Option<DockState<Entity>> {
+    let mut dock = self.dock(window)?.clone();
+    for hidden in self
+      .hidden_tabs
+      .iter()
+      .filter(|hidden| hidden.window == window)
+    {
+      hidden.insert_into(&mut dock);
+    }
+    Some(dock)
+  }
+
+  fn despawn_hidden_tabs(&mut self, window: Option<Entity>, world: &mut World) {
+    let (despawned, kept) = std::mem::take(&mut self.hidden_tabs)
+      .into_iter()
+      .partition::<Vec<_>, _>(|hidden| hidden.window == window);
+    self.hidden_tabs = kept;
+
+    for hidden in despawned {
+      let vtable = self.vtable_of(hidden.entity, world);
+      (vtable.despawn)(hidden.entity, world);
+    }
+  }
+
+  /// Opens an empty workspace in a new window, named after the first free number
+  fn new_workspace(&mut self, world: &mut World) {
+    let name = (1..)
+      .map(|n| format!("Workspace {n}"))
+      .find(|name| {
+        self
+          .workspaces
+          .iter()
+          .all(|workspace| workspace.name != *name)
+      })
+      .unwrap();
+
+    self.open_workspace(name, DockState::new(Vec::new()), world);
+  }
+
+  fn open_workspace(&mut self, name: String, state: DockState<Entity>, world: &mut World) {
+    let window = world
+      .spawn((
+        Name::new(format!("{name} Window")),
+        Window {
+          title: format!("Bevy Editor - {name}"),
+          ..default()
+        },
+      ))
+      .id();
+
+    self.workspaces.push(Workspace {
+      name,
+      window,
+      state,
+    });
+    self.shown_for = None;
+  }
+
+  /// Despawns the workspace's panels along with its window, returns whether `window` belonged to
+  /// a workspace
+  pub(super) fn close_workspace(&mut self, window: Entity, world: &mut World) -> bool {
+    let Some(index) = self
+      .workspaces
+      .iter()
+      .position(|workspace| workspace.window == window)
+    else {
+      return false;
+    };
+
+    let workspace = self.workspaces.remove(index);
+    for entity in workspace.state.iter_all_tabs().map(|(_, entity)| *entity) {
+      let vtable = self.vtable_of(entity, world);
+      (vtable.despawn)(entity, world);
+    }
+    self.despawn_hidden_tabs(Some(window), world);
+
+    if let Ok(window) = world.get_entity_mut(window) {
+      window.despawn();
+    }
+
+    true
+  }
+
+  pub fn save_workspaces(&self, world: &mut World) -> BTreeMap<String, DockState<LayoutInfo>> {
+    self
+      .workspaces
+      .iter()
+      .map(|workspace| {
+        (
+          workspace.name.clone(),
+          self.decouple(
+            &self
+              .with_hidden_tabs(Some(workspace.window))
+              .unwrap_or_else(|| workspace.state.clone()),
+            world,
+          ),
+        )
+      })
+      .collect()
   }
 
   pub(super) fn vtables(&self) -> hash_map::Values<'_, PersistentId, VTable> {
     self.vtables.values()
   }
 
-  pub fn save_current_layout(
-    &self,
-    q_uuids: &Query<&PersistentId, Without<MissingUi>>,
-    q_missing: &Query<&MissingUi>,
-  ) -> DockState<Uuid> {
-    self.state.decouple(q_uuids, q_missing)
+  pub fn save_current_layout(&self, world: &mut World) -> DockState<LayoutInfo> {
+    self.decouple(&self.current_layout(), world)
+  }
+
+  /// The primary dock with its hidden tabs in place
+  fn current_layout(&self) -> DockState<Entity> {
+    self
+      .with_hidden_tabs(None)
+      .unwrap_or_else(|| self.state.clone())
+  }
+
+  pub fn decouple(&self, dock: &DockState<Entity>, world: &mut World) -> DockState<LayoutInfo> {
+    dock.decouple(&self.vtables, world)
   }
 
-  pub fn save_layout(&mut self, name: impl Into<String>, dock: DockState<Uuid>) {
+  pub fn save_layout(&mut self, name: impl Into<String>, dock: DockState<LayoutInfo>) {
     self.layout_manager.layouts.insert(name.into(), dock);
   }
 
-  pub fn saved_layouts(&self) -> &BTreeMap<String, DockState<Uuid>> {
+  pub fn saved_layouts(&self) -> &BTreeMap<String, DockState<LayoutInfo>> {
     &self.layout_manager.layouts
   }
 
-  pub fn surface_mut(&mut self, index: SurfaceIndex) -> Option<&mut Surface<Entity>> {
-    self.state.get_surface_mut(index)
+  /// Surface of the primary dock area, or of the workspace in `window`
+  pub fn surface_mut(
+    &mut self,
+    window: Option<Entity>,
+    index: SurfaceIndex,
+  ) -> Option<&mut Surface<Entity>> {
+    self.dock_mut(window)?.get_surface_mut(index)
+  }
+
+  /// The primary dock area, or the dock of the workspace in `window`
+  fn dock(&self, window: Option<Entity>) -> Option<&DockState<Entity>> {
+    match window {
+      Some(window) => self
+        .workspaces
+        .iter()
+        .find(|workspace| workspace.window == window)
+        .map(|workspace| &workspace.state),
+      None => Some(&self.state),
+    }
+  }
+
+  fn dock_mut(&mut self, window: Option<Entity>) -> Option<&mut DockState<Entity>> {
+    match window {
+      Some(window) => self
+        .workspaces
+        .iter_mut()
+        .find(|workspace| workspace.window == window)
+        .map(|workspace| &mut workspace.state),
+      None => Some(&mut self.state),
+    }
   }
 
   pub(super) fn vtable_of(&self, entity: Entity, world: &mut World) -> &VTable {
@@ -146,7 +550,9 @@ impl UiManager {
       let vtable = self.vtable_of(entity, world);
       (vtable.despawn)(entity, world);
     }
+    self.despawn_hidden_tabs(None, world);
     self.state = new_state;
+    self.shown_for = None;
   }
 
   fn default_dock_state(&mut self, world: &mut World) -> DockState<Entity> {
@@ -184,20 +590,38 @@ impl UiManager {
   }
 
   fn menu_bar_ui(&mut self, ui: &mut egui::Ui, world: &mut World) {
-    ui.menu_button("Tools", |ui| {
-      if ui.button("Generate UUID").clicked() {
-        ui.output_mut(|output| {
-          output.copied_text = Uuid::new_v4().to_string();
-        });
-      }
-    });
+    let mut menus = world.resource::<MenuRegistry>().contributed_menus();
+    menus.push((menus::TOOLS_ORDER, String::from("Tools")));
+    menus.push((menus::VIEW_ORDER, String::from("View")));
+    menus.sort();
 
-    ui.menu_button("View", |ui| {
-      self.layout_menu(ui, world);
-      self.camera_menu(ui, world);
-    });
+    for (_, menu) in menus {
+      ui.menu_button(&menu, |ui| match menu.as_str() {
+        "Tools" => {
+          self.tools_menu(ui, world);
+          MenuRegistry::items_ui(ui, world, &menu, true);
+        }
+        "View" => {
+          self.layout_menu(ui, world);
+          self.camera_menu(ui, world);
+          MenuRegistry::items_ui(ui, world, &menu, true);
+        }
+        _ => MenuRegistry::items_ui(ui, world, &menu, false),
+      });
+    }
+
+    if world.get_state::<EditorState>() == EditorState::Editing {
+      ui.add_enabled_ui(ReadOnly::writable(world), |ui| {
+        self.create_menu(ui, world);
+      })
+      .response
+      .on_disabled_hover_text(read_only::REASON);
+    }
 
     match world.get_state::<EditorState>() {
+      EditorState::Editing if world.resource::<PrefabEditor>().editing().is_some() => {
+        self.prefab_edit_controls(ui, world);
+      }
       EditorState::Editing => {
         self.play_button(ui, world);
       }
@@ -208,6 +632,42 @@ impl UiManager {
     }
   }
 
+  fn tools_menu(&mut self, ui: &mut egui::Ui, world: &mut World) {
+    if ui.button("Generate UUID").clicked() {
+      ui.output_mut(|output| {
+        output.copied_text = Uuid::new_v4().to_string();
+      });
+    }
+
+    if ui
+      .button("Generate Reference Docs")
+      .on_hover_text(
+        "Write the registered components, prefabs and panels to docs/reference.md next to the executable",
+      )
+      .clicked()
+    {
+      let panels = self
+        .vtables
+        .values()
+        .filter(|vtable| !(vtable.hidden)())
+        .map(|vtable| (vtable.name)())
+        .collect();
+
+      let message = match Reference::new(world, panels).write() {
+        Ok(file) => StatusMessage::info(format!("Wrote {}", file.display())),
+        Err(err) => StatusMessage::error(format!("Failed to write reference docs: {err}")),
+      };
+      world.send_event(message);
+      ui.close_menu();
+    }
+
+    let mut read_only = world.resource_mut::<ReadOnly>();
+    let mut enabled = read_only.enabled();
+    if ui.checkbox(&mut enabled, "Read-Only Mode").changed() {
+      read_only.set_enabled(enabled);
+    }
+  }
+
   fn layout_menu(&mut self, ui: &mut egui::Ui, world: &mut World) {
     ui.menu_button("Layouts", |ui| {
       if ui.button("Save Layout").clicked() {
@@ -233,6 +693,44 @@ impl UiManager {
       if ui.button("Restore Default").clicked() {
         self.layout_manager.show_confirm_reset_modal = true;
       }
+
+      ui.separator();
+
+      if ui
+        .button("New Window")
+        .on_hover_text("Open a workspace with its own layout in a separate window")
+        .clicked()
+      {
+        self.new_workspace(world);
+        ui.close_menu();
+      }
+    });
+  }
+
+  fn create_menu(&self, ui: &mut egui::Ui, world: &mut World) {
+    ui.menu_button("Create", |ui| {
+      let mut settings = world.resource_mut::<PlacementSettings>();
+      let mut snap_to_ground = settings.snap_to_ground;
+      if ui.checkbox(&mut snap_to_ground, "Snap To Ground").changed() {
+        settings.snap_to_ground = snap_to_ground;
+      }
+
+      ui.separator();
+
+      let mut prefab_ids = world
+        .resource::<assets::Prefabs>()
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+
+      prefab_ids.sort();
+
+      for id in prefab_ids {
+        if ui.button(&id).clicked() {
+          placement::spawn_prefab(id, world);
+          ui.close_menu();
+        }
+      }
     });
   }
 
@@ -241,9 +739,20 @@ impl UiManager {
       if world.get_state::<EditorState>() == EditorState::Editing {
         self.camera_selector(ui, world);
 
+        let mut game_preview = world.resource_mut::<GamePreviewSettings>();
+        ui.checkbox(&mut game_preview.enabled, "Game Camera Preview")
+          .on_hover_text("Show what the game camera sees in a corner of the editor view");
+
+        name_labels::menu_ui(ui, &mut world.resource_mut::<NameLabelSettings>());
+
         let editor_camera_type = world.get_state::<ActiveEditorCamera>();
         if editor_camera_type == ActiveEditorCamera::Cam3D {
           self.look_at_origin_button(ui, world);
+          OrbitSettings::menu_ui(ui, &mut world.resource_mut::<OrbitSettings>());
+        } else {
+          ui.menu_button("Guides", |ui| {
+            rulers::menu_ui(ui, &mut world.resource_mut::<GuideSettings>());
+          });
         }
 
         self.entity_commands(ui, world, editor_camera_type);
@@ -263,7 +772,7 @@ impl UiManager {
 
   fn look_at_origin_button(&self, ui: &mut egui::Ui, world: &mut World) {
     if ui.button("Look At Origin").clicked() {
-      let mut q = world.query_filtered::<&mut Transform, With<EditorCamera>>();
+      let mut q = world.query_filtered::<&mut Transform, With<FocusedPane>>();
       for mut cam in q.iter_mut(world) {
         cam.look_at(Vec3::ZERO, view::UP);
       }
@@ -311,7 +820,7 @@ impl UiManager {
 
         let entity_pos = transform.translation;
 
-        let mut q = world.query_filtered::<&mut Transform, With<EditorCamera>>();
+        let mut q = world.query_filtered::<&mut Transform, With<FocusedPane>>();
         for mut cam in q.iter_mut(world) {
           cam.translation = entity_pos;
         }
@@ -328,7 +837,7 @@ impl UiManager {
 
         let entity_pos = transform.translation;
 
-        let mut q = world.query_filtered::<&mut Transform, With<EditorCamera>>();
+        let mut q = world.query_filtered::<&mut Transform, With<FocusedPane>>();
         for mut cam_transform in q.iter_mut(world) {
           cam_transform.look_at(entity_pos, view::UP);
         }
@@ -336,21 +845,281 @@ impl UiManager {
     }
   }
 
+  fn prefab_edit_controls(&self, ui: &mut egui::Ui, world: &mut World) {
+    let editor = world.resource::<PrefabEditor>();
+    let Some(prefab) = editor.editing() else {
+      return;
+    };
+
+    ui.label(format!("Editing prefab '{prefab}'"));
+
+    if ui
+      .add_enabled(
+        editor.can_save() && ReadOnly::writable(world),
+        egui::Button::new("Save"),
+      )
+      .on_disabled_hover_text(read_only::REASON)
+      .clicked()
+    {
+      if let Err(err) = PrefabEditor::save(world) {
+        error!("Failed to save prefab: {err}");
+      }
+    }
+
+    if ui.button("Exit").clicked() {
+      PrefabEditor::exit(world);
+    }
+  }
+
   fn play_button(&self, ui: &mut egui::Ui, world: &mut World) {
     if ui.button("▶").clicked() {
+      SessionStats::record_command(world, "Play");
       world.set_state(EditorState::Testing);
     }
+
+    if ui
+      .button("▶ Here")
+      .on_hover_text("Play with the play start entities moved to the editor camera")
+      .clicked()
+    {
+      SessionStats::record_command(world, "Play From Here");
+      TestRunConfig::play_from_here(world);
+    }
+
+    ui.menu_button("⚙", |ui| TestRunConfig::options_ui(ui, world))
+      .response
+      .on_hover_text("Play options");
   }
 
   fn pause_button(&self, ui: &mut egui::Ui, world: &mut World) {
     if ui.button("⏸").clicked() {
+      SessionStats::record_command(world, "Stop");
       world.set_state(EditorState::Editing);
     }
+
+    let seed = world.resource::<TestRunConfig>().seed;
+    ui.weak(format!("Seed {seed}"));
   }
 
   fn modal_ui(&mut self, ctx: &egui::Context, world: &mut World) {
     self.save_layout_modal_ui(ctx, world);
     self.layout_reset_modal_ui(ctx, world);
+    Self::cache_recovery_modal_ui(ctx, world);
+    Self::exit_modal_ui(ctx, world);
+    Self::restore_session_modal_ui(ctx, world);
+    Self::safe_mode_modal_ui(ctx, world);
+    Self::trust_modal_ui(ctx, world);
+  }
+
+  fn safe_mode_modal_ui(ctx: &egui::Context, world: &mut World) {
+    let safe_mode = world.resource::<SafeMode>();
+    let (Some(reason), true) = (safe_mode.reason(), safe_mode.prompt_open()) else {
+      return;
+    };
+
+    let mut continue_clicked = false;
+
+    let open = components::Dialog::new("Safe Mode").open(ctx, true, |ui| {
+      ui.label(match reason {
+        SafeModeReason::Requested => "The editor was launched with --safe-mode.",
+        SafeModeReason::Crashed => "The editor did not finish starting up last time.",
+      });
+      ui.label(
+        "The saved layout, editor cameras, selection and last scene were not restored, and the \
+         project's extensions are not running.",
+      );
+      ui.weak("Restart the editor normally once the problem is fixed.");
+      ui.separator();
+      continue_clicked = ui.button("Continue").clicked();
+    });
+
+    if continue_clicked || !open {
+      world.resource_mut::<SafeMode>().close_prompt();
+    }
+  }
+
+  fn trust_modal_ui(ctx: &egui::Context, world: &mut World) {
+    let prompt = world.resource::<TrustPrompt>();
+    if !prompt.is_open() {
+      return;
+    }
+
+    let reasons = prompt.reasons().to_vec();
+    let mut answer = None;
+
+    components::Dialog::new("Trust This Project?").open(ctx, true, |ui| {
+      ui.label("Trusting the project lets the editor run code that comes with it:");
+      for reason in &reasons {
+        ui.label(format!("• {reason}"));
+      }
+      ui.weak("Only trust projects from sources you know. This can be changed in Settings.");
+      ui.separator();
+
+      ui.horizontal(|ui| {
+        if ui.button("Trust").clicked() {
+          answer = Some(true);
+        }
+        if ui.button("Don't Trust").clicked() {
+          answer = Some(false);
+        }
+      });
+    });
+
+    if let Some(trusted) = answer {
+      TrustPrompt::answer(world, trusted);
+    }
+  }
+
+  fn exit_modal_ui(ctx: &egui::Context, world: &mut World) {
+    let confirming = world.resource::<SessionPrompts>().confirming_exit();
+    if !confirming {
+      return;
+    }
+
+    let warnings = SessionPrompts::exit_warnings(world);
+    let mut exit_clicked = false;
+    let mut cancel_clicked = false;
+
+    let open = components::Dialog::new("Exit Editor?").open(ctx, confirming, |ui| {
+      if warnings.is_empty() {
+        ui.label("There is nothing left unsaved.");
+      }
+
+      for warning in &warnings {
+        ui.label(format!("• {warning}"));
+      }
+
+      ui.weak("Layouts and settings are saved automatically.");
+      ui.separator();
+
+      ui.horizontal(|ui| {
+        exit_clicked = ui.button("Exit").clicked();
+        cancel_clicked = ui.button("Cancel").clicked();
+      });
+    });
+
+    if exit_clicked {
+      world.set_state(EditorState::Exiting);
+    }
+
+    if exit_clicked || cancel_clicked || !open {
+      world.resource_mut::<SessionPrompts>().cancel_exit();
+    }
+  }
+
+  fn restore_session_modal_ui(ctx: &egui::Context, world: &mut World) {
+    let Some(scene) = world
+      .resource::<SessionPrompts>()
+      .restorable_scene()
+      .cloned()
+    else {
+      return;
+    };
+
+    let mut restore_clicked = false;
+    let mut dismiss_clicked = false;
+
+    let open = components::Dialog::new("Restore Previous Session?").open(ctx, true, |ui| {
+      ui.label(format!(
+        "{} was open when the editor last exited.",
+        scene.display()
+      ));
+      ui.horizontal(|ui| {
+        restore_clicked = ui.button("Reopen").clicked();
+        dismiss_clicked = ui.button("Start Fresh").clicked();
+      });
+    });
+
+    if restore_clicked {
+      SessionPrompts::restore(world);
+    } else if dismiss_clicked || !open {
+      world.resource_mut::<SessionPrompts>().dismiss_restore();
+    }
+  }
+
+  fn cache_recovery_modal_ui(ctx: &egui::Context, world: &mut World) {
+    let Some(mut recovery) = world.get_resource_mut::<CacheRecovery>() else {
+      return;
+    };
+
+    let mut exit_clicked = false;
+    let mut close_clicked = false;
+    let mut restore = None;
+
+    let open =
+      components::Dialog::new("Editor Cache Corrupted").open(ctx, recovery.is_open(), |ui| {
+        ui.label(format!(
+          "The editor cache could not be read, layouts and view settings were reset: {}",
+          recovery.error()
+        ));
+        ui.weak(format!(
+          "The unreadable file was kept at {}",
+          recovery.corrupted_file().display()
+        ));
+
+        ui.separator();
+
+        if recovery.restored() {
+          ui.label("The backup will be used the next time the editor starts.");
+          ui.horizontal(|ui| {
+            exit_clicked = ui.button("Exit Now").clicked();
+            close_clicked = ui.button("Later").clicked();
+          });
+          return;
+        }
+
+        if recovery.backups().is_empty() {
+          ui.label("There are no backups to restore.");
+        }
+
+        for backup in recovery.backups() {
+          ui.horizontal(|ui| {
+            if ui.button("Restore").clicked() {
+              restore = Some(backup.clone());
+            }
+            ui.label(Self::backup_label(backup));
+          });
+        }
+
+        if let Some(err) = recovery.restore_error() {
+          ui.colored_label(egui::Color32::RED, format!("Failed to restore {err}"));
+        }
+
+        close_clicked = ui.button("Keep Defaults").clicked();
+      });
+
+    if let Some(backup) = restore {
+      recovery.restore(&backup);
+    }
+
+    if close_clicked || !open {
+      recovery.close();
+    }
+
+    if exit_clicked {
+      world.set_state(EditorState::Exiting);
+    }
+  }
+
+  /// Backups are named after the unix time they were taken at
+  fn backup_label(backup: &Path) -> String {
+    backup
+      .file_stem()
+      .and_then(|stem| stem.to_str())
+      .and_then(|stem| stem.parse::<u64>().ok())
+      .and_then(|secs| {
+        SystemTime::UNIX_EPOCH
+          .elapsed()
+          .ok()
+          .map(|now| now.as_secs().saturating_sub(secs))
+      })
+      .map(|age| match age {
+        0..60 => "Just now".to_string(),
+        60..3600 => format!("{} minutes ago", age / 60),
+        3600..86400 => format!("{} hours ago", age / 3600),
+        _ => format!("{} days ago", age / 86400),
+      })
+      .unwrap_or_else(|| backup.display().to_string())
   }
 
   fn save_layout_modal_ui(&mut self, ctx: &egui::Context, world: &mut World) {
@@ -370,7 +1139,7 @@ impl UiManager {
             .filter(|response| response.clicked())
             .then(|| {
               let name = self.layout_manager.save_name_text.take();
-              world.send_event(SaveLayoutEvent::new(name, self.state.clone()));
+              world.send_event(SaveLayoutEvent::new(name, self.current_layout()));
               save_clicked = true;
             });
         });
@@ -409,10 +1178,48 @@ impl UiManager {
   }
 }
 
+/// A dock area in its own window, i.e. for authoring panels on a second monitor
+struct Workspace {
+  name: String,
+  window: Entity,
+  state: DockState<Entity>,
+}
+
+/// Where a tab was in its dock before it was hidden
+struct HiddenTab {
+  /// `None` for the primary window
+  window: Option<Entity>,
+  surface: SurfaceIndex,
+  node: NodeIndex,
+  tab: TabIndex,
+  entity: Entity,
+}
+
+impl HiddenTab {
+  /// Puts the tab back in its leaf, or in the focused one if the layout changed so that the leaf
+  /// is gone
+  fn insert_into(&self, dock: &mut DockState<Entity>) {
+    let leaf = dock
+      .get_surface_mut(self.surface)
+      .and_then(Surface::node_tree_mut)
+      .filter(|tree| self.node.0 < tree.len())
+      .map(|tree| &mut tree[self.node])
+      .filter(|node| node.is_leaf());
+
+    match leaf {
+      Some(node) => {
+        let tab = self.tab.0.min(node.tabs_count());
+        node.insert_tab(TabIndex(tab), self.entity);
+      }
+      None => dock.push_to_focused_leaf(self.entity),
+    }
+  }
+}
+
 #[derive(Default)]
 struct LayoutManager {
   save_name_text: String,
   show_save_layout_modal: bool,
   show_confirm_reset_modal: bool,
-  layouts: BTreeMap<String, DockState<Uuid>>,
+  layouts: BTreeMap<String, DockState<LayoutInfo>>,
 }
@@ -0,0 +1,161 @@
+use bevy::prelude::*;
+use bevy_egui::egui;
+
+/// Order of the editor's own top level menus, contributed menus default to being placed after them
+pub(crate) const TOOLS_ORDER: i32 = 100;
+pub(crate) const VIEW_ORDER: i32 = 200;
+const DEFAULT_ORDER: i32 = 300;
+
+type MenuAction = dyn Fn(&mut World) + Send + Sync;
+
+/// Command contributed to a menu of the editor's menu bar
+pub struct MenuItem {
+  label: String,
+  order: i32,
+  action: Box<MenuAction>,
+  enabled: Option<fn(&World) -> bool>,
+}
+
+impl MenuItem {
+  /// `action` runs when the item is clicked, the [`MenuRegistry`] is taken out of the world while
+  /// it runs
+  pub fn new(
+    label: impl Into<String>,
+    action: impl Fn(&mut World) + Send + Sync + 'static,
+  ) -> Self {
+    Self {
+      label: label.into(),
+      order: 0,
+      action: Box::new(action),
+      enabled: None,
+    }
+  }
+
+  /// Items are sorted by order, then by the order they were added in
+  pub fn with_order(mut self, order: i32) -> Self {
+    self.order = order;
+    self
+  }
+
+  /// The item is greyed out whenever `enabled` returns false
+  pub fn with_enabled(mut self, enabled: fn(&World) -> bool) -> Self {
+    self.enabled = Some(enabled);
+    self
+  }
+}
+
+enum MenuEntry {
+  Item(MenuItem),
+  Separator(i32),
+}
+
+impl MenuEntry {
+  fn order(&self) -> i32 {
+    match self {
+      Self::Item(item) => item.order,
+      Self::Separator(order) => *order,
+    }
+  }
+}
+
+struct Menu {
+  name: String,
+  order: i32,
+  entries: Vec<MenuEntry>,
+}
+
+/// Items added to the menu bar by games and extensions embedding the editor
+///
+/// Items added to "Tools" or "View" are listed after the editor's own items of those menus, any
+/// other name creates a new top level menu, i.e. "File"
+#[derive(Resource, Default)]
+pub struct MenuRegistry {
+  menus: Vec<Menu>,
+}
+
+impl MenuRegistry {
+  pub fn add_item(&mut self, menu: &str, item: MenuItem) -> &mut Self {
+    self.insert(menu, MenuEntry::Item(item));
+    self
+  }
+
+  pub fn add_separator(&mut self, menu: &str, order: i32) -> &mut Self {
+    self.insert(menu, MenuEntry::Separator(order));
+    self
+  }
+
+  /// Places a contributed top level menu relative to "Tools" (100) and "View" (200)
+  pub fn set_menu_order(&mut self, menu: &str, order: i32) -> &mut Self {
+    self.menu_mut(menu).order = order;
+    self
+  }
+
+  fn menu_mut(&mut self, name: &str) -> &mut Menu {
+    let index = match self.menus.iter().position(|menu| menu.name == name) {
+      Some(index) => index,
+      None => {
+        let order = match name {
+          "Tools" => TOOLS_ORDER,
+          "View" => VIEW_ORDER,
+          _ => DEFAULT_ORDER,
+        };
+        self.menus.push(Menu {
+          name: name.to_string(),
+          order,
+          entries: Vec::new(),
+        });
+        self.menus.len() - 1
+      }
+    };
+
+    &mut self.menus[index]
+  }
+
+  fn insert(&mut self, menu: &str, entry: MenuEntry) {
+    let entries = &mut self.menu_mut(menu).entries;
+    let index = entries.partition_point(|existing| existing.order() <= entry.order());
+    entries.insert(index, entry);
+  }
+
+  /// Top level menus that aren't built into the editor, with their order
+  pub(crate) fn contributed_menus(&self) -> Vec<(i32, String)> {
+    self
+      .menus
+      .iter()
+      .filter(|menu| !matches!(menu.name.as_str(), "Tools" | "View"))
+      .map(|menu| (menu.order, menu.name.clone()))
+      .collect()
+  }
+
+  /// Draws the contributed items of the menu, preceded by a separator if `separate` is set and
+  /// there is anything to draw
+  pub(crate) fn items_ui(ui: &mut egui::Ui, world: &mut World, menu: &str, separate: bool) {
+    world.resource_scope(|world, registry: Mut<Self>| {
+      let Some(menu) = registry.menus.iter().find(|m| m.name == menu) else {
+        return;
+      };
+
+      if separate && !menu.entries.is_empty() {
+        ui.separator();
+      }
+
+      for entry in &menu.entries {
+        match entry {
+          MenuEntry::Separator(_) => {
+            ui.separator();
+          }
+          MenuEntry::Item(item) => {
+            let enabled = item.enabled.is_none_or(|enabled| enabled(world));
+            if ui
+              .add_enabled(enabled, egui::Button::new(&item.label))
+              .clicked()
+            {
+              (item.action)(world);
+              ui.close_menu();
+            }
+          }
+        }
+      }
+    });
+  }
+}
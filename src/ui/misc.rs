@@ -1,21 +1,37 @@
-use super::{PersistentId, RawUi, Ui, VTable};
+use super::{LayoutInfo, PersistentId, RawUi, Ui, VTable};
 use bevy::{
-  ecs::system::{SystemParam, SystemState},
+  ecs::{
+    entity::EntityHashMap,
+    system::{SystemParam, SystemState},
+  },
   prelude::*,
   utils::HashMap,
 };
 use bevy_egui::egui::{self, text::LayoutJob};
-use derive_more::derive::Deref;
 use egui_dock::DockState;
+use std::any::Any;
 use uuid::{uuid, Uuid};
 
+const ATTENTION_BLINK_SECS: f64 = 0.5;
+const ATTENTION_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 143, 0);
+
 #[derive(SystemParam)]
 pub struct NoParams;
 
+/// Marker drawn after the title of a tab
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Badge {
+  Dot,
+  Count(usize),
+}
+
 #[derive(Component, Default)]
 pub struct UiInfo {
   pub(super) rendered: bool,
   pub(super) hovered: bool,
+  pub(super) badge: Option<Badge>,
+  pub(super) attention: bool,
+  pub(super) focus_requested: bool,
 }
 
 impl UiInfo {
@@ -26,6 +42,48 @@ impl UiInfo {
   pub fn hovered(&self) -> bool {
     self.hovered
   }
+
+  pub fn badge(&self) -> Option<Badge> {
+    self.badge
+  }
+
+  pub fn set_badge(&mut self, badge: impl Into<Option<Badge>>) {
+    self.badge = badge.into();
+  }
+
+  /// Blinks the tab title until the panel is hovered
+  pub fn request_attention(&mut self) {
+    self.attention = true;
+  }
+
+  pub fn wants_attention(&self) -> bool {
+    self.attention
+  }
+
+  /// Brings the tab to the front of its node on the next frame
+  pub fn request_focus(&mut self) {
+    self.focus_requested = true;
+  }
+
+  pub(super) fn decorate_title(&self, title: egui::WidgetText, time: f64) -> egui::WidgetText {
+    let badge = match self.badge {
+      Some(Badge::Dot) => " ●".to_string(),
+      Some(Badge::Count(count)) => format!(" ({count})"),
+      None => String::new(),
+    };
+
+    if badge.is_empty() && !self.attention {
+      return title;
+    }
+
+    let text = egui::RichText::new(format!("{}{badge}", title.text()));
+
+    if self.attention && (time / ATTENTION_BLINK_SECS) as u64 % 2 == 0 {
+      text.color(ATTENTION_COLOR).strong().into()
+    } else {
+      text.into()
+    }
+  }
 }
 
 pub(super) trait UiComponentExtensions {
@@ -39,10 +97,56 @@ where
   const VTABLE: VTable = VTable::new::<Self>();
 }
 
-type UiParams<'w, 's, T> = UiComponentState<<T as Ui>::Params<'w, 's>>;
+type UiParams<T> = SystemState<<T as Ui>::Params<'static, 'static>>;
+
+/// Param state of every panel by entity, kept beside the panels so neither has to move while a
+/// callback runs
+#[derive(Resource, Default)]
+pub(super) struct UiParamStates(EntityHashMap<Box<dyn ParamState>>);
+
+trait ParamState: Send + Sync {
+  fn apply(&mut self, world: &mut World);
+
+  fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<P> ParamState for SystemState<P>
+where
+  P: SystemParam + 'static,
+{
+  fn apply(&mut self, world: &mut World) {
+    SystemState::apply(self, world);
+  }
 
+  fn as_any_mut(&mut self) -> &mut dyn Any {
+    self
+  }
+}
+
+impl UiParamStates {
+  /// Applies the commands the panels queued this frame and drops the state of despawned panels
+  pub(super) fn apply(world: &mut World) {
+    // applying may spawn panels, which register their state while this is taken
+    let mut states = std::mem::take(&mut world.resource_mut::<Self>().0);
+
+    for state in states.values_mut() {
+      state.apply(world);
+    }
+
+    states.retain(|entity, _| world.get_entity(*entity).is_ok());
+    world.resource_mut::<Self>().0.extend(states);
+  }
+}
+
+/// Runs [`Ui`] callbacks with the panel and its system params
+///
+/// The param state lives in [`UiParamStates`] and the panel stays on its entity, both are borrowed
+/// in place when the params can't reach the panel's own component. Params that can, such as `&World`,
+/// get the panel moved out of its entity for the duration of the call instead. Commands queued
+/// through the params are applied once per frame
+///
 /// # Safety
-/// Cannot access the world mutably in the system params
+/// Panels that opt into [`Ui::fast_path`] skip the check that the params don't access the panel
 /// Though it is on the user to not query for a mutable reference to themselves when they also have a self reference
 pub unsafe trait UiExtensions: Ui {
   fn get_entity<T>(
@@ -50,13 +154,22 @@ pub unsafe trait UiExtensions: Ui {
     world: &mut World,
     f: impl FnOnce(&Self, Self::Params<'_, '_>) -> T,
   ) -> T {
-    let mut q = world.query::<(&Self, &mut UiParams<Self>)>();
-    let world_cell = world.as_unsafe_world_cell();
-    let (this, mut params) = q
-      .get_mut(unsafe { world_cell.world_mut() }, entity)
-      .unwrap();
-    let params = params.get_mut(unsafe { world_cell.world_mut() });
-    f(this, params)
+    Self::with_state(entity, world, |world, state| {
+      if Self::params_access_panel(state, world, false) {
+        return Self::detached(entity, world, state, |this, params| f(this, params));
+      }
+
+      state.update_archetypes(world);
+      let world = world.as_unsafe_world_cell();
+      // SAFETY: the params don't write the panel, checked above or promised by the fast path
+      let this = unsafe {
+        world
+          .get_entity(entity)
+          .and_then(|entity| entity.get::<Self>())
+      };
+      let params = unsafe { state.get_unchecked_manual(world) };
+      f(this.unwrap(), params)
+    })
   }
 
   fn get_entity_mut<T>(
@@ -64,19 +177,34 @@ pub unsafe trait UiExtensions: Ui {
     world: &mut World,
     f: impl FnOnce(&mut Self, Self::Params<'_, '_>) -> T,
   ) -> T {
-    let mut q = world.query::<(&mut Self, &mut UiParams<Self>)>();
-    let world_cell = world.as_unsafe_world_cell();
-    let (mut this, mut params) = q
-      .get_mut(unsafe { world_cell.world_mut() }, entity)
-      .unwrap();
-    let params = params.get_mut(unsafe { world_cell.world_mut() });
-    f(this.as_mut(), params)
+    Self::with_state(entity, world, |world, state| {
+      if Self::params_access_panel(state, world, true) {
+        return Self::detached(entity, world, state, f);
+      }
+
+      state.update_archetypes(world);
+      let world = world.as_unsafe_world_cell();
+      // SAFETY: the params don't access the panel, checked above or promised by the fast path
+      let this = unsafe {
+        world
+          .get_entity(entity)
+          .and_then(|entity| entity.get_mut::<Self>())
+      };
+      let params = unsafe { state.get_unchecked_manual(world) };
+      f(this.unwrap().as_mut(), params)
+    })
   }
 
   fn register_params(entity: Entity, world: &mut World) {
-    if !world.entity(entity).contains::<UiParams<Self>>() {
-      let state = SystemState::<<Self as Ui>::Params<'_, '_>>::new(world);
-      world.entity_mut(entity).insert(UiComponentState(state));
+    let state = UiParams::<Self>::new(world);
+    let previous = world
+      .get_resource_or_init::<UiParamStates>()
+      .0
+      .insert(entity, Box::new(state));
+
+    // left by a panel despawned earlier this frame whose entity was reused
+    if let Some(mut previous) = previous {
+      previous.apply(world);
     }
   }
 
@@ -85,38 +213,95 @@ pub unsafe trait UiExtensions: Ui {
     world: &mut World,
     f: impl FnOnce(Self::Params<'_, '_>) -> T,
   ) -> T {
-    let world_cell = world.as_unsafe_world_cell();
-    let mut entity = unsafe { world_cell.world_mut() }.entity_mut(entity);
-    let mut params = entity.get_mut::<UiParams<Self>>().unwrap();
-    let params = params.get_mut(unsafe { world_cell.world_mut() });
-    f(params)
+    Self::with_state(entity, world, |world, state| f(state.get_mut(world)))
+  }
+
+  /// Takes the panel's param state out of [`UiParamStates`] while `f` runs
+  fn with_state<T>(
+    entity: Entity,
+    world: &mut World,
+    f: impl FnOnce(&mut World, &mut UiParams<Self>) -> T,
+  ) -> T {
+    let mut state = world
+      .resource_mut::<UiParamStates>()
+      .0
+      .remove(&entity)
+      .expect("panel params are registered when the panel spawns");
+
+    let result = f(
+      world,
+      state
+        .as_any_mut()
+        .downcast_mut::<UiParams<Self>>()
+        .expect("panel params match the panel type"),
+    );
+
+    world
+      .resource_mut::<UiParamStates>()
+      .0
+      .insert(entity, state);
+
+    result
+  }
+
+  /// Whether the params read, or with `write` also write, the panel's own component
+  fn params_access_panel(state: &UiParams<Self>, world: &World, write: bool) -> bool {
+    if Self::fast_path() {
+      return false;
+    }
+
+    let Some(id) = world.component_id::<Self>() else {
+      return false;
+    };
+
+    let access = state.meta().component_access_set().combined_access();
+    access.has_component_write(id) || (write && access.has_component_read(id))
+  }
+
+  /// Moves the panel out of the entity while `f` runs, for params that can reach it
+  fn detached<T>(
+    entity: Entity,
+    world: &mut World,
+    state: &mut UiParams<Self>,
+    f: impl FnOnce(&mut Self, Self::Params<'_, '_>) -> T,
+  ) -> T {
+    let mut this = world.entity_mut(entity).take::<Self>().unwrap();
+
+    let result = f(&mut this, state.get_mut(world));
+
+    world.entity_mut(entity).insert(this);
+
+    result
   }
 }
 
 unsafe impl<T> UiExtensions for T where T: Ui {}
 
-#[derive(Component, Deref, DerefMut)]
-struct UiComponentState<P>(SystemState<P>)
-where
-  P: SystemParam + 'static;
-
 #[derive(Component, Reflect)]
 pub struct MissingUi {
   message: String,
   uuid: Uuid,
+  /// Kept around so the state of the missing ui is not lost when the layout is saved again
+  #[reflect(ignore)]
+  state: Option<serde_json::Value>,
 }
 
 impl MissingUi {
-  pub fn new(id: impl Into<PersistentId>) -> Self {
+  pub fn new(id: impl Into<PersistentId>, state: Option<serde_json::Value>) -> Self {
     let id = id.into();
     Self {
       message: format!("Failed to find ui component with uuid: {}", *id),
       uuid: *id,
+      state,
     }
   }
   pub fn id(&self) -> &Uuid {
     &self.uuid
   }
+
+  pub fn state(&self) -> Option<&serde_json::Value> {
+    self.state.as_ref()
+  }
 }
 
 #[derive(SystemParam)]
@@ -132,6 +317,7 @@ impl Ui for MissingUi {
     Self {
       message: default(),
       uuid: default(),
+      state: None,
     }
   }
 
@@ -153,12 +339,12 @@ impl Ui for MissingUi {
 pub(super) trait DockExtensions {
   fn decouple(
     &self,
-    q_uuids: &Query<&PersistentId, Without<MissingUi>>,
-    q_missing: &Query<&MissingUi>,
-  ) -> DockState<Uuid>;
+    vtables: &HashMap<PersistentId, VTable>,
+    world: &mut World,
+  ) -> DockState<LayoutInfo>;
 
   fn restore(
-    dock: &DockState<Uuid>,
+    dock: &DockState<LayoutInfo>,
     vtables: &HashMap<PersistentId, VTable>,
     world: &mut World,
   ) -> Self;
@@ -167,33 +353,44 @@ pub(super) trait DockExtensions {
 impl DockExtensions for DockState<Entity> {
   fn decouple(
     &self,
-    q_uuids: &Query<&PersistentId, Without<MissingUi>>,
-    q_missing: &Query<&MissingUi>,
-  ) -> DockState<Uuid> {
+    vtables: &HashMap<PersistentId, VTable>,
+    world: &mut World,
+  ) -> DockState<LayoutInfo> {
     self.map_tabs(|tab| {
-      if let Ok(missing_uuid) = q_missing.get(*tab) {
-        *missing_uuid.id()
+      if let Some(missing) = world.get::<MissingUi>(*tab) {
+        LayoutInfo::new(*missing.id(), missing.state().cloned())
       } else {
-        **q_uuids.get(*tab).unwrap()
+        let id = *world.get::<PersistentId>(*tab).unwrap();
+        let state = (vtables[&id].save_state)(*tab, world);
+        LayoutInfo::new(*id, state)
       }
     })
   }
 
   fn restore(
-    dock: &DockState<Uuid>,
+    dock: &DockState<LayoutInfo>,
     vtables: &HashMap<PersistentId, VTable>,
     world: &mut World,
   ) -> Self {
     dock.map_tabs(|tab| {
       vtables
-        .get(&PersistentId(*tab))
-        .map(|vtable| (vtable.spawn)(world))
+        .get(&PersistentId(*tab.id()))
+        .map(|vtable| {
+          let entity = (vtable.spawn)(world);
+          if let Some(state) = tab.state() {
+            (vtable.restore_state)(entity, world, state.clone());
+          }
+          entity
+        })
         .unwrap_or_else(|| {
           let entity_id = world
-            .spawn((MissingUi::new(*tab), PersistentId(<MissingUi as RawUi>::ID)))
+            .spawn((
+              MissingUi::new(*tab.id(), tab.state().cloned()),
+              PersistentId(<MissingUi as RawUi>::ID),
+            ))
             .id();
           world.entity_mut(entity_id).insert(Name::new("Missing Ui"));
-          info!("Failed to find ui with uuid: {tab}");
+          info!("Failed to find ui with uuid: {}", tab.id());
           entity_id
         })
     })
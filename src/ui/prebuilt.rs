@@ -1,9 +1,19 @@
+pub mod array_tool;
+pub mod asset_reloads;
 pub mod assets;
 pub mod components;
 pub mod debug;
 pub mod editor_view;
+pub mod find_in_world;
 pub mod game_view;
 pub mod hierarchy;
 pub mod inspector;
+pub mod material_editor;
 pub mod prefabs;
+pub mod render_layers;
 pub mod resources;
+pub mod session_stats;
+pub mod settings;
+pub mod tile_brush;
+pub mod timeline;
+pub mod watches;
@@ -0,0 +1,226 @@
+use crate::{
+  read_only::{self, ReadOnly},
+  ui::{InspectorSelection, RawUi},
+  util::WorldExtensions,
+  view::GizmoColorSettings,
+  Editing,
+};
+use bevy::{prelude::*, render::primitives::Aabb};
+use bevy_egui::egui;
+use uuid::{uuid, Uuid};
+
+const MAX_COUNT: usize = 1000;
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Reflect)]
+enum ArrayMode {
+  #[default]
+  Linear,
+  Radial,
+}
+
+/// Clones the selected entity along a line or around a point
+#[derive(Component, Reflect)]
+pub struct ArrayTool {
+  mode: ArrayMode,
+  count: usize,
+  /// Distance between consecutive copies
+  offset: Vec3,
+  center: Vec3,
+  axis: Vec3,
+  /// Angle between consecutive copies in degrees
+  angle: f32,
+  preview: bool,
+}
+
+impl Default for ArrayTool {
+  fn default() -> Self {
+    Self {
+      mode: default(),
+      count: 4,
+      offset: Vec3::X * 2.0,
+      center: Vec3::ZERO,
+      axis: Vec3::Y,
+      angle: 72.0,
+      preview: true,
+    }
+  }
+}
+
+impl ArrayTool {
+  /// Transforms of the copies, the source itself is not included
+  fn transforms(&self, source: Transform) -> Vec<Transform> {
+    (1..=self.count)
+      .map(|i| match self.mode {
+        ArrayMode::Linear => source.with_translation(source.translation + self.offset * i as f32),
+        ArrayMode::Radial => {
+          let axis = self.axis.try_normalize().unwrap_or(Vec3::Y);
+          let rotation = Quat::from_axis_angle(axis, (self.angle * i as f32).to_radians());
+          let mut transform = source;
+          transform.rotate_around(self.center, rotation);
+          transform
+        }
+      })
+      .collect()
+  }
+
+  fn settings_ui(&mut self, ui: &mut egui::Ui) {
+    ui.horizontal(|ui| {
+      ui.selectable_value(&mut self.mode, ArrayMode::Linear, "Linear");
+      ui.selectable_value(&mut self.mode, ArrayMode::Radial, "Radial");
+    });
+
+    egui::Grid::new("array-tool").num_columns(2).show(ui, |ui| {
+      ui.label("Copies");
+      ui.add(egui::DragValue::new(&mut self.count).range(1..=MAX_COUNT));
+      ui.end_row();
+
+      match self.mode {
+        ArrayMode::Linear => {
+          ui.label("Offset");
+          Self::vec3_ui(ui, &mut self.offset);
+          ui.end_row();
+        }
+        ArrayMode::Radial => {
+          ui.label("Center");
+          Self::vec3_ui(ui, &mut self.center);
+          ui.end_row();
+
+          ui.label("Axis");
+          Self::vec3_ui(ui, &mut self.axis);
+          ui.end_row();
+
+          ui.label("Angle");
+          ui.horizontal(|ui| {
+            ui.add(egui::DragValue::new(&mut self.angle).speed(1.0).suffix("°"));
+            if ui.button("Full Circle").clicked() {
+              self.angle = 360.0 / (self.count + 1) as f32;
+            }
+          });
+          ui.end_row();
+        }
+      }
+
+      ui.label("Preview");
+      ui.checkbox(&mut self.preview, "");
+      ui.end_row();
+    });
+  }
+
+  fn vec3_ui(ui: &mut egui::Ui, value: &mut Vec3) {
+    ui.horizontal(|ui| {
+      ui.add(egui::DragValue::new(&mut value.x).speed(0.1).prefix("x "));
+      ui.add(egui::DragValue::new(&mut value.y).speed(0.1).prefix("y "));
+      ui.add(egui::DragValue::new(&mut value.z).speed(0.1).prefix("z "));
+    });
+  }
+
+  fn selected(world: &World) -> Option<Entity> {
+    match world.resource::<InspectorSelection>() {
+      InspectorSelection::Entities(selected) if selected.len() == 1 => selected.iter().next(),
+      _ => None,
+    }
+  }
+
+  fn apply(transforms: Vec<Transform>, source: Entity, world: &mut World) {
+    for transform in transforms {
+      let Some(copy) = world.duplicate(source) else {
+        return;
+      };
+      world.entity_mut(copy).insert(transform);
+    }
+  }
+
+  /// Draws where the copies would go while the tool is visible
+  fn draw_preview(
+    mut gizmos: Gizmos,
+    colors: Res<GizmoColorSettings>,
+    selection: Res<InspectorSelection>,
+    q_tools: Query<&Self>,
+    q_sources: Query<(&Transform, Option<&Aabb>)>,
+  ) {
+    let InspectorSelection::Entities(selected) = selection.as_ref() else {
+      return;
+    };
+
+    let Some((transform, aabb)) = selected
+      .iter()
+      .next()
+      .filter(|_| selected.len() == 1)
+      .and_then(|entity| q_sources.get(entity).ok())
+    else {
+      return;
+    };
+
+    let size = aabb
+      .map(|aabb| Vec3::from(aabb.half_extents) * 2.0)
+      .unwrap_or(Vec3::splat(0.25));
+
+    for tool in q_tools.iter().filter(|tool| tool.preview) {
+      for copy in tool.transforms(*transform) {
+        let center = copy.transform_point(aabb.map(|aabb| aabb.center.into()).unwrap_or_default());
+        gizmos.cuboid(
+          Transform::from_translation(center)
+            .with_rotation(copy.rotation)
+            .with_scale(size * copy.scale),
+          colors.selection,
+        );
+      }
+    }
+  }
+}
+
+impl RawUi for ArrayTool {
+  const NAME: &str = "Array";
+  const ID: Uuid = uuid!("5c2e8a31-97d4-4f6b-b0a8-3e1d7c9f4a25");
+
+  fn init(app: &mut App) {
+    app.add_systems(Update, Self::draw_preview.in_set(Editing));
+  }
+
+  fn spawn(_entity: Entity, _world: &mut World) -> Self {
+    default()
+  }
+
+  fn unique() -> bool {
+    true
+  }
+
+  fn render(entity: Entity, ui: &mut egui::Ui, world: &mut World) {
+    let source = Self::selected(world);
+
+    let Some(mut this) = world.get_mut::<Self>(entity) else {
+      return;
+    };
+
+    this.settings_ui(ui);
+
+    let transforms = source
+      .and_then(|source| world.get::<Transform>(source).copied())
+      .map(|transform| {
+        world
+          .get::<Self>(entity)
+          .map(|this| this.transforms(transform))
+          .unwrap_or_default()
+      });
+
+    ui.separator();
+
+    match (source, transforms) {
+      (Some(source), Some(transforms)) => {
+        if ui
+          .add_enabled(
+            ReadOnly::writable(world),
+            egui::Button::new(format!("Create {} Copies", transforms.len())),
+          )
+          .on_disabled_hover_text(read_only::REASON)
+          .clicked()
+        {
+          Self::apply(transforms, source, world);
+        }
+      }
+      _ => {
+        ui.weak("Select a single entity with a transform");
+      }
+    }
+  }
+}
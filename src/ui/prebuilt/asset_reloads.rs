@@ -0,0 +1,181 @@
+use crate::ui::RawUi;
+use bevy::{
+  asset::{AssetPath, UntypedAssetLoadFailedEvent},
+  gltf::Gltf,
+  prelude::*,
+};
+use bevy_egui::egui;
+use std::{collections::BTreeMap, time::Instant};
+use uuid::{uuid, Uuid};
+
+struct LoadRecord {
+  path: AssetPath<'static>,
+  type_name: &'static str,
+  /// Times the asset finished loading, the first one isn't a reload
+  loads: u32,
+  last_loaded: Option<Instant>,
+  error: Option<String>,
+}
+
+impl LoadRecord {
+  fn reloads(&self) -> u32 {
+    self.loads.saturating_sub(1)
+  }
+}
+
+/// Loads and failed loads of file backed assets since the editor was launched
+#[derive(Resource, Default)]
+struct AssetLoadLog {
+  /// Sorted by path
+  assets: BTreeMap<String, LoadRecord>,
+}
+
+impl AssetLoadLog {
+  fn record(&mut self, path: AssetPath<'static>, type_name: &'static str) -> &mut LoadRecord {
+    self
+      .assets
+      .entry(path.to_string())
+      .or_insert_with(|| LoadRecord {
+        path,
+        type_name,
+        loads: 0,
+        last_loaded: None,
+        error: None,
+      })
+  }
+
+  /// Assets are loaded again with all their dependencies whenever they are hot reloaded, unlike
+  /// changes made from code which only modify them
+  fn track_loads<A: Asset>(
+    mut log: ResMut<Self>,
+    asset_server: Res<AssetServer>,
+    mut events: EventReader<AssetEvent<A>>,
+  ) {
+    for event in events.read() {
+      let AssetEvent::LoadedWithDependencies { id } = event else {
+        continue;
+      };
+
+      let Some(path) = asset_server.get_path(*id) else {
+        continue;
+      };
+
+      let record = log.record(path.into_owned(), A::short_type_path());
+      record.loads += 1;
+      record.last_loaded = Some(Instant::now());
+      record.error = None;
+    }
+  }
+
+  fn track_failures(mut log: ResMut<Self>, mut events: EventReader<UntypedAssetLoadFailedEvent>) {
+    for event in events.read() {
+      log.record(event.path.clone(), "Unknown").error = Some(event.error.to_string());
+    }
+  }
+}
+
+/// Lists assets of type `A` in the asset reloads panel
+pub(crate) fn track_reloads<A: Asset>(app: &mut App) {
+  app
+    .init_resource::<AssetLoadLog>()
+    .add_systems(Update, AssetLoadLog::track_loads::<A>);
+}
+
+/// Assets that were hot reloaded or failed to load this session
+#[derive(Default, Component, Reflect)]
+pub struct AssetReloads;
+
+impl AssetReloads {
+  fn age(instant: Instant) -> String {
+    match instant.elapsed().as_secs() {
+      0..60 => String::from("just now"),
+      secs @ 60..3600 => format!("{}m ago", secs / 60),
+      secs => format!("{}h ago", secs / 3600),
+    }
+  }
+}
+
+impl RawUi for AssetReloads {
+  const NAME: &str = "Asset Reloads";
+  const ID: Uuid = uuid!("0e6b3f52-7d1a-4c8e-9a4f-3b2d5c7e9f10");
+
+  fn init(app: &mut App) {
+    track_reloads::<Image>(app);
+    track_reloads::<Mesh>(app);
+    track_reloads::<StandardMaterial>(app);
+    track_reloads::<Scene>(app);
+    track_reloads::<DynamicScene>(app);
+    track_reloads::<Gltf>(app);
+    track_reloads::<Font>(app);
+    track_reloads::<AudioSource>(app);
+    track_reloads::<Shader>(app);
+    track_reloads::<AnimationClip>(app);
+
+    app.add_systems(Update, AssetLoadLog::track_failures);
+  }
+
+  fn spawn(_entity: Entity, _world: &mut World) -> Self {
+    default()
+  }
+
+  fn unique() -> bool {
+    true
+  }
+
+  fn render(_entity: Entity, ui: &mut egui::Ui, world: &mut World) {
+    let asset_server = world.resource::<AssetServer>().clone();
+
+    if !asset_server.watching_for_changes() {
+      ui.weak("Hot reloading is off, enable the file_watcher feature of bevy to turn it on");
+    }
+
+    let log = world.resource::<AssetLoadLog>();
+    let mut shown = log
+      .assets
+      .iter()
+      .filter(|(_, record)| record.reloads() > 0 || record.error.is_some())
+      .peekable();
+
+    if shown.peek().is_none() {
+      ui.weak("No assets were reloaded yet");
+      return;
+    }
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+      egui::Grid::new("asset-reloads")
+        .striped(true)
+        .show(ui, |ui| {
+          ui.strong("Asset");
+          ui.strong("Type");
+          ui.strong("Reloads");
+          ui.strong("Last");
+          ui.label("");
+          ui.end_row();
+
+          for (path, record) in shown {
+            match &record.error {
+              Some(error) => {
+                ui.colored_label(ui.visuals().error_fg_color, path)
+                  .on_hover_text(error);
+              }
+              None => {
+                ui.label(path);
+              }
+            }
+            ui.label(record.type_name);
+            ui.label(record.reloads().to_string());
+            ui.label(record.last_loaded.map(Self::age).unwrap_or_default());
+
+            if ui
+              .small_button("Reload")
+              .on_hover_text("Load the asset from disk again")
+              .clicked()
+            {
+              asset_server.reload(record.path.clone());
+            }
+            ui.end_row();
+          }
+        });
+    });
+  }
+}
@@ -1,15 +1,27 @@
-use crate::ui::{InspectorSelection, Ui};
+use crate::ui::{
+  type_search::{TypeKind, TypeSearch},
+  InspectorSelection, Ui,
+};
 use bevy::{asset::ReflectAsset, ecs::system::SystemParam, prelude::*};
 use bevy_egui::egui;
 use uuid::uuid;
 
 #[derive(Default, Component, Reflect)]
-pub struct Assets;
+pub struct Assets {
+  filter: String,
+}
 
 #[derive(SystemParam)]
 pub struct Params<'w, 's> {
-  set: ParamSet<'w, 's, (&'w World, ResMut<'w, InspectorSelection>)>,
-  filter: Local<'s, String>,
+  set: ParamSet<
+    'w,
+    's,
+    (
+      &'w World,
+      ResMut<'w, InspectorSelection>,
+      ResMut<'w, TypeSearch>,
+    ),
+  >,
 }
 
 impl Ui for Assets {
@@ -26,29 +38,42 @@ impl Ui for Assets {
     true
   }
 
+  fn pausable() -> bool {
+    true
+  }
+
+  fn save_state(&self, _params: Self::Params<'_, '_>) -> Option<serde_json::Value> {
+    serde_json::to_value(&self.filter).ok()
+  }
+
+  fn restore_state(&mut self, _params: Self::Params<'_, '_>, state: serde_json::Value) {
+    if let Ok(filter) = serde_json::from_value(state) {
+      self.filter = filter;
+    }
+  }
+
   fn render(&mut self, ui: &mut egui::Ui, mut params: Self::Params<'_, '_>) {
-    let world = params.set.p0();
-    let type_registry = world.resource::<AppTypeRegistry>().0.clone();
+    let type_registry = params.set.p0().resource::<AppTypeRegistry>().0.clone();
     let type_registry = type_registry.read();
 
-    let mut assets = type_registry
-      .iter()
-      .filter_map(|registration| {
-        let reflect_asset = registration.data::<ReflectAsset>()?;
-        let name = registration.type_info().type_path_table().short_path();
-        (params.filter.is_empty() || name.to_lowercase().contains(params.filter.as_str()))
-          .then(|| (name, registration.type_id(), reflect_asset))
-      })
+    let assets = params
+      .set
+      .p2()
+      .search(&type_registry, TypeKind::Asset, &self.filter)
+      .map(|entry| (entry.name.clone(), entry.type_id))
       .collect::<Vec<_>>();
 
-    assets.sort_by(|(name_a, ..), (name_b, ..)| name_a.cmp(name_b));
+    let world = params.set.p0();
 
     let mut selection = None;
     let current_selection = world.resource::<InspectorSelection>();
 
-    ui.text_edit_singleline(&mut *params.filter).changed();
+    ui.text_edit_singleline(&mut self.filter).changed();
 
-    for (asset_name, asset_type_id, reflect_asset) in assets {
+    for (asset_name, asset_type_id) in assets {
+      let Some(reflect_asset) = type_registry.get_type_data::<ReflectAsset>(asset_type_id) else {
+        continue;
+      };
       let handles = reflect_asset.ids(world).collect::<Vec<_>>();
 
       ui.collapsing(format!("{asset_name} ({})", handles.len()), |ui| {
@@ -64,7 +89,7 @@ impl Ui for Assets {
           {
             selection = Some(InspectorSelection::Asset(
               asset_type_id,
-              asset_name.to_string(),
+              asset_name.clone(),
               handle,
             ));
           }
@@ -1,7 +1,9 @@
 use std::marker::PhantomData;
 
-use crate::ui::Ui;
+use crate::testing::{TestMonitor, TestMonitorSettings, TestRecorder, TestRecorderSettings};
+use crate::ui::{status_bar::StatusMessage, Ui};
 use crate::util::LoggingSettings;
+use crate::EditorState;
 use bevy::{diagnostic::DiagnosticsStore, ecs::system::SystemParam, prelude::*};
 use bevy_egui::egui;
 use bevy_inspector_egui::reflect_inspector::ui_for_value;
@@ -45,6 +47,12 @@ pub struct Params<'w, 's> {
   type_registry: Res<'w, AppTypeRegistry>,
   logging: ResMut<'w, LoggingSettings>,
   diagnostics: Res<'w, DiagnosticsStore>,
+  monitor: Res<'w, TestMonitor>,
+  monitor_settings: ResMut<'w, TestMonitorSettings>,
+  recorder: ResMut<'w, TestRecorder>,
+  recorder_settings: ResMut<'w, TestRecorderSettings>,
+  editor_state: Res<'w, State<EditorState>>,
+  status: EventWriter<'w, StatusMessage>,
 
   _pd: PhantomData<&'s ()>,
 }
@@ -67,5 +75,14 @@ impl Ui for DebugMenu {
     self.diagnostics(ui, &params);
     ui.separator();
     self.log_level_selector(ui, &mut params);
+    ui.separator();
+    params
+      .monitor
+      .ui(ui, &mut params.monitor_settings, &mut params.status);
+    ui.separator();
+    let testing = *params.editor_state.get() == EditorState::Testing;
+    params
+      .recorder
+      .ui(ui, &mut params.recorder_settings, testing);
   }
 }
@@ -1,14 +1,103 @@
 use crate::{
+  testing::EditorOwned,
   ui::{misc::UiInfo, Ui},
-  view::EditorCamera,
+  view::{
+    camera_preview::{self, GamePreview, GamePreviewSettings},
+    name_labels::{self, NameLabelSettings},
+    placement::PlacementSettings,
+    rulers::{self, GuideSettings},
+    shading::{self, ShadingSettings},
+    stats_hud::{self, StatsHudSettings},
+    view2d::EditorCamera2d,
+    view3d::{self, EditorCamera3d, FixedView},
+    view_cube, ActiveEditorCamera, EditorCamera, EditorPane, FocusedPane,
+  },
+};
+use bevy::{
+  diagnostic::DiagnosticsStore,
+  ecs::system::SystemParam,
+  prelude::*,
+  render::{camera::Viewport, renderer::RenderDevice},
+  window::PrimaryWindow,
 };
-use bevy::{ecs::system::SystemParam, prelude::*, render::camera::Viewport, window::PrimaryWindow};
 use bevy_egui::egui;
+use egui_dock::{NodeIndex, SurfaceIndex};
+use serde::{Deserialize, Serialize};
 use uuid::uuid;
 
+/// How many cameras the editor view is split between
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Reflect, Serialize, Deserialize)]
+pub enum PaneLayout {
+  #[default]
+  Single,
+  /// Side by side, the main view and the top view
+  Double,
+  /// The main view with the top, front and side views
+  Quad,
+}
+
+impl PaneLayout {
+  const ALL: [Self; 3] = [Self::Single, Self::Double, Self::Quad];
+
+  fn name(self) -> &'static str {
+    match self {
+      Self::Single => "Single View",
+      Self::Double => "Two Views",
+      Self::Quad => "Four Views",
+    }
+  }
+
+  fn panes(self) -> usize {
+    match self {
+      Self::Single => 1,
+      Self::Double => 2,
+      Self::Quad => 4,
+    }
+  }
+
+  /// What the pane looks at, `None` for the main pane which is the regular editor camera
+  fn fixed_view(pane: usize) -> Option<FixedView> {
+    match pane {
+      1 => Some(FixedView::Top),
+      2 => Some(FixedView::Front),
+      3 => Some(FixedView::Side),
+      _ => None,
+    }
+  }
+
+  /// Splits the view into the rect of each pane, in pane order
+  fn split(self, rect: egui::Rect) -> Vec<egui::Rect> {
+    let center = rect.center();
+    match self {
+      Self::Single => vec![rect],
+      Self::Double => vec![
+        egui::Rect::from_min_max(rect.min, egui::pos2(center.x, rect.max.y)),
+        egui::Rect::from_min_max(egui::pos2(center.x, rect.min.y), rect.max),
+      ],
+      Self::Quad => vec![
+        egui::Rect::from_min_max(rect.min, center),
+        egui::Rect::from_min_max(
+          egui::pos2(center.x, rect.min.y),
+          egui::pos2(rect.max.x, center.y),
+        ),
+        egui::Rect::from_min_max(
+          egui::pos2(rect.min.x, center.y),
+          egui::pos2(center.x, rect.max.y),
+        ),
+        egui::Rect::from_min_max(center, rect.max),
+      ],
+    }
+  }
+}
+
 #[derive(Default, Component, Reflect)]
 pub struct EditorView {
   viewport_rect: Rect,
+  layout: PaneLayout,
+  /// Rect of every pane currently shown, only the 3d view is split
+  #[reflect(ignore)]
+  panes: Vec<egui::Rect>,
+  focused: usize,
 }
 
 impl EditorView {
@@ -19,18 +108,25 @@ impl EditorView {
     }
   }
 
+  fn pane(&self, pane: EditorPane) -> Option<egui::Rect> {
+    self.panes.get(pane.0).copied()
+  }
+
   fn set_viewport(
     window: Single<&Window, With<PrimaryWindow>>,
     egui_settings: Single<&bevy_egui::EguiSettings>,
     q_editor_views: Query<(&Self, &UiInfo)>,
-    mut q_cameras: Query<&mut Camera, With<EditorCamera>>,
+    mut q_cameras: Query<(&mut Camera, &EditorPane), With<EditorCamera>>,
   ) {
     for (editor_view, ui_info) in &q_editor_views {
       if ui_info.rendered() {
-        for mut camera in &mut q_cameras {
+        for (mut camera, pane) in &mut q_cameras {
+          let Some(viewport) = editor_view.pane(*pane) else {
+            continue;
+          };
+
           let scale_factor = window.scale_factor() * egui_settings.scale_factor;
 
-          let viewport = editor_view.viewport();
           let viewport_pos = viewport.left_top().to_vec2() * scale_factor;
           let viewport_size = viewport.size() * scale_factor;
 
@@ -58,11 +154,180 @@ impl EditorView {
       }
     }
   }
+
+  /// Spawns the cameras of the extra panes and despawns those no longer shown
+  fn sync_pane_cameras(
+    mut commands: Commands,
+    q_editor_views: Query<&Self>,
+    q_cameras: Query<(Entity, &EditorPane), With<EditorCamera3d>>,
+  ) {
+    let panes = q_editor_views
+      .iter()
+      .map(|editor_view| editor_view.layout.panes())
+      .max()
+      .unwrap_or(1);
+
+    for (entity, pane) in &q_cameras {
+      if pane.0 >= panes {
+        commands.entity(entity).despawn();
+      }
+    }
+
+    for pane in 1..panes {
+      let Some(view) = PaneLayout::fixed_view(pane) else {
+        continue;
+      };
+
+      if !q_cameras.iter().any(|(_, existing)| existing.0 == pane) {
+        view3d::spawn_fixed_view(&mut commands, pane, view);
+      }
+    }
+  }
+
+  /// Moves the [`FocusedPane`] marker to the camera of the pane that was last clicked
+  fn focus_pane(
+    mut commands: Commands,
+    q_editor_views: Query<&Self>,
+    q_cameras: Query<(Entity, &EditorPane, Has<FocusedPane>), With<EditorCamera>>,
+  ) {
+    let focused = q_editor_views
+      .iter()
+      .next()
+      .map(|editor_view| editor_view.focused)
+      .unwrap_or_default();
+
+    for (entity, pane, has_focus) in &q_cameras {
+      let wants_focus = pane.0 == focused;
+      if wants_focus && !has_focus {
+        commands.entity(entity).insert(FocusedPane);
+      } else if !wants_focus && has_focus {
+        commands.entity(entity).remove::<FocusedPane>();
+      }
+    }
+  }
+
+  fn pane_name(pane: usize) -> &'static str {
+    PaneLayout::fixed_view(pane).map_or("Perspective", FixedView::name)
+  }
+
+  /// Outlines every pane, names it and highlights the one camera navigation goes to
+  fn panes_ui(&mut self, ui: &mut egui::Ui) {
+    if let Some(pos) = ui.input(|input| {
+      input
+        .pointer
+        .any_pressed()
+        .then(|| input.pointer.interact_pos())
+        .flatten()
+    }) {
+      if let Some(pane) = self.panes.iter().position(|rect| rect.contains(pos)) {
+        self.focused = pane;
+      }
+    }
+
+    let painter = ui.painter();
+    let visuals = ui.visuals();
+
+    for (pane, rect) in self.panes.iter().enumerate() {
+      let stroke = if pane == self.focused {
+        visuals.selection.stroke
+      } else {
+        visuals.widgets.noninteractive.bg_stroke
+      };
+      painter.rect_stroke(rect.shrink(1.0), 0.0, stroke);
+      painter.text(
+        egui::pos2(rect.center().x, rect.top() + PANE_LABEL_MARGIN),
+        egui::Align2::CENTER_TOP,
+        Self::pane_name(pane),
+        egui::TextStyle::Small.resolve(ui.style()),
+        visuals.text_color(),
+      );
+    }
+  }
+}
+
+const PANE_LABEL_MARGIN: f32 = 4.0;
+
+impl EditorView {
+  /// Labels the named entities of the scene seen by the camera of the main pane
+  fn name_labels_ui(ui: &egui::Ui, viewport: egui::Rect, params: &Params) {
+    if !params.name_label_settings.enabled {
+      return;
+    }
+
+    let is_2d = *params.active_camera.get() == ActiveEditorCamera::Cam2D;
+    let Some((entity, transform, ..)) = params
+      .q_pane_cameras
+      .iter()
+      .find(|(_, _, pane, camera_2d)| pane.0 == 0 && *camera_2d == is_2d)
+    else {
+      return;
+    };
+    let Ok(camera) = params.q_cameras.get(entity) else {
+      return;
+    };
+
+    let labels = params
+      .q_names
+      .iter()
+      .filter(|(entity, _, _, visibility)| {
+        !params.q_editor_owned.contains(*entity) && visibility.is_none_or(|v| v.get())
+      })
+      .map(|(_, name, transform, _)| (transform.translation(), name.as_str()));
+
+    name_labels::ui(
+      ui,
+      viewport,
+      (camera, transform),
+      labels,
+      &params.name_label_settings,
+    );
+  }
 }
 
 #[derive(SystemParam)]
 pub struct Params<'w, 's> {
   q_cameras: Query<'w, 's, &'static mut Camera, With<EditorCamera>>,
+  q_transform_3d:
+    Query<'w, 's, (&'static mut Transform, &'static EditorPane), With<EditorCamera3d>>,
+  q_camera_transforms: Query<'w, 's, &'static GlobalTransform, With<FocusedPane>>,
+  q_camera_2d: Query<
+    'w,
+    's,
+    (&'static GlobalTransform, &'static OrthographicProjection),
+    With<EditorCamera2d>,
+  >,
+  q_pane_cameras: Query<
+    'w,
+    's,
+    (
+      Entity,
+      &'static GlobalTransform,
+      &'static EditorPane,
+      Has<EditorCamera2d>,
+    ),
+    With<EditorCamera>,
+  >,
+  q_names: Query<
+    'w,
+    's,
+    (
+      Entity,
+      &'static Name,
+      &'static GlobalTransform,
+      Option<&'static InheritedVisibility>,
+    ),
+  >,
+  q_editor_owned: Query<'w, 's, (), EditorOwned>,
+  active_camera: Res<'w, State<ActiveEditorCamera>>,
+  name_label_settings: Res<'w, NameLabelSettings>,
+  stats_settings: Res<'w, StatsHudSettings>,
+  placement_settings: Res<'w, PlacementSettings>,
+  guide_settings: ResMut<'w, GuideSettings>,
+  game_preview: Res<'w, GamePreview>,
+  game_preview_settings: Res<'w, GamePreviewSettings>,
+  shading_settings: ResMut<'w, ShadingSettings>,
+  render_device: Option<Res<'w, RenderDevice>>,
+  diagnostics: Res<'w, DiagnosticsStore>,
 }
 
 impl Ui for EditorView {
@@ -72,7 +337,16 @@ impl Ui for EditorView {
   type Params<'w, 's> = Params<'w, 's>;
 
   fn init(app: &mut App) {
-    app.add_systems(PostUpdate, Self::set_viewport);
+    app
+      .register_type::<PaneLayout>()
+      .add_systems(
+        Update,
+        (
+          Self::sync_pane_cameras.run_if(in_state(ActiveEditorCamera::Cam3D)),
+          Self::focus_pane,
+        ),
+      )
+      .add_systems(PostUpdate, Self::set_viewport);
   }
 
   fn spawn(_params: Self::Params<'_, '_>) -> Self {
@@ -85,12 +359,101 @@ impl Ui for EditorView {
     }
   }
 
-  fn render(&mut self, ui: &mut egui::Ui, _params: Self::Params<'_, '_>) {
+  fn render(&mut self, ui: &mut egui::Ui, mut params: Self::Params<'_, '_>) {
     let egui_rect = ui.clip_rect();
     self.viewport_rect = Rect {
       max: Vec2::new(egui_rect.max.x, egui_rect.max.y),
       min: Vec2::new(egui_rect.min.x, egui_rect.min.y),
     };
+
+    self.panes = match params.active_camera.get() {
+      ActiveEditorCamera::Cam3D => self.layout.split(self.viewport()),
+      _ => vec![self.viewport()],
+    };
+
+    if self.focused >= self.panes.len() {
+      self.focused = 0;
+    }
+
+    if self.panes.len() > 1 {
+      self.panes_ui(ui);
+    }
+
+    let main_pane = self.panes[0];
+
+    if let Some((mut transform, _)) = params
+      .q_transform_3d
+      .iter_mut()
+      .find(|(_, pane)| pane.0 == 0)
+    {
+      if let Some(new_transform) = view_cube::ui(ui, main_pane, &transform) {
+        *transform = new_transform;
+      }
+    }
+
+    if *params.active_camera.get() == ActiveEditorCamera::Cam3D {
+      shading::ui(
+        ui,
+        main_pane,
+        &mut params.shading_settings,
+        params.render_device.as_deref(),
+      );
+    }
+
+    if *params.active_camera.get() == ActiveEditorCamera::Cam2D {
+      if let Ok(camera) = params.q_camera_2d.get_single() {
+        rulers::ui(ui, main_pane, camera, &mut params.guide_settings);
+      }
+    }
+
+    Self::name_labels_ui(ui, main_pane, &params);
+
+    camera_preview::pip_ui(
+      ui,
+      main_pane,
+      &params.game_preview,
+      &params.game_preview_settings,
+    );
+
+    let focused_pane = self.panes[self.focused];
+
+    stats_hud::ui(
+      ui,
+      focused_pane,
+      &params.stats_settings,
+      &params.diagnostics,
+      &params.placement_settings,
+      params.q_camera_transforms.iter().next(),
+    );
+  }
+
+  fn context_menu(
+    &mut self,
+    ui: &mut egui::Ui,
+    _params: Self::Params<'_, '_>,
+    _surface: SurfaceIndex,
+    _node: NodeIndex,
+  ) {
+    ui.menu_button("Layout", |ui| {
+      for layout in PaneLayout::ALL {
+        if ui
+          .radio_value(&mut self.layout, layout, layout.name())
+          .clicked()
+        {
+          ui.close_menu();
+        }
+      }
+    });
+  }
+
+  fn save_state(&self, _params: Self::Params<'_, '_>) -> Option<serde_json::Value> {
+    serde_json::to_value(self.layout).ok()
+  }
+
+  fn restore_state(&mut self, _params: Self::Params<'_, '_>, state: serde_json::Value) {
+    if let Ok(layout) = serde_json::from_value(state) {
+      self.layout = layout;
+    }
   }
 
   fn when_rendered(&mut self, mut params: Self::Params<'_, '_>) {
@@ -117,6 +480,10 @@ impl Ui for EditorView {
     true
   }
 
+  fn fast_path() -> bool {
+    true
+  }
+
   fn popout() -> bool {
     false
   }
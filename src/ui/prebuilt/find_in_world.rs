@@ -0,0 +1,142 @@
+use crate::{
+  field_query::{FieldQuery, QueryMatch},
+  ui::{InspectorSelection, RawUi, SelectedEntities},
+};
+use bevy::prelude::*;
+use bevy_egui::egui;
+use uuid::{uuid, Uuid};
+
+/// Searches the scene for entities by the values of their components, see [`FieldQuery`]
+#[derive(Default, Component, Reflect)]
+pub struct FindInWorld {
+  query: String,
+  #[reflect(ignore)]
+  results: Vec<QueryMatch>,
+  #[reflect(ignore)]
+  error: Option<String>,
+}
+
+impl FindInWorld {
+  fn search(&mut self, world: &mut World) {
+    let result = self
+      .query
+      .parse::<FieldQuery>()
+      .and_then(|query| query.run(world));
+
+    match result {
+      Ok(results) => {
+        self.results = results;
+        self.error = None;
+      }
+      Err(err) => {
+        self.results.clear();
+        self.error = Some(err.to_string());
+      }
+    }
+  }
+
+  fn results_ui(&self, ui: &mut egui::Ui, world: &mut World) {
+    ui.horizontal(|ui| {
+      ui.label(format!("{} found", self.results.len()));
+
+      if ui.button("Select All").clicked() {
+        let mut selected = SelectedEntities::default();
+        for result in &self.results {
+          if world.get_entity(result.entity).is_ok() {
+            selected.select_maybe_add(result.entity, true);
+          }
+        }
+        *world.resource_mut::<InspectorSelection>() = InspectorSelection::Entities(selected);
+      }
+    });
+
+    let add = ui.input(|input| input.modifiers.command);
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+      egui::Grid::new("find-in-world")
+        .striped(true)
+        .show(ui, |ui| {
+          for result in &self.results {
+            let Ok(entity) = world.get_entity(result.entity) else {
+              // despawned since the search
+              continue;
+            };
+
+            let label = entity
+              .get::<Name>()
+              .map(|name| name.to_string())
+              .unwrap_or_else(|| result.entity.to_string());
+
+            if ui
+              .selectable_label(false, label)
+              .on_hover_text("Click to select, ctrl click to add to the selection")
+              .clicked()
+            {
+              world
+                .resource_mut::<InspectorSelection>()
+                .add_selected(result.entity, add);
+            }
+            ui.monospace(&result.value);
+            ui.end_row();
+          }
+        });
+    });
+  }
+}
+
+impl RawUi for FindInWorld {
+  const NAME: &str = "Find in World";
+  const ID: Uuid = uuid!("97cc6824-e702-4a5b-8dac-b6f010fbfae5");
+
+  fn spawn(_entity: Entity, _world: &mut World) -> Self {
+    default()
+  }
+
+  fn save_state(entity: Entity, world: &mut World) -> Option<serde_json::Value> {
+    world
+      .get::<Self>(entity)
+      .map(|this| serde_json::Value::String(this.query.clone()))
+  }
+
+  fn restore_state(entity: Entity, world: &mut World, state: serde_json::Value) {
+    if let (Some(mut this), serde_json::Value::String(query)) =
+      (world.get_mut::<Self>(entity), state)
+    {
+      this.query = query;
+    }
+  }
+
+  fn render(entity: Entity, ui: &mut egui::Ui, world: &mut World) {
+    let Some(mut this) = world.get_mut::<Self>(entity) else {
+      return;
+    };
+    let mut this = std::mem::take(&mut *this);
+
+    let search = ui
+      .horizontal(|ui| {
+        let response = ui.add(
+          egui::TextEdit::singleline(&mut this.query)
+            .hint_text("Health.current < 10")
+            .desired_width(ui.available_width() - 60.0),
+        );
+        let submitted =
+          response.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter));
+        ui.button("Find").clicked() || submitted
+      })
+      .inner;
+
+    if search {
+      this.search(world);
+    }
+
+    if let Some(error) = &this.error {
+      ui.colored_label(ui.visuals().error_fg_color, error);
+    } else if search || !this.results.is_empty() {
+      this.results_ui(ui, world);
+    }
+
+    if let Some(mut component) = world.get_mut::<Self>(entity) {
+      *component = this;
+    }
+  }
+}
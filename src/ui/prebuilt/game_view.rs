@@ -1,9 +1,77 @@
-use crate::ui::{misc::UiInfo, Ui};
-use bevy::{ecs::system::SystemParam, prelude::*, render::camera::Viewport, window::PrimaryWindow};
+use crate::{
+  cache::Saveable,
+  settings::{EditorSettings, SettingsAppExt},
+  ui::{misc::UiInfo, Ui},
+  EditorState,
+};
+use bevy::{
+  ecs::system::SystemParam, input::InputSystem, prelude::*, render::camera::Viewport,
+  window::PrimaryWindow,
+};
 use bevy_egui::egui;
+use serde::{Deserialize, Serialize};
 use std::marker::PhantomData;
 use uuid::uuid;
 
+/// How the game's mouse input behaves while it runs inside the game view
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone)]
+#[reflect(Resource)]
+pub struct GameInputSettings {
+  /// Mouse presses outside of the game view are hidden from the game while testing
+  pub block_outside_clicks: bool,
+  /// Keeps the cursor inside the game view once it was clicked while testing, until escape is
+  /// pressed
+  pub confine_cursor: bool,
+}
+
+impl Default for GameInputSettings {
+  fn default() -> Self {
+    Self {
+      block_outside_clicks: true,
+      confine_cursor: false,
+    }
+  }
+}
+
+impl Saveable for GameInputSettings {
+  const KEY: &str = "game_input";
+}
+
+impl EditorSettings for GameInputSettings {
+  const NAME: &str = "Game Input";
+}
+
+/// The cursor of the primary window in the game view's viewport
+///
+/// Read this instead of [`Window::cursor_position`] for cursor math such as
+/// [`Camera::viewport_to_world`], the game then behaves the same embedded in the editor as in a
+/// standalone window
+#[derive(Resource, Default)]
+pub struct GameCursor {
+  position: Option<Vec2>,
+  /// Logical rect of the game view in the window, `None` while it isn't shown
+  viewport: Option<Rect>,
+  confined: bool,
+}
+
+impl GameCursor {
+  /// Logical position relative to the top left of the game's viewport, `None` when the cursor is
+  /// outside of it
+  pub fn position(&self) -> Option<Vec2> {
+    self.position
+  }
+
+  /// Logical size of the game's viewport, `None` while the game view isn't shown and the game
+  /// sees the whole window
+  pub fn viewport_size(&self) -> Option<Vec2> {
+    self.viewport.map(|viewport| viewport.size())
+  }
+
+  pub fn is_confined(&self) -> bool {
+    self.confined
+  }
+}
+
 #[derive(Component, Reflect)]
 pub struct GameView<C>
 where
@@ -45,6 +113,75 @@ where
     }
   }
 
+  /// Maps the window's cursor into the game view and keeps presses outside of it from the game
+  #[allow(clippy::too_many_arguments)]
+  fn update_cursor(
+    mut window: Single<&mut Window, With<PrimaryWindow>>,
+    egui_settings: Single<&bevy_egui::EguiSettings>,
+    q_game_views: Query<(&Self, &UiInfo)>,
+    settings: Res<GameInputSettings>,
+    editor_state: Res<State<EditorState>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut mouse_buttons: ResMut<ButtonInput<MouseButton>>,
+    mut cursor: ResMut<GameCursor>,
+  ) {
+    let scale = egui_settings.scale_factor;
+    let viewport = q_game_views
+      .iter()
+      .find(|(_, ui_info)| ui_info.rendered())
+      .map(|(game_view, _)| {
+        let viewport = game_view.viewport();
+        Rect::new(
+          viewport.min.x * scale,
+          viewport.min.y * scale,
+          viewport.max.x * scale,
+          viewport.max.y * scale,
+        )
+      });
+
+    cursor.viewport = viewport;
+
+    let Some(viewport) = viewport else {
+      // nothing is embedded, the game sees the window as it is
+      cursor.position = window.cursor_position();
+      cursor.confined = false;
+      return;
+    };
+
+    let testing = *editor_state.get() == EditorState::Testing;
+    let mut position = window.cursor_position();
+    let inside = position.is_some_and(|position| viewport.contains(position));
+
+    if !testing || !settings.confine_cursor || keys.just_pressed(KeyCode::Escape) {
+      cursor.confined = false;
+    } else if inside && mouse_buttons.just_pressed(MouseButton::Left) {
+      cursor.confined = true;
+    }
+
+    if cursor.confined && !inside {
+      if let Some(outside) = position {
+        let confined = outside.clamp(viewport.min, viewport.max - Vec2::ONE);
+        window.set_cursor_position(Some(confined));
+        position = Some(confined);
+      }
+    }
+
+    cursor.position = position
+      .filter(|position| viewport.contains(*position))
+      .map(|position| position - viewport.min);
+
+    if testing && settings.block_outside_clicks && cursor.position.is_none() {
+      // only new presses, drags that started in the game view keep going
+      let pressed = mouse_buttons
+        .get_just_pressed()
+        .copied()
+        .collect::<Vec<_>>();
+      for button in pressed {
+        mouse_buttons.reset(button);
+      }
+    }
+  }
+
   fn set_viewport(
     window: Single<&Window, With<PrimaryWindow>>,
     egui_settings: Single<&bevy_egui::EguiSettings>,
@@ -102,7 +239,12 @@ where
 
   fn init(app: &mut App) {
     app
-      .add_systems(PreUpdate, Self::on_preupdate)
+      .register_settings::<GameInputSettings>()
+      .init_resource::<GameCursor>()
+      .add_systems(
+        PreUpdate,
+        (Self::on_preupdate, Self::update_cursor.after(InputSystem)),
+      )
       .add_systems(PostUpdate, Self::set_viewport);
   }
 
@@ -1,16 +1,483 @@
-use crate::ui::{InspectorSelection, RawUi, SelectedEntities};
-use bevy::prelude::*;
-use bevy_egui::egui;
-use bevy_inspector_egui::bevy_inspector::hierarchy::hierarchy_ui;
+use crate::{
+  read_only::{self, ReadOnly},
+  scene_prefabs,
+  ui::{InspectorSelection, RawUi, SelectedEntities},
+};
+use bevy::{prelude::*, utils::HashSet};
+use bevy_egui::egui::{self, collapsing_header::CollapsingState};
+use bevy_inspector_egui::bevy_inspector::{guess_entity_name, hierarchy::SelectionMode};
 use uuid::{uuid, Uuid};
 
+/// Folder in the hierarchy that lists its members without parenting them, so grouping never
+/// changes their transforms
+///
+/// Only entities without a parent can be members, children stay listed under their parent
+#[derive(Component, Reflect, Default, Clone)]
+#[reflect(Component)]
+#[require(Name(|| Name::new("Group")))]
+pub struct EditorGroup {
+  pub hidden: bool,
+  /// Members of a locked group can't be selected nor moved to another group
+  pub locked: bool,
+}
+
+/// Group the entity is listed under in the hierarchy
+#[derive(Component, Reflect, Clone, Copy)]
+#[reflect(Component)]
+pub struct InGroup(pub Entity);
+
+/// Visibility a member of a hidden group had before the group was hidden
+#[derive(Component, Reflect, Clone, Copy)]
+#[reflect(Component)]
+pub struct HiddenByGroup(pub Visibility);
+
+impl EditorGroup {
+  /// Whether the entity belongs to a locked group
+  pub fn is_locked(
+    entity: Entity,
+    q_members: &Query<&InGroup>,
+    q_groups: &Query<&EditorGroup>,
+  ) -> bool {
+    q_members
+      .get(entity)
+      .is_ok_and(|InGroup(group)| q_groups.get(*group).is_ok_and(|group| group.locked))
+  }
+
+  fn members(world: &mut World, group: Entity) -> Vec<Entity> {
+    let mut members = world
+      .query::<(Entity, &InGroup)>()
+      .iter(world)
+      .filter(|(_, in_group)| in_group.0 == group)
+      .map(|(entity, _)| entity)
+      .collect::<Vec<_>>();
+    members.sort();
+    members
+  }
+
+  fn set_hidden(world: &mut World, group: Entity, hidden: bool) {
+    if let Some(mut this) = world.get_mut::<Self>(group) {
+      this.hidden = hidden;
+    }
+
+    for member in Self::members(world, group) {
+      if hidden {
+        Self::hide(world, member);
+      } else {
+        Self::reveal(world, member);
+      }
+    }
+  }
+
+  fn hide(world: &mut World, member: Entity) {
+    let Some(mut visibility) = world.get_mut::<Visibility>(member) else {
+      return;
+    };
+
+    let previous = std::mem::replace(&mut *visibility, Visibility::Hidden);
+    if !world.entity(member).contains::<HiddenByGroup>() {
+      world.entity_mut(member).insert(HiddenByGroup(previous));
+    }
+  }
+
+  /// Gives the member back the visibility it had before its group was hidden
+  fn reveal(world: &mut World, member: Entity) {
+    let Some(HiddenByGroup(previous)) = world.entity_mut(member).take::<HiddenByGroup>() else {
+      return;
+    };
+
+    if let Some(mut visibility) = world.get_mut::<Visibility>(member) {
+      *visibility = previous;
+    }
+  }
+
+  fn leave(world: &mut World, member: Entity) {
+    Self::reveal(world, member);
+    world.entity_mut(member).remove::<InGroup>();
+  }
+
+  /// Removes the group and lists its members at the top level again
+  fn dissolve(world: &mut World, group: Entity) {
+    for member in Self::members(world, group) {
+      Self::leave(world, member);
+    }
+    world.despawn(group);
+  }
+}
+
+/// Payload of an entity being dragged in the hierarchy
+struct DraggedEntity(Entity);
+
+enum GroupAction {
+  Join(Entity, Entity),
+  Leave(Entity),
+  Hide(Entity, bool),
+  Lock(Entity, bool),
+  Dissolve(Entity),
+}
+
+/// Entity tree like the one of `bevy_inspector_egui` with top level entities sorted into groups
+struct Tree<'a> {
+  world: &'a mut World,
+  selected: &'a mut SelectedEntities,
+  writable: bool,
+  always_open: HashSet<Entity>,
+  actions: Vec<GroupAction>,
+}
+
+impl<'a> Tree<'a> {
+  fn new(world: &'a mut World, selected: &'a mut SelectedEntities) -> Self {
+    // parents and groups of the selection are kept open so the selection is always visible
+    let always_open = selected
+      .iter()
+      .flat_map(|selected| {
+        std::iter::successors(Some(selected), |&entity| {
+          world
+            .get::<Parent>(entity)
+            .map(Parent::get)
+            .or_else(|| world.get::<InGroup>(entity).map(|in_group| in_group.0))
+        })
+        .skip(1)
+      })
+      .collect();
+
+    Self {
+      writable: ReadOnly::writable(world),
+      world,
+      selected,
+      always_open,
+      actions: default(),
+    }
+  }
+
+  /// Returns true if the selection changed
+  fn show(mut self, ui: &mut egui::Ui) -> bool {
+    let mut q_roots = self
+      .world
+      .query_filtered::<(Entity, Option<&InGroup>, Has<EditorGroup>), Without<Parent>>();
+
+    let mut roots = q_roots
+      .iter(self.world)
+      .filter(|(_, in_group, _)| {
+        // the group may have been despawned without its members, by a testing restore or the game
+        in_group.is_none_or(|in_group| {
+          !self
+            .world
+            .get_entity(in_group.0)
+            .is_ok_and(|group| group.contains::<EditorGroup>())
+        })
+      })
+      .map(|(entity, _, is_group)| (!is_group, entity))
+      .collect::<Vec<_>>();
+    roots.sort();
+    let roots = roots
+      .into_iter()
+      .map(|(_, entity)| entity)
+      .collect::<Vec<_>>();
+
+    let mut new_selection = false;
+    for &entity in &roots {
+      new_selection |= self.entity_ui(ui, entity, &roots, false);
+    }
+
+    for action in std::mem::take(&mut self.actions) {
+      match action {
+        GroupAction::Join(entity, group) => {
+          let joinable = entity != group
+            && !self.world.entity(entity).contains::<EditorGroup>()
+            && !self.world.entity(entity).contains::<Parent>();
+          if joinable {
+            self.world.entity_mut(entity).insert(InGroup(group));
+          }
+        }
+        GroupAction::Leave(entity) => EditorGroup::leave(self.world, entity),
+        GroupAction::Hide(group, hidden) => EditorGroup::set_hidden(self.world, group, hidden),
+        GroupAction::Lock(group, locked) => {
+          if let Some(mut group) = self.world.get_mut::<EditorGroup>(group) {
+            group.locked = locked;
+          }
+        }
+        GroupAction::Dissolve(group) => EditorGroup::dissolve(self.world, group),
+      }
+    }
+
+    new_selection
+  }
+
+  fn entity_ui(
+    &mut self,
+    ui: &mut egui::Ui,
+    entity: Entity,
+    at_same_level: &[Entity],
+    locked: bool,
+  ) -> bool {
+    let group = self.world.get::<EditorGroup>(entity).cloned();
+    let children = match group {
+      Some(_) => EditorGroup::members(self.world, entity),
+      None => self
+        .world
+        .get::<Children>(entity)
+        .map(|children| children.to_vec())
+        .unwrap_or_default(),
+    };
+
+    let mut new_selection = false;
+
+    if children.is_empty() {
+      ui.horizontal(|ui| {
+        ui.add_space(ui.spacing().indent);
+        new_selection |= self.row_ui(ui, entity, group.as_ref(), at_same_level, locked);
+      });
+      return new_selection;
+    }
+
+    let id = ui.make_persistent_id(entity);
+    let mut state = CollapsingState::load_with_default_open(ui.ctx(), id, false);
+    if self.always_open.contains(&entity) {
+      state.set_open(true);
+    }
+
+    let locked = locked || group.as_ref().is_some_and(|group| group.locked);
+
+    state
+      .show_header(ui, |ui| {
+        new_selection |= self.row_ui(ui, entity, group.as_ref(), at_same_level, locked);
+      })
+      .body(|ui| {
+        for &child in &children {
+          new_selection |= self.entity_ui(ui, child, &children, locked);
+        }
+      });
+
+    new_selection
+  }
+
+  fn row_ui(
+    &mut self,
+    ui: &mut egui::Ui,
+    entity: Entity,
+    group: Option<&EditorGroup>,
+    at_same_level: &[Entity],
+    locked: bool,
+  ) -> bool {
+    let name = guess_entity_name(self.world, entity);
+    let label = egui::SelectableLabel::new(
+      self.selected.contains(entity),
+      match group {
+        Some(_) => format!("📁 {name}"),
+        None => name,
+      },
+    );
+
+    let draggable = group.is_none() && !locked && self.writable;
+    let response = if draggable {
+      ui.dnd_drag_source(
+        egui::Id::new(("hierarchy_drag", entity)),
+        DraggedEntity(entity),
+        |ui| ui.add(label),
+      )
+      .inner
+    } else {
+      ui.add_enabled(!locked || group.is_some(), label)
+    };
+
+    let mut new_selection = false;
+    if response.clicked() {
+      let selection_mode = ui
+        .input(|input| SelectionMode::from_ctrl_shift(input.modifiers.ctrl, input.modifiers.shift));
+      let extend_with = |from, to| {
+        let from_position = at_same_level.iter().position(|&entity| entity == from);
+        let to_position = at_same_level.iter().position(|&entity| entity == to);
+        from_position
+          .zip(to_position)
+          .map(|(from, to)| {
+            let (min, max) = if from < to { (from, to) } else { (to, from) };
+            at_same_level[min..=max].iter().copied()
+          })
+          .into_iter()
+          .flatten()
+      };
+      self.selected.select(selection_mode, entity, extend_with);
+      new_selection = true;
+    }
+
+    let Some(group) = group else {
+      if self.world.entity(entity).contains::<InGroup>() {
+        response.context_menu(|ui| {
+          if ui
+            .add_enabled(
+              self.writable && !locked,
+              egui::Button::new("Remove from Group"),
+            )
+            .clicked()
+          {
+            self.actions.push(GroupAction::Leave(entity));
+            ui.close_menu();
+          }
+        });
+      }
+      return new_selection;
+    };
+
+    if self.writable {
+      if response.dnd_hover_payload::<DraggedEntity>().is_some() {
+        let stroke = ui.visuals().selection.stroke;
+        ui.painter().rect_stroke(response.rect, 2.0, stroke);
+      }
+      if let Some(dragged) = response.dnd_release_payload::<DraggedEntity>() {
+        self.actions.push(GroupAction::Join(dragged.0, entity));
+      }
+    }
+
+    ui.add_enabled_ui(self.writable, |ui| {
+      let hide = if group.hidden { "🚫" } else { "👁" };
+      if ui
+        .small_button(hide)
+        .on_hover_text("Show or hide every member")
+        .clicked()
+      {
+        self.actions.push(GroupAction::Hide(entity, !group.hidden));
+      }
+
+      let lock = if group.locked { "🔒" } else { "🔓" };
+      if ui
+        .small_button(lock)
+        .on_hover_text("Lock or unlock selecting the members")
+        .clicked()
+      {
+        self.actions.push(GroupAction::Lock(entity, !group.locked));
+      }
+    });
+
+    response.context_menu(|ui| {
+      if ui
+        .add_enabled(self.writable, egui::Button::new("Ungroup"))
+        .clicked()
+      {
+        self.actions.push(GroupAction::Dissolve(entity));
+        ui.close_menu();
+      }
+    });
+
+    new_selection
+  }
+}
+
 #[derive(Default, Component, Reflect)]
-pub struct Hierarchy;
+pub struct Hierarchy {
+  #[reflect(ignore)]
+  convert: Option<ConvertToPrefab>,
+}
+
+struct ConvertToPrefab {
+  entity: Entity,
+  name: String,
+  error: Option<String>,
+}
+
+impl Hierarchy {
+  fn selection_menu_ui(&mut self, ui: &mut egui::Ui, world: &mut World) {
+    let selected_entities = match world.resource::<InspectorSelection>() {
+      InspectorSelection::Entities(selected) => selected.iter().collect::<Vec<_>>(),
+      _ => Vec::new(),
+    };
+    let selected = match selected_entities.as_slice() {
+      &[entity] => Some(entity),
+      _ => None,
+    };
+
+    ui.add_enabled_ui(!selected_entities.is_empty(), |ui| {
+      ui.menu_button("Selection", |ui| {
+        if ui
+          .add_enabled(ReadOnly::writable(world), egui::Button::new("Group"))
+          .on_hover_text("Put the selected top level entities in a new group")
+          .on_disabled_hover_text(read_only::REASON)
+          .clicked()
+        {
+          let group = world.spawn(EditorGroup::default()).id();
+          for entity in selected_entities {
+            let entity_ref = world.entity(entity);
+            if !entity_ref.contains::<Parent>() && !entity_ref.contains::<EditorGroup>() {
+              world.entity_mut(entity).insert(InGroup(group));
+            }
+          }
+          ui.close_menu();
+        }
+
+        if ui
+          .add_enabled(
+            selected.is_some() && ReadOnly::writable(world),
+            egui::Button::new("Convert to Prefab…"),
+          )
+          .on_disabled_hover_text(read_only::REASON)
+          .clicked()
+        {
+          if let Some(entity) = selected {
+            let name = world
+              .get::<Name>(entity)
+              .map(|name| name.as_str().replace(' ', "_"))
+              .unwrap_or_default();
+
+            self.convert = Some(ConvertToPrefab {
+              entity,
+              name,
+              error: None,
+            });
+          }
+          ui.close_menu();
+        }
+      });
+    });
+  }
+
+  fn convert_ui(&mut self, ui: &mut egui::Ui, world: &mut World) {
+    let Some(convert) = self.convert.as_mut() else {
+      return;
+    };
+
+    let mut close = false;
+
+    ui.horizontal(|ui| {
+      ui.label("Prefab name");
+      ui.text_edit_singleline(&mut convert.name);
+
+      if ui.button("Convert").clicked() {
+        match scene_prefabs::convert_to_prefab(convert.entity, &convert.name, world) {
+          Ok(instance) => {
+            world
+              .resource_mut::<InspectorSelection>()
+              .add_selected(instance, false);
+            close = true;
+          }
+          Err(err) => convert.error = Some(err.to_string()),
+        }
+      }
+
+      close |= ui.button("Cancel").clicked();
+    });
+
+    if let Some(err) = &convert.error {
+      ui.colored_label(egui::Color32::RED, err);
+    }
+
+    if close {
+      self.convert = None;
+    }
+
+    ui.separator();
+  }
+}
 
 impl RawUi for Hierarchy {
   const NAME: &str = stringify!(Hierarchy);
   const ID: Uuid = uuid!("860ac319-5c6e-4a2e-83ae-8bb0000d5cb4");
 
+  fn init(app: &mut App) {
+    app
+      .register_type::<EditorGroup>()
+      .register_type::<InGroup>()
+      .register_type::<HiddenByGroup>();
+  }
+
   fn spawn(_entity: Entity, _world: &mut World) -> Self {
     default()
   }
@@ -19,13 +486,29 @@ impl RawUi for Hierarchy {
     true
   }
 
-  fn render(_entity: Entity, ui: &mut egui::Ui, world: &mut World) {
+  fn pausable() -> bool {
+    true
+  }
+
+  fn render(entity: Entity, ui: &mut egui::Ui, world: &mut World) {
+    if let Some(mut this) = world
+      .get_mut::<Self>(entity)
+      .map(|mut this| std::mem::take(&mut *this))
+    {
+      this.selection_menu_ui(ui, world);
+      this.convert_ui(ui, world);
+
+      if let Some(mut current) = world.get_mut::<Self>(entity) {
+        *current = this;
+      }
+    }
+
     world.resource_scope(|world, mut selection: Mut<InspectorSelection>| {
       if let InspectorSelection::Entities(selected_entities) = selection.as_mut() {
-        hierarchy_ui(world, ui, selected_entities);
+        Tree::new(world, selected_entities).show(ui);
       } else {
         let mut selected_entities = SelectedEntities::default();
-        if hierarchy_ui(world, ui, &mut selected_entities) {
+        if Tree::new(world, &mut selected_entities).show(ui) {
           *selection = InspectorSelection::Entities(selected_entities);
         }
       }
@@ -1,12 +1,35 @@
-use crate::ui::{InspectorSelection, RawUi};
-use bevy::prelude::*;
-use bevy_egui::egui;
-use bevy_inspector_egui::bevy_inspector::{
-  by_type_id::{ui_for_asset, ui_for_resource},
-  ui_for_entities_shared_components, ui_for_entity_with_children,
+use crate::{
+  cache::{Cache, Saveable},
+  read_only::{self, ReadOnly},
+  ui::{
+    component_toggle,
+    type_search::{TypeKind, TypeSearch},
+    InspectorSelection, RawUi,
+  },
+  util,
+  view::camera_preview,
 };
+use bevy::{
+  asset::ReflectAsset,
+  ecs::{component::ComponentId, world::CommandQueue},
+  prelude::*,
+  reflect::TypeRegistry,
+};
+use bevy_egui::egui::{self, collapsing_header::CollapsingState};
+use bevy_inspector_egui::{
+  bevy_inspector::{
+    by_type_id::{ui_for_asset, ui_for_resource},
+    guess_entity_name, ui_for_entities_shared_components,
+  },
+  reflect_inspector::{Context, InspectorUi},
+  restricted_world_view::RestrictedWorldView,
+};
+use serde::{Deserialize, Serialize};
+use std::{any::TypeId, collections::BTreeSet};
 use uuid::{uuid, Uuid};
 
+const MAX_SUGGESTIONS: usize = 8;
+
 #[derive(Default, Component, Reflect)]
 pub struct Inspector;
 
@@ -14,6 +37,12 @@ impl RawUi for Inspector {
   const NAME: &str = stringify!(Inspector);
   const ID: Uuid = uuid!("10bb68b8-c247-4792-89e9-61d1b9682a72");
 
+  fn init(app: &mut App) {
+    app
+      .init_resource::<InspectorLayout>()
+      .add_systems(Startup, InspectorLayout::load);
+  }
+
   fn spawn(_entity: Entity, _world: &mut World) -> Self {
     default()
   }
@@ -22,25 +51,439 @@ impl RawUi for Inspector {
     true
   }
 
+  fn pausable() -> bool {
+    true
+  }
+
   fn render(_entity: Entity, ui: &mut egui::Ui, world: &mut World) {
     let type_registry = world.resource::<AppTypeRegistry>().0.clone();
     let type_registry = type_registry.read();
 
+    let writable = ReadOnly::writable(world);
+    if !writable {
+      ui.weak("Read-only, values can't be changed")
+        .on_hover_text(read_only::REASON);
+    }
+
+    // values are drawn read-only rather than disabling the ui, so sections can still be expanded
     world.resource_scope(
       |world, selection: Mut<InspectorSelection>| match selection.as_ref() {
         InspectorSelection::Entities(selected_entities) => match selected_entities.as_slice() {
-          &[entity] => ui_for_entity_with_children(world, entity, ui),
-          entities => ui_for_entities_shared_components(world, entities, ui),
+          &[entity] => {
+            ui.add_enabled_ui(writable, |ui| {
+              component_toggle::ui(ui, world, entity);
+              camera_preview::ui(ui, world, entity);
+            });
+            ui.label(guess_entity_name(world, entity));
+            entity_ui(world, entity, ui, egui::Id::new(entity), &type_registry);
+            if writable {
+              add_component_ui(ui, world, &[entity], &type_registry);
+            }
+          }
+          entities if writable => {
+            ui_for_entities_shared_components(world, entities, ui);
+            add_component_ui(ui, world, entities, &type_registry);
+          }
+          entities => {
+            for &entity in entities {
+              let id = egui::Id::new(entity);
+              egui::CollapsingHeader::new(guess_entity_name(world, entity))
+                .id_salt(id)
+                .show(ui, |ui| {
+                  entity_ui(world, entity, ui, id, &type_registry);
+                });
+            }
+          }
         },
         InspectorSelection::Resource(type_id, ref name) => {
           ui.label(name);
-          ui_for_resource(world, *type_id, ui, name, &type_registry)
+          if writable {
+            ui_for_resource(world, *type_id, ui, name, &type_registry)
+          } else {
+            let resource = type_registry
+              .get_type_data::<ReflectResource>(*type_id)
+              .and_then(|reflect_resource| reflect_resource.reflect(world));
+            readonly_ui(resource, ui, &type_registry);
+          }
         }
         InspectorSelection::Asset(type_id, ref name, handle) => {
           ui.label(name);
-          ui_for_asset(world, *type_id, *handle, ui, &type_registry);
+          if writable {
+            ui_for_asset(world, *type_id, *handle, ui, &type_registry);
+          } else {
+            let asset = type_registry
+              .get_type_data::<ReflectAsset>(*type_id)
+              .and_then(|reflect_asset| reflect_asset.get(world, UntypedHandle::Weak(*handle)));
+            readonly_ui(asset, ui, &type_registry);
+          }
         }
       },
     );
   }
 }
+
+fn readonly_ui(value: Option<&dyn Reflect>, ui: &mut egui::Ui, type_registry: &TypeRegistry) {
+  match value {
+    Some(value) => InspectorUi::for_bevy(type_registry, &mut Context::default())
+      .ui_for_reflect_readonly(value.as_partial_reflect(), ui),
+    None => {
+      ui.weak("Not found");
+    }
+  }
+}
+
+/// Order and collapse state of the component sections, by component type
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub(crate) struct InspectorLayout {
+  /// Components dragged into place, others are listed after these by name
+  order: Vec<String>,
+  collapsed: BTreeSet<String>,
+}
+
+impl Saveable for InspectorLayout {
+  const KEY: &str = "inspector_layout";
+}
+
+impl InspectorLayout {
+  fn load(mut layout: ResMut<Self>, cache: Res<Cache>) {
+    if let Some(saved) = cache.get::<Self>() {
+      *layout = saved;
+    }
+  }
+
+  pub(crate) fn on_app_exit(world: &mut World) {
+    world.resource_scope(|world, layout: Mut<Self>| {
+      world.resource_mut::<Cache>().store(layout.as_ref());
+    });
+  }
+
+  fn sort(&self, sections: &mut [Section]) {
+    sections.sort_by_cached_key(|section| {
+      let position = self
+        .order
+        .iter()
+        .position(|type_path| *type_path == section.type_path)
+        .unwrap_or(usize::MAX);
+      (position, section.name.clone())
+    });
+  }
+
+  /// Puts `dragged` right before `target`, keeping the order of every other section on screen
+  fn move_before(&mut self, sections: &[Section], dragged: &str, target: &str) {
+    let mut order = sections
+      .iter()
+      .map(|section| section.type_path.clone())
+      .filter(|type_path| type_path != dragged)
+      .collect::<Vec<_>>();
+
+    let index = order
+      .iter()
+      .position(|type_path| type_path == target)
+      .unwrap_or(order.len());
+    order.insert(index, dragged.to_string());
+
+    // components this entity doesn't have keep their place after the ones on screen
+    order.extend(self.order.drain(..).filter(|type_path| {
+      sections
+        .iter()
+        .all(|section| section.type_path != *type_path)
+    }));
+
+    self.order = order;
+  }
+
+  fn set_collapsed(&mut self, type_path: &str, collapsed: bool) {
+    if collapsed {
+      self.collapsed.insert(type_path.to_string());
+    } else {
+      self.collapsed.remove(type_path);
+    }
+  }
+}
+
+struct Section {
+  name: String,
+  type_path: String,
+  component_id: ComponentId,
+  type_id: Option<TypeId>,
+  size: usize,
+}
+
+/// Payload of a component header being dragged
+struct DraggedSection(String);
+
+fn sections_of(
+  world: &World,
+  entity: Entity,
+  type_registry: &TypeRegistry,
+) -> Option<Vec<Section>> {
+  let entity_ref = world.get_entity(entity).ok()?;
+
+  let sections = entity_ref
+    .archetype()
+    .components()
+    .filter_map(|component_id| {
+      let info = world.components().get_info(component_id)?;
+      let name = info
+        .type_id()
+        .and_then(|type_id| type_registry.get(type_id))
+        .map(|registration| {
+          registration
+            .type_info()
+            .type_path_table()
+            .short_path()
+            .to_string()
+        })
+        .unwrap_or_else(|| info.name().to_string());
+
+      Some(Section {
+        name,
+        type_path: info.name().to_string(),
+        component_id,
+        type_id: info.type_id(),
+        size: info.layout().size(),
+      })
+    })
+    .collect();
+
+  Some(sections)
+}
+
+/// Components of the entity in the user's order followed by its children
+fn entity_ui(
+  world: &mut World,
+  entity: Entity,
+  ui: &mut egui::Ui,
+  id: egui::Id,
+  type_registry: &TypeRegistry,
+) {
+  let mut queue = CommandQueue::default();
+
+  world.resource_scope(|world, mut layout: Mut<InspectorLayout>| {
+    components_ui(
+      world,
+      &mut queue,
+      entity,
+      ui,
+      id,
+      type_registry,
+      &mut layout,
+    );
+  });
+
+  let children = world
+    .get::<Children>(entity)
+    .map(|children| children.iter().copied().collect::<Vec<_>>())
+    .unwrap_or_default();
+
+  if !children.is_empty() {
+    ui.label("Children");
+    for child in children {
+      let id = id.with(child);
+      let child_name = guess_entity_name(world, child);
+      egui::CollapsingHeader::new(&child_name)
+        .id_salt(id)
+        .show(ui, |ui| {
+          ui.label(&child_name);
+          entity_ui(world, child, ui, id, type_registry);
+        });
+    }
+  }
+
+  queue.apply(world);
+}
+
+fn components_ui(
+  world: &mut World,
+  queue: &mut CommandQueue,
+  entity: Entity,
+  ui: &mut egui::Ui,
+  id: egui::Id,
+  type_registry: &TypeRegistry,
+  layout: &mut InspectorLayout,
+) {
+  let writable = ReadOnly::writable(world);
+  let Some(mut sections) = sections_of(world, entity, type_registry) else {
+    ui.weak(format!("Entity {entity} does not exist"));
+    return;
+  };
+  layout.sort(&mut sections);
+
+  let mut world = RestrictedWorldView::new(world);
+  let mut dropped = None;
+
+  for section in &sections {
+    let id = id.with(section.component_id);
+
+    let state = CollapsingState::load_with_default_open(
+      ui.ctx(),
+      id,
+      !layout.collapsed.contains(&section.type_path),
+    );
+
+    let (_, header, _) = state
+      .show_header(ui, |ui| {
+        ui.dnd_drag_source(
+          id.with("drag"),
+          DraggedSection(section.type_path.clone()),
+          |ui| ui.label("☰"),
+        )
+        .response
+        .on_hover_text("Drag to reorder");
+        ui.label(&section.name);
+      })
+      .body(|ui| {
+        let Some(type_id) = section.type_id.filter(|_| section.size > 0) else {
+          return;
+        };
+
+        // the component is edited while the rest of the world stays available to its fields
+        let (mut component_view, world) = world.split_off_component((entity, type_id));
+        let mut cx = Context {
+          world: Some(world),
+          queue: Some(queue),
+        };
+
+        match component_view.get_entity_component_reflect(entity, type_id, type_registry) {
+          Ok(value) if !writable => {
+            InspectorUi::for_bevy(type_registry, &mut cx).ui_for_reflect_readonly_with_options(
+              value.as_partial_reflect(),
+              ui,
+              id.with("value"),
+              &(),
+            );
+          }
+          Ok(mut value) => {
+            let changed = InspectorUi::for_bevy(type_registry, &mut cx)
+              .ui_for_reflect_with_options(
+                value.bypass_change_detection().as_partial_reflect_mut(),
+                ui,
+                id.with("value"),
+                &(),
+              );
+            if changed {
+              value.set_changed();
+            }
+          }
+          Err(err) => {
+            ui.weak(format!("{err:?}"));
+          }
+        }
+      });
+
+    let header = header.response;
+    if header.dnd_hover_payload::<DraggedSection>().is_some() {
+      let stroke = ui.visuals().selection.stroke;
+      ui.painter()
+        .hline(header.rect.x_range(), header.rect.top(), stroke);
+    }
+    if let Some(dragged) = header.dnd_release_payload::<DraggedSection>() {
+      dropped = Some((dragged.0.clone(), section.type_path.clone()));
+    }
+
+    let open = CollapsingState::load(ui.ctx(), id).is_some_and(|state| state.is_open());
+    layout.set_collapsed(&section.type_path, !open);
+  }
+
+  if let Some((dragged, target)) = dropped.filter(|(dragged, target)| dragged != target) {
+    layout.move_before(&sections, &dragged, &target);
+  }
+}
+
+/// Search box that adds the chosen component with its default value to every entity that lacks
+/// it, Enter picks the best match
+fn add_component_ui(
+  ui: &mut egui::Ui,
+  world: &mut World,
+  entities: &[Entity],
+  type_registry: &TypeRegistry,
+) {
+  let id = egui::Id::new("inspector_add_component");
+  let mut search = ui
+    .data(|data| data.get_temp::<String>(id))
+    .unwrap_or_default();
+
+  ui.separator();
+  let response = ui.add(
+    egui::TextEdit::singleline(&mut search)
+      .hint_text("Add component…")
+      .desired_width(f32::INFINITY),
+  );
+  let submitted = response.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter));
+
+  let mut chosen = None;
+
+  if !search.is_empty() {
+    let matches = world.resource_scope(|world, mut type_search: Mut<TypeSearch>| {
+      type_search
+        .search(type_registry, TypeKind::DefaultComponent, &search)
+        .filter(|entry| !util::is_hierarchy_component(entry.type_id))
+        .filter(|entry| {
+          !entities.iter().all(|entity| {
+            world
+              .get_entity(*entity)
+              .is_ok_and(|entity| entity.contains_type_id(entry.type_id))
+          })
+        })
+        .take(MAX_SUGGESTIONS)
+        .map(|entry| (entry.name.clone(), entry.type_id))
+        .collect::<Vec<_>>()
+    });
+
+    if submitted {
+      chosen = matches.first().map(|(_, type_id)| *type_id);
+    }
+
+    for (name, type_id) in &matches {
+      if ui.selectable_label(false, name).clicked() {
+        chosen = Some(*type_id);
+      }
+    }
+
+    if matches.is_empty() {
+      ui.weak("No component with a default value matches");
+    }
+  }
+
+  if let Some(type_id) = chosen {
+    // entities that already have the component keep their values
+    for entity in entities {
+      let missing = world
+        .get_entity(*entity)
+        .is_ok_and(|entity| !entity.contains_type_id(type_id));
+      if missing {
+        insert_default(world, *entity, type_id, type_registry);
+      }
+    }
+    search.clear();
+  }
+
+  ui.data_mut(|data| data.insert_temp(id, search));
+}
+
+fn insert_default(
+  world: &mut World,
+  entity: Entity,
+  type_id: TypeId,
+  type_registry: &TypeRegistry,
+) {
+  let Some(registration) = type_registry.get(type_id) else {
+    return;
+  };
+  let Some(reflect_component) = registration.data::<ReflectComponent>() else {
+    return;
+  };
+
+  let value = match (
+    registration.data::<ReflectDefault>(),
+    registration.data::<ReflectFromWorld>(),
+  ) {
+    (Some(default), _) => default.default(),
+    (None, Some(from_world)) => from_world.from_world(world),
+    (None, None) => return,
+  };
+
+  if let Ok(mut entity_mut) = world.get_entity_mut(entity) {
+    reflect_component.insert(&mut entity_mut, value.as_partial_reflect(), type_registry);
+  }
+}
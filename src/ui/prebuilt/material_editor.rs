@@ -0,0 +1,590 @@
+use crate::{
+  read_only::{self, ReadOnly},
+  ui::{InspectorSelection, RawUi},
+  view::UP,
+};
+use bevy::{
+  asset::RenderAssetUsages,
+  prelude::*,
+  render::{
+    camera::RenderTarget,
+    render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+    view::RenderLayers,
+  },
+};
+use bevy_egui::{egui, EguiUserTextures};
+use uuid::{uuid, Uuid};
+
+/// Layer the material preview is rendered on so it never shows up in the other views
+pub const PREVIEW_LAYER: usize = 63;
+
+const PREVIEW_SIZE: u32 = 256;
+const THUMBNAIL_SIZE: f32 = 48.0;
+
+/// Marks the camera, light and mesh used to render the material preview
+#[derive(Component)]
+pub(crate) struct MaterialPreview;
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Reflect)]
+enum PreviewShape {
+  #[default]
+  Sphere,
+  Cube,
+}
+
+/// Edits a [`StandardMaterial`] with its properties grouped and a live preview
+#[derive(Component, Reflect)]
+pub struct MaterialEditor {
+  material: Option<AssetId<StandardMaterial>>,
+  shape: PreviewShape,
+  #[reflect(ignore)]
+  preview: Option<Preview>,
+}
+
+struct Preview {
+  image: Handle<Image>,
+  texture: egui::TextureId,
+  camera: Entity,
+  light: Entity,
+  mesh: Entity,
+  shape: PreviewShape,
+}
+
+impl MaterialEditor {
+  fn spawn_preview(parent: Entity, world: &mut World) -> Preview {
+    let size = Extent3d {
+      width: PREVIEW_SIZE,
+      height: PREVIEW_SIZE,
+      depth_or_array_layers: 1,
+    };
+
+    let mut image = Image::new_fill(
+      size,
+      TextureDimension::D2,
+      &[0, 0, 0, 0],
+      TextureFormat::Bgra8UnormSrgb,
+      RenderAssetUsages::default(),
+    );
+    image.texture_descriptor.usage =
+      TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT;
+
+    let image = world.resource_mut::<Assets<Image>>().add(image);
+    let texture = world
+      .resource_mut::<EguiUserTextures>()
+      .add_image(image.clone());
+
+    let layer = RenderLayers::layer(PREVIEW_LAYER);
+
+    let camera = world
+      .spawn((
+        Name::new("Material Preview Camera"),
+        MaterialPreview,
+        Camera3d::default(),
+        Camera {
+          target: RenderTarget::Image(image.clone()),
+          clear_color: ClearColorConfig::Custom(Color::NONE),
+          order: -1,
+          is_active: false,
+          ..default()
+        },
+        Transform::from_xyz(0.0, 0.0, 3.0).looking_at(Vec3::ZERO, UP),
+        layer.clone(),
+      ))
+      .set_parent(parent)
+      .id();
+
+    let light = world
+      .spawn((
+        Name::new("Material Preview Light"),
+        MaterialPreview,
+        DirectionalLight::default(),
+        Transform::from_xyz(2.0, 3.0, 4.0).looking_at(Vec3::ZERO, UP),
+        layer.clone(),
+      ))
+      .set_parent(parent)
+      .id();
+
+    let mesh = world
+      .resource_mut::<Assets<Mesh>>()
+      .add(Self::mesh_of(PreviewShape::Sphere));
+
+    let mesh = world
+      .spawn((
+        Name::new("Material Preview Mesh"),
+        MaterialPreview,
+        Mesh3d(mesh),
+        Transform::default(),
+        Visibility::Hidden,
+        layer,
+      ))
+      .set_parent(parent)
+      .id();
+
+    Preview {
+      image,
+      texture,
+      camera,
+      light,
+      mesh,
+      shape: PreviewShape::Sphere,
+    }
+  }
+
+  fn mesh_of(shape: PreviewShape) -> Mesh {
+    match shape {
+      PreviewShape::Sphere => Sphere::new(1.0).mesh().uv(64, 32),
+      PreviewShape::Cube => Cuboid::from_length(1.4).into(),
+    }
+  }
+
+  /// Puts the edited material and shape on the preview mesh
+  fn sync_preview(&mut self, world: &mut World) {
+    let Some(preview) = self.preview.as_mut() else {
+      return;
+    };
+
+    if preview.shape != self.shape {
+      preview.shape = self.shape;
+      let mesh = world
+        .resource_mut::<Assets<Mesh>>()
+        .add(Self::mesh_of(self.shape));
+      world.entity_mut(preview.mesh).insert(Mesh3d(mesh));
+    }
+
+    let handle = self.material.and_then(|id| {
+      world
+        .resource_mut::<Assets<StandardMaterial>>()
+        .get_strong_handle(id)
+    });
+
+    let mut entity = world.entity_mut(preview.mesh);
+    match handle {
+      Some(handle) => {
+        if entity
+          .get::<MeshMaterial3d<StandardMaterial>>()
+          .map(|m| m.id())
+          != Some(handle.id())
+        {
+          entity.insert((MeshMaterial3d(handle), Visibility::Inherited));
+        }
+      }
+      None => {
+        entity.insert(Visibility::Hidden);
+      }
+    }
+  }
+
+  fn set_preview_active(entity: Entity, world: &mut World, active: bool) {
+    let Some(camera) = world
+      .get::<Self>(entity)
+      .and_then(|this| this.preview.as_ref())
+      .map(|preview| preview.camera)
+    else {
+      return;
+    };
+
+    if let Some(mut camera) = world.get_mut::<Camera>(camera) {
+      if camera.is_active != active {
+        camera.is_active = active;
+      }
+    }
+  }
+
+  fn material_name(id: AssetId<StandardMaterial>, world: &World) -> String {
+    world
+      .resource::<AssetServer>()
+      .get_path(id)
+      .map(|path| path.to_string())
+      .unwrap_or_else(|| format!("{id}"))
+  }
+
+  fn picker_ui(&mut self, ui: &mut egui::Ui, world: &World) {
+    let selected_material = match world.resource::<InspectorSelection>() {
+      InspectorSelection::Entities(selected) => selected
+        .iter()
+        .next()
+        .and_then(|entity| world.get::<MeshMaterial3d<StandardMaterial>>(entity))
+        .map(|material| material.id()),
+      _ => None,
+    };
+
+    ui.horizontal(|ui| {
+      let current = self
+        .material
+        .map(|id| Self::material_name(id, world))
+        .unwrap_or_else(|| "None".into());
+
+      egui::ComboBox::from_id_salt("material-editor-picker")
+        .selected_text(current)
+        .show_ui(ui, |ui| {
+          let materials = world.resource::<Assets<StandardMaterial>>();
+          let mut ids = materials
+            .ids()
+            .map(|id| (Self::material_name(id, world), id))
+            .collect::<Vec<_>>();
+          ids.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+          for (name, id) in ids {
+            ui.selectable_value(&mut self.material, Some(id), name);
+          }
+        });
+
+      if ui
+        .add_enabled(
+          selected_material.is_some(),
+          egui::Button::new("From Selection"),
+        )
+        .clicked()
+      {
+        self.material = selected_material;
+      }
+    });
+  }
+
+  fn preview_ui(&mut self, ui: &mut egui::Ui) {
+    let Some(preview) = self.preview.as_ref() else {
+      return;
+    };
+
+    ui.horizontal(|ui| {
+      ui.image(egui::load::SizedTexture::new(
+        preview.texture,
+        egui::Vec2::splat(PREVIEW_SIZE as f32 / 2.0),
+      ));
+
+      ui.vertical(|ui| {
+        ui.selectable_value(&mut self.shape, PreviewShape::Sphere, "Sphere");
+        ui.selectable_value(&mut self.shape, PreviewShape::Cube, "Cube");
+      });
+    });
+  }
+
+  /// Returns true if the material was changed
+  fn properties_ui(material: &mut StandardMaterial, ui: &mut egui::Ui, world: &mut World) -> bool {
+    let mut changed = false;
+
+    egui::CollapsingHeader::new("Base Color")
+      .default_open(true)
+      .show(ui, |ui| {
+        changed |= Self::color_ui(ui, &mut material.base_color);
+        changed |= Self::texture_slot(ui, "Texture", &mut material.base_color_texture, world);
+      });
+
+    egui::CollapsingHeader::new("Metallic / Roughness")
+      .default_open(true)
+      .show(ui, |ui| {
+        changed |= ui
+          .add(egui::Slider::new(&mut material.metallic, 0.0..=1.0).text("Metallic"))
+          .changed();
+        changed |= ui
+          .add(egui::Slider::new(&mut material.perceptual_roughness, 0.089..=1.0).text("Roughness"))
+          .changed();
+        changed |= ui
+          .add(egui::Slider::new(&mut material.reflectance, 0.0..=1.0).text("Reflectance"))
+          .changed();
+        changed |= Self::texture_slot(
+          ui,
+          "Texture",
+          &mut material.metallic_roughness_texture,
+          world,
+        );
+      });
+
+    egui::CollapsingHeader::new("Emissive").show(ui, |ui| {
+      // split into a displayable color and an intensity since emissive values go above 1
+      let intensity = material
+        .emissive
+        .red
+        .max(material.emissive.green)
+        .max(material.emissive.blue);
+      let mut color = if intensity > 0.0 {
+        Color::from(material.emissive * intensity.recip())
+      } else {
+        Color::BLACK
+      };
+      let mut new_intensity = intensity;
+
+      let color_changed = Self::color_ui(ui, &mut color);
+      let intensity_changed = ui
+        .add(
+          egui::DragValue::new(&mut new_intensity)
+            .speed(0.1)
+            .range(0.0..=f32::MAX)
+            .prefix("Intensity "),
+        )
+        .changed();
+
+      if color_changed || intensity_changed {
+        // a color picked while the intensity is zero would stay black
+        if color_changed && new_intensity == 0.0 {
+          new_intensity = 1.0;
+        }
+        material.emissive = color.to_linear() * new_intensity;
+        changed = true;
+      }
+
+      changed |= ui
+        .add(
+          egui::Slider::new(&mut material.emissive_exposure_weight, 0.0..=1.0)
+            .text("Exposure Weight"),
+        )
+        .changed();
+      changed |= Self::texture_slot(ui, "Texture", &mut material.emissive_texture, world);
+    });
+
+    egui::CollapsingHeader::new("Alpha").show(ui, |ui| {
+      changed |= Self::alpha_mode_ui(ui, &mut material.alpha_mode);
+      changed |= ui
+        .checkbox(&mut material.double_sided, "Double Sided")
+        .changed();
+      changed |= ui.checkbox(&mut material.unlit, "Unlit").changed();
+    });
+
+    egui::CollapsingHeader::new("Surface").show(ui, |ui| {
+      changed |= Self::texture_slot(ui, "Normal Map", &mut material.normal_map_texture, world);
+      changed |= Self::texture_slot(ui, "Occlusion", &mut material.occlusion_texture, world);
+    });
+
+    changed
+  }
+
+  fn color_ui(ui: &mut egui::Ui, color: &mut Color) -> bool {
+    let mut rgba = color.to_srgba().to_f32_array();
+    let changed = ui.color_edit_button_rgba_unmultiplied(&mut rgba).changed();
+    if changed {
+      *color = Srgba::from_f32_array(rgba).into();
+    }
+    changed
+  }
+
+  fn alpha_mode_ui(ui: &mut egui::Ui, alpha_mode: &mut AlphaMode) -> bool {
+    let modes = [
+      AlphaMode::Opaque,
+      AlphaMode::Mask(0.5),
+      AlphaMode::Blend,
+      AlphaMode::Premultiplied,
+      AlphaMode::AlphaToCoverage,
+      AlphaMode::Add,
+      AlphaMode::Multiply,
+    ];
+
+    let mut changed = false;
+
+    egui::ComboBox::from_label("Alpha Mode")
+      .selected_text(format!("{alpha_mode:?}"))
+      .show_ui(ui, |ui| {
+        for mode in modes {
+          let selected = std::mem::discriminant(alpha_mode) == std::mem::discriminant(&mode);
+          if ui.selectable_label(selected, format!("{mode:?}")).clicked() && !selected {
+            *alpha_mode = mode;
+            changed = true;
+          }
+        }
+      });
+
+    if let AlphaMode::Mask(cutoff) = alpha_mode {
+      changed |= ui
+        .add(egui::Slider::new(cutoff, 0.0..=1.0).text("Cutoff"))
+        .changed();
+    }
+
+    changed
+  }
+
+  /// Shows the image in the slot and accepts images dragged from the image list
+  fn texture_slot(
+    ui: &mut egui::Ui,
+    label: &str,
+    slot: &mut Option<Handle<Image>>,
+    world: &mut World,
+  ) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+      let (_, dropped) =
+        ui.dnd_drop_zone::<Handle<Image>, _>(egui::Frame::group(ui.style()), |ui| match slot {
+          Some(image) => {
+            let texture = Self::texture_id(image, world);
+            ui.image(egui::load::SizedTexture::new(
+              texture,
+              egui::Vec2::splat(THUMBNAIL_SIZE),
+            ));
+          }
+          None => {
+            ui.add_sized(egui::Vec2::splat(THUMBNAIL_SIZE), egui::Label::new("None"));
+          }
+        });
+
+      if let Some(image) = dropped {
+        *slot = Some((*image).clone());
+        changed = true;
+      }
+
+      ui.vertical(|ui| {
+        ui.label(label);
+        if slot.is_some() && ui.small_button("Clear").clicked() {
+          *slot = None;
+          changed = true;
+        }
+      });
+    });
+
+    changed
+  }
+
+  /// The egui texture the image is registered as, the resource is only changed, and sent to the
+  /// render world again, the first time an image is shown
+  fn texture_id(image: &Handle<Image>, world: &mut World) -> egui::TextureId {
+    match world.resource::<EguiUserTextures>().image_id(image) {
+      Some(texture) => texture,
+      None => world
+        .resource_mut::<EguiUserTextures>()
+        .add_image(image.clone_weak()),
+    }
+  }
+
+  /// Loaded images that can be dragged onto a texture slot
+  fn images_ui(ui: &mut egui::Ui, world: &mut World) {
+    let paths = world
+      .resource::<Assets<Image>>()
+      .ids()
+      .filter_map(|id| {
+        let path = world.resource::<AssetServer>().get_path(id)?;
+        Some((id, path.to_string()))
+      })
+      .collect::<Vec<_>>();
+
+    let mut assets = world.resource_mut::<Assets<Image>>();
+    let images = paths
+      .into_iter()
+      .filter_map(|(id, path)| Some((path, assets.get_strong_handle(id)?)))
+      .collect::<Vec<_>>();
+
+    egui::CollapsingHeader::new(format!("Images ({})", images.len())).show(ui, |ui| {
+      if images.is_empty() {
+        ui.weak("No images loaded from the asset folder");
+        return;
+      }
+
+      ui.horizontal_wrapped(|ui| {
+        for (path, image) in images {
+          let texture = Self::texture_id(&image, world);
+
+          ui.dnd_drag_source(
+            egui::Id::new(("material-editor-image", &path)),
+            image,
+            |ui| {
+              ui.image(egui::load::SizedTexture::new(
+                texture,
+                egui::Vec2::splat(THUMBNAIL_SIZE),
+              ))
+            },
+          )
+          .response
+          .on_hover_text(path);
+        }
+      });
+    });
+  }
+}
+
+impl RawUi for MaterialEditor {
+  const NAME: &str = "Material Editor";
+  const ID: Uuid = uuid!("9a4f3e2b-6c1d-4e8a-b7f5-2d3c8e1a6b90");
+
+  fn spawn(entity: Entity, world: &mut World) -> Self {
+    Self {
+      material: None,
+      shape: default(),
+      preview: Some(Self::spawn_preview(entity, world)),
+    }
+  }
+
+  fn on_despawn(entity: Entity, world: &mut World) {
+    let Some(preview) = world
+      .get_mut::<Self>(entity)
+      .and_then(|mut this| this.preview.take())
+    else {
+      return;
+    };
+
+    world
+      .resource_mut::<EguiUserTextures>()
+      .remove_image(&preview.image);
+
+    for entity in [preview.camera, preview.light, preview.mesh] {
+      if let Ok(entity) = world.get_entity_mut(entity) {
+        entity.despawn_recursive();
+      }
+    }
+  }
+
+  fn when_rendered(entity: Entity, world: &mut World) {
+    Self::set_preview_active(entity, world, true);
+  }
+
+  fn when_not_rendered(entity: Entity, world: &mut World) {
+    Self::set_preview_active(entity, world, false);
+  }
+
+  fn render(entity: Entity, ui: &mut egui::Ui, world: &mut World) {
+    let Some(mut this) = world.get_mut::<Self>(entity).map(|mut this| Self {
+      material: this.material,
+      shape: this.shape,
+      preview: this.preview.take(),
+    }) else {
+      return;
+    };
+
+    this.picker_ui(ui, world);
+    ui.separator();
+
+    let material = this.material.and_then(|id| {
+      world
+        .resource::<Assets<StandardMaterial>>()
+        .get(id)
+        .cloned()
+    });
+
+    match material {
+      Some(mut material) => {
+        this.preview_ui(ui);
+        ui.separator();
+
+        let writable = ReadOnly::writable(world);
+        if !writable {
+          ui.weak("Read-only, the material can't be changed")
+            .on_hover_text(read_only::REASON);
+        }
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+          let changed = ui
+            .add_enabled_ui(writable, |ui| Self::properties_ui(&mut material, ui, world))
+            .inner;
+
+          // dropping a texture onto a slot still works in a disabled ui
+          if changed && writable {
+            if let Some(id) = this.material {
+              if let Some(current) = world.resource_mut::<Assets<StandardMaterial>>().get_mut(id) {
+                *current = material;
+              }
+            }
+          }
+
+          ui.separator();
+          Self::images_ui(ui, world);
+        });
+      }
+      None => {
+        this.material = None;
+        ui.weak("Pick a material or select an entity that uses one");
+      }
+    }
+
+    this.sync_preview(world);
+
+    if let Some(mut current) = world.get_mut::<Self>(entity) {
+      *current = this;
+    }
+  }
+}
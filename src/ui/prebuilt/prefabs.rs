@@ -1,4 +1,11 @@
-use crate::{assets, ui::RawUi};
+use crate::{
+  assets,
+  prefab_editor::PrefabEditor,
+  read_only::{self, ReadOnly},
+  ui::RawUi,
+  view::placement,
+  EditorState,
+};
 use bevy::prelude::*;
 use bevy_egui::egui;
 use uuid::{uuid, Uuid};
@@ -6,6 +13,29 @@ use uuid::{uuid, Uuid};
 #[derive(Default, Component, Reflect)]
 pub struct Prefabs;
 
+impl Prefabs {
+  fn load_errors_ui(ui: &mut egui::Ui, world: &World) {
+    let errors = world.resource::<assets::PrefabLoadErrors>();
+    if errors.is_empty() {
+      return;
+    }
+
+    let count = errors.iter().count();
+    egui::CollapsingHeader::new(
+      egui::RichText::new(format!("{count} prefab files failed to load"))
+        .color(ui.visuals().error_fg_color),
+    )
+    .default_open(true)
+    .show(ui, |ui| {
+      for (path, error) in errors.iter() {
+        ui.strong(path);
+        ui.monospace(error);
+      }
+    });
+    ui.separator();
+  }
+}
+
 impl RawUi for Prefabs {
   const NAME: &str = stringify!(Prefabs);
   const ID: Uuid = uuid!("fa977fad-ed99-4842-bab4-7c00641b39b0");
@@ -19,19 +49,37 @@ impl RawUi for Prefabs {
   }
 
   fn render(_entity: Entity, ui: &mut egui::Ui, world: &mut World) {
-    world.resource_scope(|world, mut prefabs: Mut<assets::Prefabs>| {
-      let mut prefab_ids = prefabs.keys().cloned().collect::<Vec<_>>();
-
-      prefab_ids.sort();
-
-      for id in prefab_ids {
-        ui.horizontal(|ui| {
-          ui.label(&id);
-          if ui.button("Spawn").clicked() {
-            prefabs.spawn(id, world);
-          }
-        });
-      }
-    });
+    Self::load_errors_ui(ui, world);
+
+    let mut prefab_ids = world
+      .resource::<assets::Prefabs>()
+      .keys()
+      .cloned()
+      .collect::<Vec<_>>();
+
+    prefab_ids.sort();
+
+    let writable = ReadOnly::writable(world);
+    let editing = *world.resource::<State<EditorState>>().get() == EditorState::Editing;
+
+    for id in prefab_ids {
+      ui.horizontal(|ui| {
+        ui.label(&id);
+        if ui
+          .add_enabled(writable, egui::Button::new("Spawn"))
+          .on_disabled_hover_text(read_only::REASON)
+          .clicked()
+        {
+          placement::spawn_prefab(&id, world);
+        }
+        if ui
+          .add_enabled(editing, egui::Button::new("Edit"))
+          .on_disabled_hover_text("Prefabs can only be edited while editing")
+          .clicked()
+        {
+          PrefabEditor::enter(world, id);
+        }
+      });
+    }
   }
 }
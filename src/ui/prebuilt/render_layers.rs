@@ -0,0 +1,192 @@
+use crate::{
+  ui::{InspectorSelection, RawUi},
+  view::EditorCamera,
+};
+use bevy::{prelude::*, render::view::RenderLayers};
+use bevy_egui::egui;
+use uuid::{uuid, Uuid};
+
+const DEFAULT_LAYER_COUNT: usize = 8;
+const MAX_LAYER_COUNT: usize = 64;
+
+#[derive(Component, Reflect)]
+pub struct RenderLayersEditor {
+  layer_count: usize,
+}
+
+impl Default for RenderLayersEditor {
+  fn default() -> Self {
+    Self {
+      layer_count: DEFAULT_LAYER_COUNT,
+    }
+  }
+}
+
+impl RenderLayersEditor {
+  fn layer_matrix(
+    ui: &mut egui::Ui,
+    world: &mut World,
+    id: &str,
+    layer_count: usize,
+    entities: &[Entity],
+  ) {
+    egui::Grid::new(id).striped(true).show(ui, |ui| {
+      ui.label("");
+      for layer in 0..layer_count {
+        ui.label(layer.to_string());
+      }
+      ui.end_row();
+
+      for entity in entities.iter().copied() {
+        let Ok(entity_ref) = world.get_entity(entity) else {
+          continue;
+        };
+
+        let name = entity_ref
+          .get::<Name>()
+          .map(|name| name.to_string())
+          .unwrap_or_else(|| entity.to_string());
+
+        let layers = entity_ref
+          .get::<RenderLayers>()
+          .cloned()
+          .unwrap_or_default();
+
+        ui.label(name);
+
+        let mut new_layers = layers.clone();
+        for layer in 0..layer_count {
+          let mut enabled = layers.intersects(&RenderLayers::layer(layer));
+          if ui.checkbox(&mut enabled, "").changed() {
+            new_layers = if enabled {
+              new_layers.with(layer)
+            } else {
+              new_layers.without(layer)
+            };
+          }
+        }
+
+        if new_layers != layers {
+          world.entity_mut(entity).insert(new_layers);
+        }
+
+        ui.end_row();
+      }
+    });
+  }
+
+  fn editor_view_actions(ui: &mut egui::Ui, world: &mut World, layer_count: usize) {
+    let mut q_editor_cams = world.query_filtered::<Entity, With<EditorCamera>>();
+    let editor_cams = q_editor_cams.iter(world).collect::<Vec<_>>();
+
+    if editor_cams.is_empty() {
+      ui.label("No editor camera");
+      return;
+    }
+
+    let mut new_layers = None;
+
+    let current = world
+      .get::<RenderLayers>(editor_cams[0])
+      .cloned()
+      .unwrap_or_default();
+
+    egui::Grid::new("render-layers-editor-view").show(ui, |ui| {
+      ui.label("");
+      for layer in 0..layer_count {
+        ui.label(layer.to_string());
+      }
+      ui.end_row();
+
+      ui.label("Solo");
+      for layer in 0..layer_count {
+        if ui.small_button("S").clicked() {
+          new_layers = Some(RenderLayers::layer(layer));
+        }
+      }
+      ui.end_row();
+
+      ui.label("Mute");
+      for layer in 0..layer_count {
+        let muted = !current.intersects(&RenderLayers::layer(layer));
+        if ui.selectable_label(muted, "M").clicked() {
+          new_layers = Some(if muted {
+            current.clone().with(layer)
+          } else {
+            current.clone().without(layer)
+          });
+        }
+      }
+      ui.end_row();
+    });
+
+    if ui.button("Show All Layers").clicked() {
+      new_layers = Some(RenderLayers::from_layers(
+        &(0..layer_count).collect::<Vec<_>>(),
+      ));
+    }
+
+    if ui.button("Reset").clicked() {
+      new_layers = Some(RenderLayers::default());
+    }
+
+    if let Some(new_layers) = new_layers {
+      for entity in editor_cams {
+        world.entity_mut(entity).insert(new_layers.clone());
+      }
+    }
+  }
+}
+
+impl RawUi for RenderLayersEditor {
+  const NAME: &str = "Render Layers";
+  const ID: Uuid = uuid!("9dfc1e7a-5d0e-4b2b-9c7b-0f6b0a64f0d3");
+
+  fn spawn(_entity: Entity, _world: &mut World) -> Self {
+    default()
+  }
+
+  fn unique() -> bool {
+    true
+  }
+
+  fn render(entity: Entity, ui: &mut egui::Ui, world: &mut World) {
+    let layer_count = {
+      let Some(mut this) = world.get_mut::<Self>(entity) else {
+        return;
+      };
+
+      ui.horizontal(|ui| {
+        ui.label("Layers");
+        ui.add(egui::DragValue::new(&mut this.layer_count).range(1..=MAX_LAYER_COUNT));
+      });
+
+      this.layer_count
+    };
+
+    ui.collapsing("Editor View", |ui| {
+      Self::editor_view_actions(ui, world, layer_count);
+    });
+
+    ui.collapsing("Cameras", |ui| {
+      let mut q_cameras = world.query_filtered::<Entity, (With<Camera>, Without<EditorCamera>)>();
+      let cameras = q_cameras.iter(world).collect::<Vec<_>>();
+      Self::layer_matrix(ui, world, "render-layers-cameras", layer_count, &cameras);
+    });
+
+    ui.collapsing("Selection", |ui| {
+      let selected = match world.resource::<InspectorSelection>() {
+        InspectorSelection::Entities(selected_entities) => {
+          selected_entities.iter().collect::<Vec<_>>()
+        }
+        _ => Vec::new(),
+      };
+
+      if selected.is_empty() {
+        ui.label("Nothing selected");
+      } else {
+        Self::layer_matrix(ui, world, "render-layers-selection", layer_count, &selected);
+      }
+    });
+  }
+}
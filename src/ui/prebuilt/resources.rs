@@ -1,18 +1,22 @@
-use crate::ui::{InspectorSelection, Ui};
+use crate::ui::{
+  type_search::{TypeKind, TypeSearch},
+  InspectorSelection, Ui,
+};
 use bevy::{ecs::system::SystemParam, prelude::*};
 use bevy_egui::egui;
 use std::marker::PhantomData;
 use uuid::uuid;
 
 #[derive(Default, Component, Reflect)]
-pub struct Resources;
+pub struct Resources {
+  filter: String,
+}
 
 #[derive(SystemParam)]
 pub struct Params<'w, 's> {
   type_registry: Res<'w, AppTypeRegistry>,
   selection: ResMut<'w, InspectorSelection>,
-
-  filter: Local<'s, String>,
+  search: ResMut<'w, TypeSearch>,
 
   #[system_param(ignore)]
   _pd: PhantomData<&'s ()>,
@@ -32,21 +36,30 @@ impl Ui for Resources {
     true
   }
 
+  fn pausable() -> bool {
+    true
+  }
+
+  fn save_state(&self, _params: Self::Params<'_, '_>) -> Option<serde_json::Value> {
+    serde_json::to_value(&self.filter).ok()
+  }
+
+  fn restore_state(&mut self, _params: Self::Params<'_, '_>, state: serde_json::Value) {
+    if let Ok(filter) = serde_json::from_value(state) {
+      self.filter = filter;
+    }
+  }
+
   fn render(&mut self, ui: &mut egui::Ui, mut params: Self::Params<'_, '_>) {
     let type_registry = params.type_registry.read();
 
-    let mut resources: Vec<_> = type_registry
-      .iter()
-      .filter(|registration| registration.data::<ReflectResource>().is_some())
-      .filter_map(|registration| {
-        let name = registration.type_info().type_path_table().short_path();
-        (params.filter.is_empty() || name.to_lowercase().contains(params.filter.as_str()))
-          .then(|| (name, registration.type_id()))
-      })
-      .collect();
-    resources.sort_by(|(name_a, _), (name_b, _)| name_a.cmp(name_b));
+    let resources = params
+      .search
+      .search(&type_registry, TypeKind::Resource, &self.filter)
+      .map(|entry| (entry.name.clone(), entry.type_id))
+      .collect::<Vec<_>>();
 
-    ui.text_edit_singleline(&mut *params.filter).changed();
+    ui.text_edit_singleline(&mut self.filter).changed();
 
     for (resource_name, type_id) in resources {
       let selected = match *params.selection {
@@ -54,8 +67,8 @@ impl Ui for Resources {
         _ => false,
       };
 
-      if ui.selectable_label(selected, resource_name).clicked() {
-        *params.selection = InspectorSelection::Resource(type_id, resource_name.to_string());
+      if ui.selectable_label(selected, &resource_name).clicked() {
+        *params.selection = InspectorSelection::Resource(type_id, resource_name);
       }
     }
   }
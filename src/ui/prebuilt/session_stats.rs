@@ -0,0 +1,239 @@
+use crate::{
+  cache::Saveable,
+  input::EditorActions,
+  settings::{EditorSettings, SettingsAppExt},
+  ui::{status_bar::StatusMessage, RawUi},
+  util::{self, unix_time},
+};
+use bevy::prelude::*;
+use bevy_egui::egui;
+use leafwing_input_manager::prelude::ActionState;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::PathBuf};
+use uuid::{uuid, Uuid};
+
+const BAR_SIZE: egui::Vec2 = egui::vec2(120.0, 12.0);
+const COLD: egui::Color32 = egui::Color32::from_rgb(40, 90, 200);
+const HOT: egui::Color32 = egui::Color32::from_rgb(230, 60, 40);
+
+/// Usage statistics are only collected once they are turned on
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone, Default)]
+#[reflect(Resource)]
+pub struct SessionStatsSettings {
+  pub enabled: bool,
+}
+
+impl Saveable for SessionStatsSettings {
+  const KEY: &str = "session_stats";
+}
+
+impl EditorSettings for SessionStatsSettings {
+  const NAME: &str = "Session Statistics";
+}
+
+#[derive(Default, Clone, Serialize)]
+pub struct PanelUsage {
+  /// Seconds the panel was visible
+  pub shown: f32,
+  /// Seconds the pointer was over the panel
+  pub hovered: f32,
+  pub clicks: u32,
+}
+
+/// Which panels and commands were used since the editor was launched, while enabled in
+/// [`SessionStatsSettings`]
+#[derive(Resource, Serialize)]
+pub struct SessionStats {
+  /// Seconds since the unix epoch
+  pub started: u64,
+  pub panels: BTreeMap<String, PanelUsage>,
+  pub commands: BTreeMap<String, u32>,
+}
+
+impl Default for SessionStats {
+  fn default() -> Self {
+    Self {
+      started: unix_time(),
+      panels: default(),
+      commands: default(),
+    }
+  }
+}
+
+impl SessionStats {
+  /// Counts a use of the command, ignored while statistics are disabled
+  pub fn record_command(world: &mut World, command: impl Into<String>) {
+    if !world.resource::<SessionStatsSettings>().enabled {
+      return;
+    }
+
+    *world
+      .resource_mut::<Self>()
+      .commands
+      .entry(command.into())
+      .or_default() += 1;
+  }
+
+  /// Called for every panel drawn this frame
+  pub(crate) fn record_panel(world: &mut World, panel: &str, hovered: bool, clicked: bool) {
+    if !world.resource::<SessionStatsSettings>().enabled {
+      return;
+    }
+
+    let delta = world.resource::<Time<Real>>().delta_secs();
+    let mut stats = world.resource_mut::<Self>();
+    let usage = stats.panels.entry(panel.to_string()).or_default();
+
+    usage.shown += delta;
+    if hovered {
+      usage.hovered += delta;
+    }
+    if clicked {
+      usage.clicks += 1;
+    }
+  }
+
+  fn record_actions(
+    settings: Res<SessionStatsSettings>,
+    q_action_states: Query<&ActionState<EditorActions>>,
+    mut stats: ResMut<Self>,
+  ) {
+    if !settings.enabled {
+      return;
+    }
+
+    for action_state in &q_action_states {
+      for action in action_state.get_just_pressed() {
+        *stats.commands.entry(format!("{action:?}")).or_default() += 1;
+      }
+    }
+  }
+
+  fn export(&self) -> Result<PathBuf, String> {
+    util::export_json("session_stats", self)
+  }
+}
+
+#[derive(Default, Component, Reflect)]
+pub struct SessionStatsPanel;
+
+impl SessionStatsPanel {
+  fn heat_bar(ui: &mut egui::Ui, heat: f32, text: String) {
+    let (rect, response) = ui.allocate_exact_size(BAR_SIZE, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+
+    let mut filled = rect;
+    filled.set_width(rect.width() * heat.clamp(0.0, 1.0));
+    painter.rect_filled(filled, 2.0, COLD.lerp_to_gamma(HOT, heat));
+
+    response.on_hover_text(text);
+  }
+
+  fn panels_ui(ui: &mut egui::Ui, stats: &SessionStats) {
+    let max_hovered = stats
+      .panels
+      .values()
+      .map(|usage| usage.hovered)
+      .fold(0.0, f32::max);
+
+    let mut panels = stats.panels.iter().collect::<Vec<_>>();
+    panels.sort_by(|a, b| b.1.hovered.total_cmp(&a.1.hovered));
+
+    egui::Grid::new("session-stats-panels")
+      .striped(true)
+      .show(ui, |ui| {
+        ui.strong("Panel");
+        ui.strong("Hovered");
+        ui.strong("Shown");
+        ui.strong("Clicks");
+        ui.end_row();
+
+        for (name, usage) in panels {
+          ui.label(name);
+          let heat = if max_hovered > 0.0 {
+            usage.hovered / max_hovered
+          } else {
+            0.0
+          };
+          Self::heat_bar(ui, heat, format!("{:.0}s", usage.hovered));
+          ui.label(format!("{:.0}s", usage.shown));
+          ui.label(usage.clicks.to_string());
+          ui.end_row();
+        }
+      });
+  }
+
+  fn commands_ui(ui: &mut egui::Ui, stats: &SessionStats) {
+    let mut commands = stats.commands.iter().collect::<Vec<_>>();
+    commands.sort_by(|a, b| b.1.cmp(a.1));
+
+    egui::Grid::new("session-stats-commands")
+      .striped(true)
+      .show(ui, |ui| {
+        for (command, count) in commands {
+          ui.label(command);
+          ui.label(count.to_string());
+          ui.end_row();
+        }
+      });
+  }
+}
+
+impl RawUi for SessionStatsPanel {
+  const NAME: &str = "Session Statistics";
+  const ID: Uuid = uuid!("5c1d7e32-8f4b-4d6a-b0e9-2a7c9e41f8d3");
+
+  fn init(app: &mut App) {
+    app
+      .register_settings::<SessionStatsSettings>()
+      .init_resource::<SessionStats>()
+      .add_systems(Update, SessionStats::record_actions);
+  }
+
+  fn spawn(_entity: Entity, _world: &mut World) -> Self {
+    default()
+  }
+
+  fn unique() -> bool {
+    true
+  }
+
+  fn render(_entity: Entity, ui: &mut egui::Ui, world: &mut World) {
+    let mut enabled = world.resource::<SessionStatsSettings>().enabled;
+    if ui
+      .checkbox(&mut enabled, "Collect Statistics")
+      .on_hover_text("Record which panels and commands are used, nothing leaves this machine")
+      .changed()
+    {
+      world.resource_mut::<SessionStatsSettings>().enabled = enabled;
+    }
+
+    ui.horizontal(|ui| {
+      if ui.button("Export JSON").clicked() {
+        let message = match world.resource::<SessionStats>().export() {
+          Ok(file) => {
+            StatusMessage::info(format!("Exported session statistics to {}", file.display()))
+          }
+          Err(err) => StatusMessage::error(format!("Failed to export session statistics: {err}")),
+        };
+        world.send_event(message);
+      }
+
+      if ui.button("Reset").clicked() {
+        *world.resource_mut::<SessionStats>() = default();
+      }
+    });
+
+    let stats = world.resource::<SessionStats>();
+    if stats.panels.is_empty() && stats.commands.is_empty() {
+      ui.weak("Nothing recorded yet");
+      return;
+    }
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+      ui.collapsing("Panels", |ui| Self::panels_ui(ui, stats));
+      ui.collapsing("Commands", |ui| Self::commands_ui(ui, stats));
+    });
+  }
+}
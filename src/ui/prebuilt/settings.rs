@@ -0,0 +1,48 @@
+use crate::{settings::SettingsRegistry, ui::RawUi};
+use bevy::prelude::*;
+use bevy_egui::egui;
+use bevy_inspector_egui::bevy_inspector::by_type_id::ui_for_resource;
+use uuid::{uuid, Uuid};
+
+#[derive(Default, Component, Reflect)]
+pub struct Settings;
+
+impl RawUi for Settings {
+  const NAME: &str = stringify!(Settings);
+  const ID: Uuid = uuid!("3b0f9f1e-61a4-4a8e-9d43-0c6f3c5a2d71");
+
+  fn spawn(_entity: Entity, _world: &mut World) -> Self {
+    default()
+  }
+
+  fn unique() -> bool {
+    true
+  }
+
+  fn render(_entity: Entity, ui: &mut egui::Ui, world: &mut World) {
+    let type_registry = world.resource::<AppTypeRegistry>().0.clone();
+    let type_registry = type_registry.read();
+
+    world.resource_scope(|world, registry: Mut<SettingsRegistry>| {
+      for entry in registry.entries() {
+        ui.collapsing(entry.name(), |ui| {
+          let presets = entry.presets();
+          if !presets.is_empty() {
+            ui.horizontal_wrapped(|ui| {
+              ui.label("Presets");
+              for preset in presets {
+                if ui.button(preset).clicked() {
+                  entry.apply_preset(world, preset);
+                }
+              }
+            });
+          }
+
+          ui.push_id(entry.type_id(), |ui| {
+            ui_for_resource(world, entry.type_id(), ui, entry.name(), &type_registry);
+          });
+        });
+      }
+    });
+  }
+}
@@ -0,0 +1,409 @@
+use crate::{
+  assets,
+  input::EditorActions,
+  read_only::{self, ReadOnly},
+  ui::{misc::UiInfo, prebuilt::editor_view::EditorView, RawUi},
+  util::WorldExtensions,
+  view::{placement, view2d::EditorCamera2d, ActiveEditorCamera, GizmoColorSettings},
+  Editing,
+};
+use bevy::{ecs::system::SystemState, prelude::*, window::PrimaryWindow};
+use bevy_egui::{egui, EguiUserTextures};
+use leafwing_input_manager::prelude::ActionState;
+use serde::{Deserialize, Serialize};
+use uuid::{uuid, Uuid};
+
+const THUMBNAIL_SIZE: f32 = 32.0;
+/// How many cells of the grid are drawn around the cursor, odd so the lines fall on cell edges
+const GRID_PREVIEW_CELLS: u32 = 15;
+
+/// Cell of the tile grid an entity was painted into
+#[derive(Component, Reflect, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[reflect(Component)]
+pub struct Tile {
+  pub cell: IVec2,
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Reflect)]
+enum BrushMode {
+  #[default]
+  Paint,
+  Erase,
+  /// Paints every cell of the rectangle between where the button was pressed and released
+  Fill,
+}
+
+#[derive(Clone, PartialEq, Reflect)]
+enum Brush {
+  Prefab(String),
+  Sprite(Handle<Image>),
+}
+
+/// Paints prefabs or sprites onto a grid in the 2d view
+#[derive(Component, Reflect)]
+pub struct TileBrush {
+  active: bool,
+  mode: BrushMode,
+  brush: Option<Brush>,
+  grid: Vec2,
+  #[reflect(ignore)]
+  fill_start: Option<IVec2>,
+}
+
+impl Default for TileBrush {
+  fn default() -> Self {
+    Self {
+      active: false,
+      mode: default(),
+      brush: None,
+      grid: Vec2::splat(32.0),
+      fill_start: None,
+    }
+  }
+}
+
+enum TileOp {
+  Place(Brush, IVec2),
+  Erase(IVec2),
+}
+
+type PaintParams<'w, 's> = (
+  Query<'w, 's, &'static mut TileBrush>,
+  Query<'w, 's, &'static UiInfo, With<EditorView>>,
+  Single<'w, &'static Window, With<PrimaryWindow>>,
+  Query<'w, 's, (&'static Camera, &'static GlobalTransform), With<EditorCamera2d>>,
+  Query<'w, 's, &'static ActionState<EditorActions>>,
+);
+
+impl TileBrush {
+  fn cell_of(&self, position: Vec2) -> IVec2 {
+    (position / self.grid).floor().as_ivec2()
+  }
+
+  fn center_of(&self, cell: IVec2) -> Vec2 {
+    (cell.as_vec2() + 0.5) * self.grid
+  }
+
+  /// Where the cursor is in the 2d world, if it is over the 2d view
+  fn cursor_position(
+    window: &Window,
+    q_cams: &Query<(&Camera, &GlobalTransform), With<EditorCamera2d>>,
+  ) -> Option<Vec2> {
+    let cursor = window.cursor_position()?;
+    q_cams.iter().find_map(|(camera, transform)| {
+      let viewport = camera.logical_viewport_rect()?;
+      viewport
+        .contains(cursor)
+        .then(|| {
+          camera
+            .viewport_to_world_2d(transform, cursor - viewport.min)
+            .ok()
+        })
+        .flatten()
+    })
+  }
+
+  fn paint(world: &mut World, state: &mut SystemState<PaintParams>) {
+    let (mut q_brushes, q_views, window, q_cams, q_actions) = state.get_mut(world);
+
+    let hovered = q_views.iter().any(UiInfo::hovered);
+    let position = Self::cursor_position(&window, &q_cams).filter(|_| hovered);
+
+    let Some(mut brush) = q_brushes.iter_mut().find(|brush| brush.active) else {
+      return;
+    };
+
+    let Some(actions) = q_actions.iter().next() else {
+      return;
+    };
+
+    let Some(position) = position else {
+      if actions.just_released(&EditorActions::Paint) {
+        brush.fill_start = None;
+      }
+      return;
+    };
+
+    let cell = brush.cell_of(position);
+    let mut ops = Vec::new();
+
+    match brush.mode {
+      BrushMode::Paint => {
+        if let (true, Some(source)) = (actions.pressed(&EditorActions::Paint), &brush.brush) {
+          ops.push(TileOp::Place(source.clone(), cell));
+        }
+      }
+      BrushMode::Erase => {
+        if actions.pressed(&EditorActions::Paint) {
+          ops.push(TileOp::Erase(cell));
+        }
+      }
+      BrushMode::Fill => {
+        if actions.just_pressed(&EditorActions::Paint) {
+          brush.fill_start = Some(cell);
+        }
+
+        if actions.just_released(&EditorActions::Paint) {
+          if let (Some(start), Some(source)) = (brush.fill_start.take(), &brush.brush) {
+            let (min, max) = (start.min(cell), start.max(cell));
+            for y in min.y..=max.y {
+              for x in min.x..=max.x {
+                ops.push(TileOp::Place(source.clone(), IVec2::new(x, y)));
+              }
+            }
+          }
+        }
+      }
+    }
+
+    let grid = brush.grid;
+
+    for op in ops {
+      match op {
+        TileOp::Place(source, cell) => Self::place(world, source, cell, grid),
+        TileOp::Erase(cell) => Self::erase(world, cell),
+      }
+    }
+  }
+
+  fn tiles_at(world: &mut World, cell: IVec2) -> Vec<Entity> {
+    let mut q_tiles = world.query::<(Entity, &Tile)>();
+    q_tiles
+      .iter(world)
+      .filter(|(_, tile)| tile.cell == cell)
+      .map(|(entity, _)| entity)
+      .collect()
+  }
+
+  fn place(world: &mut World, source: Brush, cell: IVec2, grid: Vec2) {
+    if !Self::tiles_at(world, cell).is_empty() {
+      return;
+    }
+
+    let center = (cell.as_vec2() + 0.5) * grid;
+
+    let entity = match source {
+      Brush::Prefab(id) => placement::spawn_prefab(id, world),
+      Brush::Sprite(image) => Some(
+        world
+          .spawn((
+            Name::new("Tile"),
+            Sprite {
+              image,
+              custom_size: Some(grid),
+              ..default()
+            },
+          ))
+          .id(),
+      ),
+    };
+
+    let Some(entity) = entity else {
+      return;
+    };
+
+    let mut entity = world.entity_mut(entity);
+    let z = entity
+      .get::<Transform>()
+      .map(|transform| transform.translation.z)
+      .unwrap_or_default();
+    entity.insert((Tile { cell }, Transform::from_translation(center.extend(z))));
+  }
+
+  fn erase(world: &mut World, cell: IVec2) {
+    for entity in Self::tiles_at(world, cell) {
+      world.entity_mut(entity).despawn_recursive();
+    }
+  }
+
+  /// Outlines the cell under the cursor, or the rectangle being filled, over a patch of the grid
+  fn draw_cursor(
+    mut gizmos: Gizmos,
+    colors: Res<GizmoColorSettings>,
+    q_brushes: Query<&Self>,
+    q_views: Query<&UiInfo, With<EditorView>>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    q_cams: Query<(&Camera, &GlobalTransform), With<EditorCamera2d>>,
+  ) {
+    let Some(brush) = q_brushes.iter().find(|brush| brush.active) else {
+      return;
+    };
+
+    if !q_views.iter().any(UiInfo::hovered) {
+      return;
+    }
+
+    let Some(position) = Self::cursor_position(&window, &q_cams) else {
+      return;
+    };
+
+    let cell = brush.cell_of(position);
+
+    gizmos.grid_2d(
+      Isometry2d::from_translation(brush.center_of(cell)),
+      UVec2::splat(GRID_PREVIEW_CELLS),
+      brush.grid,
+      colors.pointer_normal.with_alpha(0.25),
+    );
+
+    let (min, max) = match brush.fill_start {
+      Some(start) => (start.min(cell), start.max(cell)),
+      None => (cell, cell),
+    };
+
+    let color = match brush.mode {
+      BrushMode::Erase => colors.pointer_hit,
+      _ => colors.selection,
+    };
+
+    let min = min.as_vec2() * brush.grid;
+    let max = (max + IVec2::ONE).as_vec2() * brush.grid;
+    gizmos.rect_2d(
+      Isometry2d::from_translation((min + max) / 2.0),
+      max - min,
+      color,
+    );
+  }
+
+  fn brush_ui(&mut self, ui: &mut egui::Ui, world: &mut World) {
+    let mut prefab_ids = world
+      .resource::<assets::Prefabs>()
+      .keys()
+      .cloned()
+      .collect::<Vec<_>>();
+    prefab_ids.sort();
+
+    egui::CollapsingHeader::new("Prefabs")
+      .default_open(true)
+      .show(ui, |ui| {
+        if prefab_ids.is_empty() {
+          ui.weak("No prefabs registered");
+        }
+
+        for id in prefab_ids {
+          let brush = Brush::Prefab(id.clone());
+          let selected = self.brush.as_ref() == Some(&brush);
+          if ui.selectable_label(selected, id).clicked() {
+            self.brush = Some(brush);
+          }
+        }
+      });
+
+    let paths = world
+      .resource::<Assets<Image>>()
+      .ids()
+      .filter_map(|id| {
+        let path = world.resource::<AssetServer>().get_path(id)?;
+        Some((id, path.to_string()))
+      })
+      .collect::<Vec<_>>();
+
+    let mut images = world.resource_mut::<Assets<Image>>();
+    let images = paths
+      .into_iter()
+      .filter_map(|(id, path)| Some((path, images.get_strong_handle(id)?)))
+      .collect::<Vec<_>>();
+
+    egui::CollapsingHeader::new("Sprites")
+      .default_open(true)
+      .show(ui, |ui| {
+        if images.is_empty() {
+          ui.weak("No images loaded from the asset folder");
+          return;
+        }
+
+        ui.horizontal_wrapped(|ui| {
+          for (path, image) in images {
+            let texture = world
+              .resource_mut::<EguiUserTextures>()
+              .add_image(image.clone_weak());
+
+            let brush = Brush::Sprite(image);
+            let selected = self.brush.as_ref() == Some(&brush);
+            let thumbnail =
+              egui::load::SizedTexture::new(texture, egui::Vec2::splat(THUMBNAIL_SIZE));
+
+            if ui
+              .add(egui::ImageButton::new(thumbnail).selected(selected))
+              .on_hover_text(path)
+              .clicked()
+            {
+              self.brush = Some(brush);
+            }
+          }
+        });
+      });
+  }
+}
+
+impl RawUi for TileBrush {
+  const NAME: &str = "Tile Brush";
+  const ID: Uuid = uuid!("1f8b6d4e-3a2c-4e9f-8b7a-5c6d2e1f0a93");
+
+  fn init(app: &mut App) {
+    app.add_systems(
+      Update,
+      (Self::paint.run_if(ReadOnly::is_writable), Self::draw_cursor)
+        .in_set(Editing)
+        .run_if(in_state(ActiveEditorCamera::Cam2D)),
+    );
+  }
+
+  fn spawn(_entity: Entity, _world: &mut World) -> Self {
+    default()
+  }
+
+  fn unique() -> bool {
+    true
+  }
+
+  fn render(entity: Entity, ui: &mut egui::Ui, world: &mut World) {
+    let Some(mut this) = world
+      .get_mut::<Self>(entity)
+      .map(|mut this| std::mem::take(&mut *this))
+    else {
+      return;
+    };
+
+    if world.get_state::<ActiveEditorCamera>() != ActiveEditorCamera::Cam2D {
+      ui.weak("Switch to the 2d view to paint tiles");
+    }
+
+    if !ReadOnly::writable(world) {
+      ui.weak("Painting is disabled")
+        .on_hover_text(read_only::REASON);
+    }
+
+    ui.checkbox(&mut this.active, "Painting");
+
+    ui.horizontal(|ui| {
+      ui.selectable_value(&mut this.mode, BrushMode::Paint, "Paint");
+      ui.selectable_value(&mut this.mode, BrushMode::Erase, "Erase");
+      ui.selectable_value(&mut this.mode, BrushMode::Fill, "Fill");
+    });
+
+    ui.horizontal(|ui| {
+      ui.label("Grid");
+      ui.add(
+        egui::DragValue::new(&mut this.grid.x)
+          .range(1.0..=f32::MAX)
+          .prefix("x "),
+      );
+      ui.add(
+        egui::DragValue::new(&mut this.grid.y)
+          .range(1.0..=f32::MAX)
+          .prefix("y "),
+      );
+    });
+
+    ui.separator();
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+      this.brush_ui(ui, world);
+    });
+
+    if let Some(mut current) = world.get_mut::<Self>(entity) {
+      *current = this;
+    }
+  }
+}
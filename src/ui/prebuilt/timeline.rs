@@ -0,0 +1,388 @@
+use crate::{
+  keyframes::{KeyframeClip, KeyframePlugin, KeyframeTrack},
+  read_only::ReadOnly,
+  testing::EditorOwned,
+  ui::{status_bar::StatusMessage, InspectorSelection, RawUi},
+  util,
+};
+use bevy::{
+  prelude::*,
+  utils::{HashMap, HashSet},
+};
+use bevy_egui::egui;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use uuid::{uuid, Uuid};
+
+const STRIP_HEIGHT: f32 = 16.0;
+const KEY_RADIUS: f32 = 4.0;
+
+/// Fields keyed together by "Key Transform"
+const TRANSFORM_FIELDS: [&str; 3] = ["translation", "rotation", "scale"];
+
+/// Authors [`KeyframeClip`]s by keying reflected fields of named entities and scrubbing through
+/// them in the editor
+#[derive(Default, Component, Reflect)]
+pub struct Timeline {
+  #[reflect(ignore)]
+  clip: KeyframeClip,
+  /// Relative to the assets folder
+  path: String,
+  time: f32,
+  playing: bool,
+  looping: bool,
+  component: String,
+  field: String,
+  error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TimelineState {
+  clip: KeyframeClip,
+  path: String,
+}
+
+impl Timeline {
+  /// Named entities of the scene, the first entity with a name wins
+  fn targets(world: &mut World) -> HashMap<String, Entity> {
+    let editor_owned = world
+      .query_filtered::<Entity, EditorOwned>()
+      .iter(world)
+      .collect::<HashSet<_>>();
+
+    let mut targets = HashMap::default();
+    for (entity, name) in world.query::<(Entity, &Name)>().iter(world) {
+      if !editor_owned.contains(&entity) {
+        targets.entry(name.to_string()).or_insert(entity);
+      }
+    }
+    targets
+  }
+
+  fn selected(world: &World) -> Option<(Entity, String)> {
+    let InspectorSelection::Entities(selected) = world.resource::<InspectorSelection>() else {
+      return None;
+    };
+    let entity = selected.iter().next()?;
+    let name = world.get::<Name>(entity)?;
+    Some((entity, name.to_string()))
+  }
+
+  fn file_ui(&mut self, ui: &mut egui::Ui, world: &mut World) {
+    ui.horizontal(|ui| {
+      if ui.button("New").clicked() {
+        self.clip = default();
+        self.time = 0.0;
+        self.playing = false;
+      }
+
+      ui.add(
+        egui::TextEdit::singleline(&mut self.path)
+          .hint_text(format!("animations/clip.{}", KeyframeClip::EXTENSION))
+          .desired_width(200.0),
+      );
+
+      let path = util::asset_root().join(&self.path);
+      let has_path = self.path.ends_with(KeyframeClip::EXTENSION);
+
+      if ui
+        .add_enabled(has_path, egui::Button::new("Open"))
+        .on_disabled_hover_text(format!("The file must end in .{}", KeyframeClip::EXTENSION))
+        .clicked()
+      {
+        let result = std::fs::read_to_string(&path)
+          .map_err(|err| err.to_string())
+          .and_then(|text| ron::from_str::<KeyframeClip>(&text).map_err(|err| err.to_string()));
+
+        match result {
+          Ok(clip) => {
+            self.clip = clip;
+            self.time = 0.0;
+          }
+          Err(err) => {
+            world.send_event(StatusMessage::error(format!(
+              "Failed to open {}: {err}",
+              path.display()
+            )));
+          }
+        }
+      }
+
+      if ui
+        .add_enabled(has_path, egui::Button::new("Export"))
+        .on_disabled_hover_text(format!("The file must end in .{}", KeyframeClip::EXTENSION))
+        .clicked()
+      {
+        let message = match Self::export(&self.clip, &path) {
+          Ok(()) => StatusMessage::info(format!("Exported the clip to {}", path.display())),
+          Err(err) => StatusMessage::error(format!("Failed to export the clip: {err}")),
+        };
+        world.send_event(message);
+      }
+    });
+  }
+
+  fn export(clip: &KeyframeClip, path: &Path) -> Result<(), String> {
+    let text = ron::ser::to_string_pretty(clip, default()).map_err(|err| err.to_string())?;
+    if let Some(dir) = path.parent() {
+      std::fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+    }
+    std::fs::write(path, text).map_err(|err| err.to_string())
+  }
+
+  /// Returns whether the time was moved by hand
+  fn playback_ui(&mut self, ui: &mut egui::Ui, writable: bool) -> bool {
+    ui.horizontal(|ui| {
+      let label = if self.playing { "⏸" } else { "▶" };
+      if ui
+        .add_enabled(writable, egui::Button::new(label))
+        .on_hover_text("Play the clip on the scene")
+        .clicked()
+      {
+        self.playing = !self.playing;
+        if self.playing && self.time >= self.clip.duration {
+          self.time = 0.0;
+        }
+      }
+
+      let stopped = ui.add_enabled(writable, egui::Button::new("⏹")).clicked();
+      if stopped {
+        self.playing = false;
+        self.time = 0.0;
+      }
+
+      ui.checkbox(&mut self.looping, "Loop");
+
+      ui.label("Length");
+      ui.add(
+        egui::DragValue::new(&mut self.clip.duration)
+          .range(0.1..=f32::MAX)
+          .speed(0.05)
+          .suffix("s"),
+      );
+
+      let scrubbed = ui
+        .add_enabled(
+          writable,
+          egui::Slider::new(&mut self.time, 0.0..=self.clip.duration).suffix("s"),
+        )
+        .changed();
+
+      stopped || scrubbed
+    })
+    .inner
+  }
+
+  fn key_ui(&mut self, ui: &mut egui::Ui, world: &mut World, writable: bool) {
+    let selected = Self::selected(world);
+
+    ui.horizontal(|ui| {
+      let target = selected.as_ref().filter(|_| writable);
+
+      let key_transform = ui
+        .add_enabled(target.is_some(), egui::Button::new("Key Transform"))
+        .on_hover_text("Key the translation, rotation and scale of the selected entity")
+        .on_disabled_hover_text("Select a named entity to key it");
+
+      ui.add(egui::TextEdit::singleline(&mut self.component).hint_text("Type"));
+      ui.add(egui::TextEdit::singleline(&mut self.field).hint_text("field.path"));
+
+      let key_field = ui
+        .add_enabled(
+          target.is_some() && !self.component.is_empty(),
+          egui::Button::new("Key"),
+        )
+        .on_disabled_hover_text("Select a named entity and enter a component to key it");
+
+      let Some((entity, name)) = target else {
+        return;
+      };
+
+      let result = if key_transform.clicked() {
+        TRANSFORM_FIELDS.iter().try_for_each(|field| {
+          self
+            .clip
+            .key(self.time, world, *entity, name, "Transform", field)
+        })
+      } else if key_field.clicked() {
+        self.clip.key(
+          self.time,
+          world,
+          *entity,
+          name,
+          self.component.trim(),
+          self.field.trim(),
+        )
+      } else {
+        return;
+      };
+
+      self.error = result.err();
+    });
+
+    if let Some(error) = &self.error {
+      ui.colored_label(ui.visuals().error_fg_color, error);
+    }
+  }
+
+  /// Draws a track's keys along the clip, returns the time that was clicked
+  fn track_strip(
+    ui: &mut egui::Ui,
+    track: &mut KeyframeTrack,
+    time: f32,
+    duration: f32,
+  ) -> Option<f32> {
+    let size = egui::vec2(ui.available_width().max(100.0), STRIP_HEIGHT);
+    let (rect, response) = ui.allocate_exact_size(size, egui::Sense::click());
+
+    let x_of = |time: f32| rect.left() + rect.width() * (time / duration).clamp(0.0, 1.0);
+
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+
+    for key in &track.keys {
+      painter.circle_filled(
+        egui::pos2(x_of(key.time), rect.center().y),
+        KEY_RADIUS,
+        ui.visuals().selection.bg_fill,
+      );
+    }
+
+    painter.vline(
+      x_of(time),
+      rect.y_range(),
+      egui::Stroke::new(1.0, ui.visuals().warn_fg_color),
+    );
+
+    let pointer = response.interact_pointer_pos()?;
+
+    if response.secondary_clicked() {
+      track
+        .keys
+        .retain(|key| (x_of(key.time) - pointer.x).abs() > KEY_RADIUS);
+      return None;
+    }
+
+    response
+      .clicked()
+      .then(|| (pointer.x - rect.left()) / rect.width() * duration)
+  }
+
+  fn tracks_ui(&mut self, ui: &mut egui::Ui) -> bool {
+    if self.clip.tracks.is_empty() {
+      ui.weak("Select a named entity and key one of its fields to start");
+      return false;
+    }
+
+    let mut scrubbed = false;
+    let mut removed = None;
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+      egui::Grid::new("timeline-tracks")
+        .striped(true)
+        .show(ui, |ui| {
+          for (index, track) in self.clip.tracks.iter_mut().enumerate() {
+            if ui.small_button("✖").clicked() {
+              removed = Some(index);
+            }
+
+            let label = if track.field.is_empty() {
+              format!("{} {}", track.target, track.component)
+            } else {
+              format!("{} {}.{}", track.target, track.component, track.field)
+            };
+            ui.label(label)
+              .on_hover_text("Right click a key to remove it");
+
+            if let Some(time) = Self::track_strip(ui, track, self.time, self.clip.duration) {
+              self.time = time;
+              scrubbed = true;
+            }
+            ui.end_row();
+          }
+        });
+    });
+
+    if let Some(index) = removed {
+      self.clip.tracks.remove(index);
+    }
+
+    scrubbed
+  }
+}
+
+impl RawUi for Timeline {
+  const NAME: &str = stringify!(Timeline);
+  const ID: Uuid = uuid!("34671616-4ce7-4272-b54a-07f5496fb808");
+
+  fn init(app: &mut App) {
+    if !app.is_plugin_added::<KeyframePlugin>() {
+      app.add_plugins(KeyframePlugin);
+    }
+  }
+
+  fn spawn(_entity: Entity, _world: &mut World) -> Self {
+    default()
+  }
+
+  fn save_state(entity: Entity, world: &mut World) -> Option<serde_json::Value> {
+    world.get::<Self>(entity).and_then(|this| {
+      serde_json::to_value(TimelineState {
+        clip: this.clip.clone(),
+        path: this.path.clone(),
+      })
+      .ok()
+    })
+  }
+
+  fn restore_state(entity: Entity, world: &mut World, state: serde_json::Value) {
+    if let (Some(mut this), Ok(state)) = (
+      world.get_mut::<Self>(entity),
+      serde_json::from_value::<TimelineState>(state),
+    ) {
+      this.clip = state.clip;
+      this.path = state.path;
+    }
+  }
+
+  fn render(entity: Entity, ui: &mut egui::Ui, world: &mut World) {
+    let Some(mut this) = world.get_mut::<Self>(entity) else {
+      return;
+    };
+    let mut this = std::mem::take(&mut *this);
+
+    let writable = ReadOnly::writable(world);
+    if !writable {
+      this.playing = false;
+    }
+
+    this.file_ui(ui, world);
+    ui.separator();
+    let mut moved = this.playback_ui(ui, writable);
+    this.key_ui(ui, world, writable);
+    ui.separator();
+    moved |= this.tracks_ui(ui) && writable;
+
+    if this.playing {
+      this.time += world.resource::<Time<Real>>().delta_secs();
+      if this.time > this.clip.duration {
+        if this.looping {
+          this.time %= this.clip.duration;
+        } else {
+          this.time = this.clip.duration;
+          this.playing = false;
+        }
+      }
+      ui.ctx().request_repaint();
+    }
+
+    if this.playing || moved {
+      let targets = Self::targets(world);
+      this.clip.apply(this.time, world, &targets);
+    }
+
+    if let Some(mut component) = world.get_mut::<Self>(entity) {
+      *component = this;
+    }
+  }
+}
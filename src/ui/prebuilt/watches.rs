@@ -0,0 +1,280 @@
+use crate::{
+  field_path::{FieldPath, FieldTarget},
+  ui::{
+    misc::{Badge, UiInfo},
+    EntityTarget, InspectorSelection, RawUi,
+  },
+};
+use bevy::prelude::*;
+use bevy_egui::egui;
+use bevy_inspector_egui::reflect_inspector::ui_for_value;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use uuid::{uuid, Uuid};
+
+const HISTORY_LEN: usize = 120;
+const SPARKLINE_SIZE: egui::Vec2 = egui::vec2(120.0, 24.0);
+
+#[derive(Default, Component, Reflect)]
+pub struct Watches {
+  #[reflect(ignore)]
+  watches: Vec<Watch>,
+  watch_resource: bool,
+  type_name: String,
+  field: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Watch {
+  /// `None` for resources
+  entity: Option<EntityTarget>,
+  type_name: String,
+  field: String,
+  #[serde(skip)]
+  history: VecDeque<f64>,
+}
+
+impl Watch {
+  /// Path to the watched value as of this frame, `None` if the entity is missing
+  fn path(&mut self, world: &mut World) -> Option<FieldPath> {
+    match self.entity.as_mut() {
+      Some(target) => target
+        .resolve(world)
+        .map(|entity| FieldPath::component(entity, &self.type_name, &self.field)),
+      None => Some(FieldPath::resource(&self.type_name, &self.field)),
+    }
+  }
+}
+
+impl Watches {
+  /// Returns the target of a watch to pin, along with its type and field
+  fn add_watch_ui(
+    &mut self,
+    ui: &mut egui::Ui,
+    selected: Option<Entity>,
+  ) -> Option<(FieldTarget, String, String)> {
+    ui.horizontal(|ui| {
+      egui::ComboBox::from_id_salt("watch-target")
+        .selected_text(if self.watch_resource {
+          "Resource"
+        } else {
+          "Selected Entity"
+        })
+        .show_ui(ui, |ui| {
+          ui.selectable_value(&mut self.watch_resource, false, "Selected Entity");
+          ui.selectable_value(&mut self.watch_resource, true, "Resource");
+        });
+
+      ui.add(egui::TextEdit::singleline(&mut self.type_name).hint_text("Type"));
+      ui.add(egui::TextEdit::singleline(&mut self.field).hint_text("field.path"));
+
+      let target = if self.watch_resource {
+        Some(FieldTarget::Resource)
+      } else {
+        selected.map(FieldTarget::Component)
+      };
+
+      let can_add = target.is_some() && !self.type_name.is_empty();
+
+      if ui.add_enabled(can_add, egui::Button::new("Pin")).clicked() {
+        target.map(|target| {
+          (
+            target,
+            self.type_name.trim().to_string(),
+            self.field.trim().to_string(),
+          )
+        })
+      } else {
+        None
+      }
+    })
+    .inner
+  }
+
+  fn watch_ui(watch: &mut Watch, ui: &mut egui::Ui, world: &mut World) -> bool {
+    let mut keep = true;
+    let path = watch.path(world);
+
+    ui.horizontal(|ui| {
+      if ui.small_button("✖").clicked() {
+        keep = false;
+      }
+
+      let label = match &path {
+        Some(
+          path @ FieldPath {
+            target: FieldTarget::Component(entity),
+            ..
+          },
+        ) => world
+          .get::<Name>(*entity)
+          .map(|name| format!("{name}/{}", watch.type_name))
+          .unwrap_or_else(|| path.to_string()),
+        Some(path) => path.to_string(),
+        None => watch.type_name.clone(),
+      };
+      ui.label(label);
+      if !watch.field.is_empty() {
+        ui.monospace(&watch.field);
+      }
+    });
+
+    let Some(path) = path else {
+      ui.weak("Target missing");
+      return keep;
+    };
+
+    let id = egui::Id::new(&path);
+    let history = &mut watch.history;
+    let result = path.with_value(world, |value, type_registry| {
+      if let Some(number) = FieldPath::as_f64(value) {
+        if history.len() == HISTORY_LEN {
+          history.pop_front();
+        }
+        history.push_back(number);
+      }
+
+      ui.push_id(id, |ui| ui_for_value(value, ui, type_registry))
+        .inner
+    });
+
+    if let Err(err) = result {
+      ui.colored_label(egui::Color32::RED, err.to_string());
+    }
+
+    if watch.history.len() > 1 {
+      Self::sparkline(ui, &watch.history);
+    }
+
+    keep
+  }
+
+  fn sparkline(ui: &mut egui::Ui, history: &VecDeque<f64>) {
+    let (rect, _) = ui.allocate_exact_size(SPARKLINE_SIZE, egui::Sense::hover());
+
+    let (min, max) = history.iter().fold((f64::MAX, f64::MIN), |(min, max), v| {
+      (min.min(*v), max.max(*v))
+    });
+    let range = (max - min).max(f64::EPSILON);
+
+    let step = rect.width() / (HISTORY_LEN - 1) as f32;
+    let points = history
+      .iter()
+      .enumerate()
+      .map(|(i, v)| {
+        let t = ((v - min) / range) as f32;
+        egui::pos2(
+          rect.left() + i as f32 * step,
+          rect.bottom() - t * rect.height(),
+        )
+      })
+      .collect::<Vec<_>>();
+
+    let painter = ui.painter_at(rect);
+    painter.rect_stroke(rect, 0.0, ui.visuals().widgets.noninteractive.bg_stroke);
+    painter.add(egui::Shape::line(
+      points,
+      egui::Stroke::new(1.0, ui.visuals().selection.bg_fill),
+    ));
+  }
+
+  /// Counts the watches whose target is missing, asking for attention when the first one goes
+  /// missing
+  fn update_badge(entity: Entity, world: &mut World) {
+    let Some(mut this) = world.get_mut::<Self>(entity) else {
+      return;
+    };
+
+    let mut watches = std::mem::take(&mut this.watches);
+    let missing = watches
+      .iter_mut()
+      .map(|watch| watch.path(world))
+      .filter(Option::is_none)
+      .count();
+
+    if let Some(mut this) = world.get_mut::<Self>(entity) {
+      this.watches = watches;
+    }
+
+    let Some(mut ui_info) = world.get_mut::<UiInfo>(entity) else {
+      return;
+    };
+
+    let badge = (missing > 0).then_some(Badge::Count(missing));
+    if badge.is_some() && ui_info.badge().is_none() {
+      ui_info.request_attention();
+    }
+    ui_info.set_badge(badge);
+  }
+}
+
+impl RawUi for Watches {
+  const NAME: &str = stringify!(Watches);
+  const ID: Uuid = uuid!("b6a3d2c4-2f7e-4d8b-a1e5-7c9f0e3b5a61");
+
+  fn spawn(_entity: Entity, _world: &mut World) -> Self {
+    default()
+  }
+
+  fn save_state(entity: Entity, world: &mut World) -> Option<serde_json::Value> {
+    world
+      .get::<Self>(entity)
+      .and_then(|this| serde_json::to_value(&this.watches).ok())
+  }
+
+  fn restore_state(entity: Entity, world: &mut World, state: serde_json::Value) {
+    if let (Some(mut this), Ok(watches)) =
+      (world.get_mut::<Self>(entity), serde_json::from_value(state))
+    {
+      this.watches = watches;
+    }
+  }
+
+  fn when_rendered(entity: Entity, world: &mut World) {
+    Self::update_badge(entity, world);
+  }
+
+  fn when_not_rendered(entity: Entity, world: &mut World) {
+    Self::update_badge(entity, world);
+  }
+
+  fn render(entity: Entity, ui: &mut egui::Ui, world: &mut World) {
+    let selected = match world.resource::<InspectorSelection>() {
+      InspectorSelection::Entities(selected_entities) => selected_entities.iter().next(),
+      _ => None,
+    };
+
+    let Some(mut this) = world.get_mut::<Self>(entity) else {
+      return;
+    };
+
+    let pinned = this.add_watch_ui(ui, selected);
+    let mut watches = std::mem::take(&mut this.watches);
+
+    if let Some((target, type_name, field)) = pinned {
+      watches.push(Watch {
+        entity: match target {
+          FieldTarget::Component(entity) => Some(EntityTarget::new(entity, world)),
+          FieldTarget::Resource => None,
+        },
+        type_name,
+        field,
+        history: VecDeque::with_capacity(HISTORY_LEN),
+      });
+    }
+
+    ui.separator();
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+      watches.retain_mut(|watch| {
+        let keep = Self::watch_ui(watch, ui, world);
+        ui.separator();
+        keep
+      });
+    });
+
+    if let Some(mut this) = world.get_mut::<Self>(entity) {
+      this.watches = watches;
+    }
+  }
+}
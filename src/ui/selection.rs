@@ -0,0 +1,195 @@
+use super::{EntityTarget, InspectorSelection, SelectedEntities};
+use crate::{
+  cache::{Cache, Saveable},
+  events::EditorDidLoadScene,
+};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// The inspector selection as it was when the editor last exited
+#[derive(Serialize, Deserialize)]
+pub(crate) enum SavedSelection {
+  Entities(Vec<EntityTarget>),
+  Resource {
+    type_path: String,
+    name: String,
+  },
+  Asset {
+    type_path: String,
+    name: String,
+    path: String,
+  },
+}
+
+impl Saveable for SavedSelection {
+  const KEY: &str = "selection";
+}
+
+impl SavedSelection {
+  fn new(selection: &InspectorSelection, world: &mut World) -> Option<Self> {
+    let type_path_of = |type_id, world: &World| {
+      world
+        .resource::<AppTypeRegistry>()
+        .read()
+        .get(type_id)
+        .map(|registration| registration.type_info().type_path().to_string())
+    };
+
+    match selection {
+      InspectorSelection::Entities(selected) => {
+        let entities = selected
+          .iter()
+          .filter(|entity| world.get_entity(*entity).is_ok())
+          .collect::<Vec<_>>();
+        Some(Self::Entities(
+          entities
+            .into_iter()
+            .map(|entity| EntityTarget::new(entity, world))
+            .collect(),
+        ))
+      }
+      InspectorSelection::Resource(type_id, name) => Some(Self::Resource {
+        type_path: type_path_of(*type_id, world)?,
+        name: name.clone(),
+      }),
+      InspectorSelection::Asset(type_id, name, id) => Some(Self::Asset {
+        type_path: type_path_of(*type_id, world)?,
+        name: name.clone(),
+        path: world.resource::<AssetServer>().get_path(*id)?.to_string(),
+      }),
+    }
+  }
+
+  /// The selection pointing at what is currently in the world, `None` if none of it exists (yet)
+  fn resolve(&mut self, world: &mut World) -> Option<InspectorSelection> {
+    let type_id_of = |type_path: &str, world: &World| {
+      world
+        .resource::<AppTypeRegistry>()
+        .read()
+        .get_with_type_path(type_path)
+        .map(|registration| registration.type_id())
+    };
+
+    match self {
+      Self::Entities(targets) => {
+        let mut selected = SelectedEntities::default();
+        for entity in targets
+          .iter_mut()
+          .filter_map(|target| target.resolve(world))
+        {
+          selected.select_maybe_add(entity, true);
+        }
+        (!selected.is_empty()).then_some(InspectorSelection::Entities(selected))
+      }
+      Self::Resource { type_path, name } => {
+        let type_id = type_id_of(type_path, world)?;
+        world
+          .components()
+          .get_resource_id(type_id)
+          .and_then(|id| world.storages().resources.get(id))
+          .is_some_and(|data| data.is_present())
+          .then(|| InspectorSelection::Resource(type_id, name.clone()))
+      }
+      Self::Asset {
+        type_path,
+        name,
+        path,
+      } => {
+        let type_id = type_id_of(type_path, world)?;
+        let id = world
+          .resource::<AssetServer>()
+          .get_path_ids(path.as_str())
+          .into_iter()
+          .find(|id| id.type_id() == type_id)?;
+        Some(InspectorSelection::Asset(type_id, name.clone(), id))
+      }
+    }
+  }
+}
+
+/// Selection from the last session that is applied once what it points at exists
+#[derive(Resource, Default)]
+pub(super) struct PendingSelection {
+  saved: Option<SavedSelection>,
+  scene_loaded: bool,
+}
+
+impl PendingSelection {
+  pub(super) fn load(mut pending: ResMut<Self>, cache: Res<Cache>) {
+    pending.saved = cache.get::<SavedSelection>();
+  }
+
+  pub(super) fn is_pending(pending: Res<Self>) -> bool {
+    pending.saved.is_some()
+  }
+
+  pub(super) fn on_scene_loaded(
+    mut events: EventReader<EditorDidLoadScene>,
+    mut pending: ResMut<Self>,
+  ) {
+    if events.read().count() > 0 {
+      pending.scene_loaded = true;
+    }
+  }
+
+  /// Entities from the last session may be part of a scene that is still streaming in, so the
+  /// selection is retried every frame until it resolves or a scene finished loading
+  pub(super) fn restore(world: &mut World) {
+    world.resource_scope(|world, mut pending: Mut<Self>| {
+      // the user picked something before the old selection could be restored
+      let untouched = match world.resource::<InspectorSelection>() {
+        InspectorSelection::Entities(selected) => selected.is_empty(),
+        _ => false,
+      };
+
+      if !untouched {
+        pending.saved = None;
+        return;
+      }
+
+      let Some(saved) = pending.saved.as_mut() else {
+        return;
+      };
+
+      match saved.resolve(world) {
+        Some(selection) => {
+          *world.resource_mut::<InspectorSelection>() = selection;
+          pending.saved = None;
+        }
+        None if pending.scene_loaded => {
+          debug!("Previous selection no longer exists");
+          pending.saved = None;
+        }
+        None => (),
+      }
+    });
+  }
+}
+
+/// Gives newly selected entities a persistent id so the selection can be found again next launch
+pub(super) fn tag_selected(world: &mut World) {
+  let InspectorSelection::Entities(selected) = world.resource::<InspectorSelection>() else {
+    return;
+  };
+
+  let entities = selected.iter().collect::<Vec<_>>();
+  for entity in entities {
+    if world.get_entity(entity).is_ok() {
+      EntityTarget::new(entity, world);
+    }
+  }
+}
+
+pub(super) fn on_app_exit(world: &mut World) {
+  // nothing was selected since the last session's selection was found, keep it for next time
+  if let Some(saved) = world.resource_mut::<PendingSelection>().saved.take() {
+    world.resource_mut::<Cache>().store(&saved);
+    return;
+  }
+
+  world.resource_scope(|world, selection: Mut<InspectorSelection>| {
+    if let Some(saved) = SavedSelection::new(&selection, world) {
+      world.resource_mut::<Cache>().store(&saved);
+    }
+  });
+}
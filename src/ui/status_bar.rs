@@ -0,0 +1,178 @@
+use super::{misc::UiInfo, prebuilt::editor_view::EditorView, InspectorSelection};
+use crate::{
+  read_only::{self, ReadOnly},
+  tasks::EditorTasks,
+  util::WorldExtensions,
+  view::{placement::PlacementSettings, ActiveEditorCamera},
+  EditorState,
+};
+use bevy::prelude::*;
+use bevy_egui::egui;
+use std::time::{Duration, Instant};
+
+const DEFAULT_DURATION: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusKind {
+  Info,
+  Warning,
+  Error,
+}
+
+/// Shows a message in the status bar for a while, replacing whatever message was there
+#[derive(Event, Clone, Debug)]
+pub struct StatusMessage {
+  pub text: String,
+  pub kind: StatusKind,
+  pub duration: Duration,
+}
+
+impl StatusMessage {
+  pub fn info(text: impl Into<String>) -> Self {
+    Self {
+      text: text.into(),
+      kind: StatusKind::Info,
+      duration: DEFAULT_DURATION,
+    }
+  }
+
+  pub fn warning(text: impl Into<String>) -> Self {
+    Self {
+      kind: StatusKind::Warning,
+      ..Self::info(text)
+    }
+  }
+
+  pub fn error(text: impl Into<String>) -> Self {
+    Self {
+      kind: StatusKind::Error,
+      ..Self::info(text)
+    }
+  }
+
+  pub fn with_duration(mut self, duration: Duration) -> Self {
+    self.duration = duration;
+    self
+  }
+}
+
+/// The last [`StatusMessage`] and when it was received
+#[derive(Resource, Default)]
+pub(super) struct StatusBar {
+  message: Option<(StatusMessage, Instant)>,
+}
+
+impl StatusBar {
+  pub(super) fn receive(mut events: EventReader<StatusMessage>, mut status_bar: ResMut<Self>) {
+    if let Some(message) = events.read().last() {
+      status_bar.message = Some((message.clone(), Instant::now()));
+    }
+  }
+
+  fn current_message(&mut self) -> Option<&StatusMessage> {
+    if self
+      .message
+      .as_ref()
+      .is_some_and(|(message, at)| at.elapsed() > message.duration)
+    {
+      self.message = None;
+    }
+
+    self.message.as_ref().map(|(message, _)| message)
+  }
+
+  /// What can be done with the panel under the cursor, shown when there is no message
+  fn hint(world: &mut World) -> Option<&'static str> {
+    if world.get_state::<EditorState>() == EditorState::Testing {
+      return Some("F5 to stop testing");
+    }
+
+    let mut q_views = world.query_filtered::<&UiInfo, With<EditorView>>();
+    if !q_views.iter(world).any(UiInfo::hovered) {
+      return None;
+    }
+
+    match world.get_state::<ActiveEditorCamera>() {
+      ActiveEditorCamera::Cam3D => Some(
+        "Click to select, right-drag to orbit, middle-drag to pan, scroll to zoom, WASD to move",
+      ),
+      ActiveEditorCamera::Cam2D => {
+        Some("Click to select, middle-drag to pan, scroll to zoom, WASD to move")
+      }
+      ActiveEditorCamera::None => None,
+    }
+  }
+
+  pub(super) fn ui(ui: &mut egui::Ui, world: &mut World) {
+    ui.horizontal(|ui| {
+      let state = match world.get_state::<EditorState>() {
+        EditorState::Editing => "Editing",
+        EditorState::Testing => "Testing",
+        EditorState::Exiting => "Exiting",
+      };
+      ui.strong(state);
+
+      ui.separator();
+
+      let camera = match world.get_state::<ActiveEditorCamera>() {
+        ActiveEditorCamera::None => "No Camera",
+        ActiveEditorCamera::Cam2D => "2D",
+        ActiveEditorCamera::Cam3D => "3D",
+      };
+      ui.label(camera);
+
+      ui.separator();
+
+      let selected = match world.resource::<InspectorSelection>() {
+        InspectorSelection::Entities(selected) => format!("{} selected", selected.len()),
+        InspectorSelection::Resource(_, name) | InspectorSelection::Asset(_, name, _) => {
+          name.clone()
+        }
+      };
+      ui.label(selected);
+
+      ui.separator();
+
+      let snap = if world.resource::<PlacementSettings>().snap_to_ground {
+        "Snap to ground"
+      } else {
+        "No snapping"
+      };
+      ui.label(snap);
+
+      if !ReadOnly::writable(world) {
+        ui.separator();
+        ui.label(egui::RichText::new("Read-Only").strong())
+          .on_hover_text(read_only::REASON);
+      }
+
+      ui.separator();
+
+      let message = world
+        .resource_mut::<Self>()
+        .current_message()
+        .map(|message| (message.text.clone(), message.kind));
+
+      match message {
+        Some((text, StatusKind::Info)) => {
+          ui.label(text);
+        }
+        Some((text, StatusKind::Warning)) => {
+          ui.colored_label(ui.visuals().warn_fg_color, text);
+        }
+        Some((text, StatusKind::Error)) => {
+          ui.colored_label(ui.visuals().error_fg_color, text);
+        }
+        None => {
+          if let Some(hint) = Self::hint(world) {
+            ui.weak(hint);
+          }
+        }
+      }
+
+      ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+        world.resource_scope(|_, mut tasks: Mut<EditorTasks>| tasks.ui(ui));
+      });
+    });
+  }
+}
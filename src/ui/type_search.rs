@@ -0,0 +1,141 @@
+use bevy::{
+  asset::ReflectAsset,
+  prelude::*,
+  reflect::{TypeRegistration, TypeRegistry},
+  utils::HashMap,
+};
+use nucleo::{
+  pattern::{CaseMatching, Normalization, Pattern},
+  Config, Matcher, Utf32Str,
+};
+use std::{any::TypeId, cmp::Reverse};
+
+/// Which registered types a panel lists
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum TypeKind {
+  /// Components that can be inserted with a default value
+  DefaultComponent,
+  Resource,
+  Asset,
+}
+
+impl TypeKind {
+  fn matches(self, registration: &TypeRegistration) -> bool {
+    match self {
+      Self::DefaultComponent => {
+        registration.data::<ReflectComponent>().is_some()
+          && (registration.data::<ReflectDefault>().is_some()
+            || registration.data::<ReflectFromWorld>().is_some())
+      }
+      Self::Resource => registration.data::<ReflectResource>().is_some(),
+      Self::Asset => registration.data::<ReflectAsset>().is_some(),
+    }
+  }
+}
+
+/// A registered type as it is listed, by its short type path
+pub struct TypeEntry {
+  pub name: String,
+  pub type_id: TypeId,
+}
+
+/// Fuzzy search over the names of registered types, shared by the panels that list them
+///
+/// The names of each kind are collected once and the results of the last query of each kind are
+/// kept, so panels redrawn with the same filter don't search again
+#[derive(Resource)]
+pub struct TypeSearch {
+  /// Registrations the entries were collected from, types are only ever added to the registry
+  registered: usize,
+  /// Sorted by name
+  entries: HashMap<TypeKind, Vec<TypeEntry>>,
+  /// Last query of each kind with the indices of its matches, best first
+  results: HashMap<TypeKind, (String, Vec<usize>)>,
+  matcher: Matcher,
+}
+
+impl Default for TypeSearch {
+  fn default() -> Self {
+    Self {
+      registered: 0,
+      entries: default(),
+      results: default(),
+      matcher: Matcher::new(Config::DEFAULT),
+    }
+  }
+}
+
+impl TypeSearch {
+  /// Types of the kind whose names match the query, best match first or by name for an empty
+  /// query
+  pub fn search(
+    &mut self,
+    type_registry: &TypeRegistry,
+    kind: TypeKind,
+    query: &str,
+  ) -> impl Iterator<Item = &TypeEntry> {
+    // the registrations are a map so the hint is exact
+    let registered = type_registry.iter().size_hint().0;
+    if registered != self.registered {
+      self.registered = registered;
+      self.entries.clear();
+      self.results.clear();
+    }
+
+    let entries = self
+      .entries
+      .entry(kind)
+      .or_insert_with(|| Self::collect(type_registry, kind));
+
+    let query = query.trim();
+    let cached = self
+      .results
+      .get(&kind)
+      .is_some_and(|(last, _)| last == query);
+
+    if !cached {
+      let matches = if query.is_empty() {
+        (0..entries.len()).collect()
+      } else {
+        let pattern = Pattern::parse(query, CaseMatching::Ignore, Normalization::Smart);
+        let mut buf = Vec::new();
+        let mut scored = entries
+          .iter()
+          .enumerate()
+          .filter_map(|(index, entry)| {
+            pattern
+              .score(Utf32Str::new(&entry.name, &mut buf), &mut self.matcher)
+              .map(|score| (index, score))
+          })
+          .collect::<Vec<_>>();
+        // stable so equal scores stay sorted by name
+        scored.sort_by_key(|(_, score)| Reverse(*score));
+        scored.into_iter().map(|(index, _)| index).collect()
+      };
+      self.results.insert(kind, (query.to_string(), matches));
+    }
+
+    let entries = &self.entries[&kind];
+    self.results[&kind]
+      .1
+      .iter()
+      .map(move |index| &entries[*index])
+  }
+
+  fn collect(type_registry: &TypeRegistry, kind: TypeKind) -> Vec<TypeEntry> {
+    let mut entries = type_registry
+      .iter()
+      .filter(|registration| kind.matches(registration))
+      .map(|registration| TypeEntry {
+        name: registration
+          .type_info()
+          .type_path_table()
+          .short_path()
+          .to_string(),
+        type_id: registration.type_id(),
+      })
+      .collect::<Vec<_>>();
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    entries
+  }
+}
@@ -1,9 +1,15 @@
 use std::{
+  any::TypeId,
   collections::BTreeMap,
   hash::{DefaultHasher, Hash, Hasher},
+  path::PathBuf,
+  sync::OnceLock,
+  time::{SystemTime, UNIX_EPOCH},
 };
 
 use bevy::{
+  asset::io::file::FileAssetReader,
+  ecs::entity::EntityHashMap,
   log::Level,
   log::{
     tracing_subscriber::{self, reload, Layer},
@@ -18,7 +24,53 @@ use bevy::{
 };
 use serde::{Deserialize, Serialize, Serializer};
 
-use crate::cache::{Cache, Saveable};
+use crate::{
+  cache::{self, Cache, Saveable},
+  ui::PersistentEntityId,
+};
+
+static ASSET_ROOT: OnceLock<PathBuf> = OnceLock::new();
+
+/// Records the asset folder the app's [`AssetPlugin`] was configured with, call once the default
+/// plugins are added
+pub(crate) fn init_asset_root(app: &App) {
+  let file_path = app
+    .get_added_plugins::<AssetPlugin>()
+    .first()
+    .map(|plugin| plugin.file_path.clone())
+    .unwrap_or_else(|| AssetPlugin::default().file_path);
+
+  let _ = ASSET_ROOT.set(FileAssetReader::get_base_path().join(file_path));
+}
+
+/// Folder the default asset source reads from, files are saved here to be loaded back as assets
+pub(crate) fn asset_root() -> PathBuf {
+  ASSET_ROOT
+    .get_or_init(|| FileAssetReader::get_base_path().join(AssetPlugin::default().file_path))
+    .clone()
+}
+
+/// Seconds since the unix epoch, zero if the clock is set before it
+pub(crate) fn unix_time() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|time| time.as_secs())
+    .unwrap_or_default()
+}
+
+/// Writes the value as json to `{name}_{unix time}.json` next to the executable, returns the file
+pub(crate) fn export_json(name: &str, value: &impl Serialize) -> Result<PathBuf, String> {
+  let file = cache::cache_dir().join(format!("{name}_{}.json", unix_time()));
+  let json = serde_json::to_string_pretty(value).map_err(|err| err.to_string())?;
+  std::fs::write(&file, json)
+    .map_err(|err| format!("failed to write '{}': {err}", file.display()))?;
+  Ok(file)
+}
+
+/// Components that tie entities into a hierarchy, adding or removing them by hand breaks it
+pub(crate) fn is_hierarchy_component(type_id: TypeId) -> bool {
+  type_id == TypeId::of::<Parent>() || type_id == TypeId::of::<Children>()
+}
 
 #[macro_export]
 macro_rules! here {
@@ -78,6 +130,11 @@ pub trait WorldExtensions {
   fn set_state<T>(&mut self, state: T)
   where
     T: FreelyMutableState + Copy;
+
+  /// Spawns a copy of every reflected component of `entity` under the same parent
+  ///
+  /// Children are not copied
+  fn duplicate(&mut self, entity: Entity) -> Option<Entity>;
 }
 
 impl WorldExtensions for World {
@@ -99,6 +156,32 @@ impl WorldExtensions for World {
   {
     self.resource_mut::<NextState<T>>().set(state);
   }
+
+  fn duplicate(&mut self, entity: Entity) -> Option<Entity> {
+    let parent = self.get::<Parent>(entity).map(Parent::get);
+
+    // persistent ids have to stay unique and the hierarchy is rebuilt below
+    let scene = DynamicSceneBuilder::from_world(self)
+      .deny_component::<Parent>()
+      .deny_component::<Children>()
+      .deny_component::<PersistentEntityId>()
+      .extract_entity(entity)
+      .build();
+
+    let mut entity_map = EntityHashMap::default();
+    if let Err(err) = scene.write_to_world(self, &mut entity_map) {
+      error!("Failed to duplicate {entity}: {err}");
+      return None;
+    }
+
+    let copy = entity_map.get(&entity).copied()?;
+
+    if let Some(parent) = parent {
+      self.entity_mut(parent).add_child(copy);
+    }
+
+    Some(copy)
+  }
 }
 
 pub fn sorted_keys<S, K: Ord + Serialize, V: Serialize>(
@@ -112,6 +195,30 @@ where
   ordered.serialize(serializer)
 }
 
+/// Whether the text matches the pattern, `*` matches any run of characters
+pub fn matches_pattern(pattern: &str, text: &str) -> bool {
+  let mut parts = pattern.split('*');
+  let first = parts.next().unwrap_or_default();
+  let Some(mut rest) = text.strip_prefix(first) else {
+    return false;
+  };
+
+  let mut parts = parts.collect::<Vec<_>>();
+  let Some(last) = parts.pop() else {
+    // no wildcard at all
+    return rest.is_empty();
+  };
+
+  for part in parts {
+    match rest.find(part) {
+      Some(index) => rest = &rest[index + part.len()..],
+      None => return false,
+    }
+  }
+
+  rest.len() >= last.len() && rest.ends_with(last)
+}
+
 #[derive(Default, Clone, Serialize, Deserialize)]
 pub struct LogInfo {
   level: LogLevel,
@@ -1,8 +1,18 @@
+pub mod camera_preview;
+pub mod name_labels;
+pub mod outline;
+pub mod placement;
+pub mod rulers;
+pub mod shading;
+pub mod stats_hud;
 pub mod view2d;
 pub mod view3d;
+pub mod view_cube;
 
 use crate::{
   cache::{Cache, Saveable},
+  read_only::ReadOnly,
+  settings::{EditorSettings, SettingsAppExt},
   ui::{
     misc::UiInfo,
     prebuilt::{editor_view::EditorView, game_view::GameView},
@@ -10,14 +20,19 @@ use crate::{
   Editing,
 };
 use bevy::{color::palettes::tailwind, prelude::*};
+use camera_preview::CameraPreviewPlugin;
+use name_labels::NameLabelSettings;
+use outline::SelectionOutlinePlugin;
+use placement::PlacementSettings;
+use rulers::GuideSettings;
 use serde::{Deserialize, Serialize};
+use shading::{ShadingPlugin, ShadingSettings};
+use stats_hud::StatsHudSettings;
 use view2d::View2d;
 use view3d::View3d;
 
 pub const UP: Vec3 = Vec3::Y;
 
-const GAME_CAMERA_COLOR: Srgba = tailwind::GREEN_700;
-
 pub struct EditorViewPlugin;
 
 impl EditorViewPlugin {
@@ -30,6 +45,7 @@ impl EditorViewPlugin {
 impl Plugin for EditorViewPlugin {
   fn build(&self, app: &mut bevy::prelude::App) {
     app
+      .add_plugins((SelectionOutlinePlugin, CameraPreviewPlugin, ShadingPlugin))
       .configure_sets(
         Update,
         (
@@ -48,7 +64,18 @@ impl Plugin for EditorViewPlugin {
           ZoomSet.in_set(CameraInput::Mouse),
         ),
       )
+      .register_settings::<GizmoColorSettings>()
+      .register_settings::<PlacementSettings>()
+      .register_settings::<StatsHudSettings>()
+      .register_settings::<GuideSettings>()
+      .register_settings::<NameLabelSettings>()
+      .register_settings::<ShadingSettings>()
+      .register_settings::<view2d::SpriteDragSettings>()
+      .register_settings::<LowLatencySettings>()
+      .register_settings::<view3d::OrbitSettings>()
+      .init_resource::<view3d::OrbitFocus>()
       .register_type::<ActiveEditorCamera>()
+      .register_type::<EditorPane>()
       .register_type::<view2d::CameraSettings>()
       .register_type::<view2d::CameraState>()
       .insert_state(ActiveEditorCamera::None)
@@ -60,6 +87,8 @@ impl Plugin for EditorViewPlugin {
       .add_systems(OnExit(ActiveEditorCamera::Cam2D), view2d::save_settings)
       .add_systems(OnEnter(ActiveEditorCamera::Cam3D), view3d::enable)
       .add_systems(OnExit(ActiveEditorCamera::Cam3D), view3d::save_settings)
+      .add_systems(Update, stats_hud::toggle.in_set(Editing))
+      .add_systems(Update, view3d::OrbitFocus::track_selection.in_set(View3d))
       .add_systems(
         Update,
         (
@@ -73,6 +102,7 @@ impl Plugin for EditorViewPlugin {
           )
             .chain(),
           view2d::movement_system.in_set(CameraInput::Keyboard),
+          view2d::drag_sprites.run_if(ReadOnly::is_writable),
         )
           .chain()
           .in_set(View2d),
@@ -84,7 +114,7 @@ impl Plugin for EditorViewPlugin {
           (
             view3d::mouse_input_actions.in_set(CameraInput::Mouse),
             (
-              view3d::orbit_system.in_set(OrbitSet),
+              (view3d::orbit_system, view3d::OrbitFocus::draw_pivot).in_set(OrbitSet),
               view3d::pan_system.in_set(PanSet),
               view3d::zoom_system.in_set(ZoomSet),
             ),
@@ -93,15 +123,116 @@ impl Plugin for EditorViewPlugin {
           view3d::movement_system.in_set(CameraInput::Keyboard),
         )
           .chain()
+          .after(view3d::OrbitFocus::track_selection)
           .in_set(View3d),
       );
   }
 }
 
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone)]
+#[reflect(Resource)]
+pub struct GizmoColorSettings {
+  pub selection: Color,
+  pub pointer_hit: Color,
+  pub pointer_normal: Color,
+  pub game_camera: Color,
+}
+
+impl Default for GizmoColorSettings {
+  fn default() -> Self {
+    Self {
+      selection: tailwind::AMBER_400.into(),
+      pointer_hit: tailwind::RED_500.into(),
+      pointer_normal: tailwind::PINK_100.into(),
+      game_camera: tailwind::GREEN_700.into(),
+    }
+  }
+}
+
+impl Saveable for GizmoColorSettings {
+  const KEY: &str = "gizmo_colors";
+}
+
+impl EditorSettings for GizmoColorSettings {
+  const NAME: &str = "Gizmo Colors";
+
+  fn presets() -> Vec<(&'static str, Self)> {
+    // based on the Okabe-Ito palette which stays distinguishable for the common forms of color blindness
+    let okabe_ito = |r, g, b| Color::srgb_u8(r, g, b);
+
+    vec![
+      ("Default", Self::default()),
+      (
+        "Deuteranopia / Protanopia",
+        Self {
+          selection: okabe_ito(240, 228, 66),
+          pointer_hit: okabe_ito(213, 94, 0),
+          pointer_normal: okabe_ito(86, 180, 233),
+          game_camera: okabe_ito(0, 114, 178),
+        },
+      ),
+      (
+        "Tritanopia",
+        Self {
+          selection: okabe_ito(204, 121, 167),
+          pointer_hit: okabe_ito(213, 94, 0),
+          pointer_normal: Color::WHITE,
+          game_camera: okabe_ito(0, 158, 115),
+        },
+      ),
+    ]
+  }
+}
+
+/// Pauses panels that are expensive to draw while the editor view is orbited or panned so the
+/// camera keeps moving at full frame rate
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone)]
+#[reflect(Resource)]
+pub struct LowLatencySettings {
+  pub enabled: bool,
+}
+
+impl Default for LowLatencySettings {
+  fn default() -> Self {
+    Self { enabled: true }
+  }
+}
+
+impl Saveable for LowLatencySettings {
+  const KEY: &str = "low_latency";
+}
+
+impl EditorSettings for LowLatencySettings {
+  const NAME: &str = "Low Latency Navigation";
+}
+
+/// Whether the editor view is being orbited or panned while [`LowLatencySettings`] is enabled
+pub(crate) fn navigating_with_low_latency(world: &World) -> bool {
+  let navigating = world
+    .get_resource::<State<OrbitState>>()
+    .is_some_and(|state| *state.get() == OrbitState::Active)
+    || world
+      .get_resource::<State<PanState>>()
+      .is_some_and(|state| *state.get() == PanState::Active);
+
+  navigating
+    && world
+      .get_resource::<LowLatencySettings>()
+      .is_some_and(|settings| settings.enabled)
+}
+
 #[derive(Default, Component, Reflect)]
-#[require(RayCastPickable)]
+#[require(RayCastPickable, EditorPane)]
 pub struct EditorCamera;
 
+/// Which pane of the editor view a camera draws into, the main camera is pane 0
+#[derive(Default, Component, Reflect, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct EditorPane(pub usize);
+
+/// Marks the editor camera of the pane that was last clicked, camera navigation only moves it
+#[derive(Default, Component)]
+pub struct FocusedPane;
+
 impl EditorCamera {}
 
 #[derive(
@@ -159,6 +290,7 @@ where
       (
         render_2d_cameras::<C>.in_set(View2d),
         render_3d_cameras::<C>.in_set(View3d),
+        camera_preview::sync_game_preview::<C>.in_set(Editing),
       ),
     );
 }
@@ -166,6 +298,7 @@ where
 #[allow(clippy::type_complexity)]
 fn render_2d_cameras<C: Component>(
   mut gizmos: Gizmos,
+  colors: Res<GizmoColorSettings>,
   q_cam: Query<(&Transform, &OrthographicProjection), (With<Camera2d>, With<C>)>,
 ) {
   for (transform, projection) in &q_cam {
@@ -173,7 +306,7 @@ fn render_2d_cameras<C: Component>(
     gizmos.rect(
       rect_pos,
       projection.area.max - projection.area.min,
-      GAME_CAMERA_COLOR,
+      colors.game_camera,
     );
   }
 }
@@ -181,22 +314,33 @@ fn render_2d_cameras<C: Component>(
 #[allow(clippy::type_complexity)]
 fn render_3d_cameras<C: Component>(
   mut gizmos: Gizmos,
+  colors: Res<GizmoColorSettings>,
   q_cam: Query<(&Transform, &Projection), (With<Camera3d>, With<C>)>,
 ) {
   for (transform, projection) in &q_cam {
     match projection {
       Projection::Perspective(perspective) => {
-        show_camera(*transform, perspective.aspect_ratio, &mut gizmos);
+        show_camera(
+          *transform,
+          perspective.aspect_ratio,
+          colors.game_camera,
+          &mut gizmos,
+        );
       }
       Projection::Orthographic(orthographic) => {
-        show_camera(*transform, orthographic.scale, &mut gizmos);
+        show_camera(
+          *transform,
+          orthographic.scale,
+          colors.game_camera,
+          &mut gizmos,
+        );
       }
     }
   }
 }
 
-fn show_camera(transform: Transform, scaler: f32, gizmos: &mut Gizmos) {
-  gizmos.cuboid(transform, GAME_CAMERA_COLOR);
+fn show_camera(transform: Transform, scaler: f32, color: Color, gizmos: &mut Gizmos) {
+  gizmos.cuboid(transform, color);
 
   let forward = transform.forward().as_vec3();
 
@@ -204,7 +348,7 @@ fn show_camera(transform: Transform, scaler: f32, gizmos: &mut Gizmos) {
   let rect_iso = Isometry3d::new(rect_pos, transform.rotation);
   let rect_dim = Vec2::new(scaler, 1.0);
 
-  gizmos.rect(rect_iso, rect_dim, GAME_CAMERA_COLOR);
+  gizmos.rect(rect_iso, rect_dim, color);
 
   let start = transform.translation + forward * transform.scale / 2.0;
 
@@ -218,7 +362,7 @@ fn show_camera(transform: Transform, scaler: f32, gizmos: &mut Gizmos) {
   .map(|corner| rect_iso * corner);
 
   for corner in rect_corners {
-    gizmos.line(start, corner, GAME_CAMERA_COLOR);
+    gizmos.line(start, corner, color);
   }
 }
 
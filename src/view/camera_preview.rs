@@ -0,0 +1,373 @@
+use super::{outline::OUTLINE_LAYER, EditorCamera, GizmoColorSettings};
+use crate::{
+  cache::Saveable,
+  settings::{EditorSettings, SettingsAppExt},
+  ui::InspectorSelection,
+  Editing, EditorState,
+};
+use bevy::{
+  asset::RenderAssetUsages,
+  prelude::*,
+  render::{
+    camera::{CameraProjection, RenderTarget},
+    render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+    view::RenderLayers,
+  },
+};
+use bevy_egui::{egui, EguiUserTextures};
+use serde::{Deserialize, Serialize};
+
+/// Size of the preview shown in the inspector
+const PREVIEW_SIZE: UVec2 = UVec2::new(320, 180);
+/// Size of the game camera's picture-in-picture, larger since it's shown over the editor view
+const PIP_SIZE: UVec2 = UVec2::new(640, 360);
+const PIP_MARGIN: f32 = 8.0;
+
+/// Far planes are usually much further away than anything worth looking at in the editor view
+const MAX_FRUSTUM_DEPTH: f32 = 25.0;
+
+/// Marks the cameras rendering what another camera sees
+#[derive(Component)]
+pub(crate) struct CameraPreview;
+
+/// Frustums are only drawn for the editor cameras so they never show up in a preview or game view
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct FrustumGizmos;
+
+/// Shows what the game camera sees in a corner of the editor view while editing
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone)]
+#[reflect(Resource)]
+pub struct GamePreviewSettings {
+  pub enabled: bool,
+  /// Width of the picture-in-picture as a fraction of the editor view
+  pub size: f32,
+}
+
+impl Default for GamePreviewSettings {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      size: 0.25,
+    }
+  }
+}
+
+impl Saveable for GamePreviewSettings {
+  const KEY: &str = "game_preview";
+}
+
+impl EditorSettings for GamePreviewSettings {
+  const NAME: &str = "Game Camera Preview";
+}
+
+/// A camera rendering into an image on behalf of another camera
+struct Preview {
+  size: UVec2,
+  texture: Option<(Handle<Image>, egui::TextureId)>,
+  /// Previewed camera and the camera previewing it
+  cameras: Option<(Entity, Entity)>,
+}
+
+impl Preview {
+  fn new(size: UVec2) -> Self {
+    Self {
+      size,
+      texture: None,
+      cameras: None,
+    }
+  }
+
+  /// Keeps the preview camera in sync with `source`, or removes it when there is nothing to preview
+  fn sync(
+    &mut self,
+    commands: &mut Commands,
+    images: &mut Assets<Image>,
+    egui_textures: &mut EguiUserTextures,
+    source: Option<(Entity, SourceItem)>,
+  ) {
+    let Some((entity, (camera, transform, projection, orthographic, layers))) = source else {
+      self.clear(commands);
+      return;
+    };
+
+    let size = self.size;
+    let image = self
+      .texture
+      .get_or_insert_with(|| {
+        let image = images.add(preview_image(size));
+        let texture = egui_textures.add_image(image.clone());
+        (image, texture)
+      })
+      .0
+      .clone();
+
+    let preview = match self.cameras {
+      Some((previewed, preview)) if previewed == entity => preview,
+      previous => {
+        if let Some((_, preview)) = previous {
+          commands.entity(preview).despawn();
+        }
+
+        let preview = commands
+          .spawn((Name::new("Camera Preview"), CameraPreview))
+          .id();
+        match projection {
+          Some(_) => commands.entity(preview).insert(Camera3d::default()),
+          None => commands.entity(preview).insert(Camera2d),
+        };
+        self.cameras = Some((entity, preview));
+        preview
+      }
+    };
+
+    let mut preview = commands.entity(preview);
+    preview.insert((
+      Camera {
+        target: RenderTarget::Image(image),
+        clear_color: camera.clear_color,
+        order: -1,
+        hdr: camera.hdr,
+        ..default()
+      },
+      transform.compute_transform(),
+      layers.cloned().unwrap_or_default(),
+    ));
+
+    if let Some(projection) = projection {
+      preview.insert(projection.clone());
+    } else if let Some(orthographic) = orthographic {
+      preview.insert(orthographic.clone());
+    }
+  }
+
+  fn clear(&mut self, commands: &mut Commands) {
+    if let Some((_, preview)) = self.cameras.take() {
+      commands.entity(preview).despawn();
+    }
+  }
+
+  /// Texture of the preview while it is previewing `entity`, or any camera if `None`
+  fn texture(&self, entity: Option<Entity>) -> Option<egui::TextureId> {
+    let (_, texture) = self.texture.as_ref()?;
+    let (previewed, _) = self.cameras?;
+    entity
+      .is_none_or(|entity| entity == previewed)
+      .then_some(*texture)
+  }
+}
+
+/// Preview of the selected camera
+#[derive(Resource)]
+struct PreviewTarget(Preview);
+
+impl Default for PreviewTarget {
+  fn default() -> Self {
+    Self(Preview::new(PREVIEW_SIZE))
+  }
+}
+
+/// Preview of the game camera
+#[derive(Resource)]
+pub(crate) struct GamePreview(Preview);
+
+impl Default for GamePreview {
+  fn default() -> Self {
+    Self(Preview::new(PIP_SIZE))
+  }
+}
+
+type SourceCamera = (With<Camera>, Without<EditorCamera>, Without<CameraPreview>);
+
+type SourceData = (
+  &'static Camera,
+  &'static GlobalTransform,
+  Option<&'static Projection>,
+  Option<&'static OrthographicProjection>,
+  Option<&'static RenderLayers>,
+);
+
+type SourceItem<'a> = (
+  &'a Camera,
+  &'a GlobalTransform,
+  Option<&'a Projection>,
+  Option<&'a OrthographicProjection>,
+  Option<&'a RenderLayers>,
+);
+
+pub(crate) struct CameraPreviewPlugin;
+
+impl Plugin for CameraPreviewPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .init_gizmo_group::<FrustumGizmos>()
+      .register_settings::<GamePreviewSettings>()
+      .init_resource::<PreviewTarget>()
+      .init_resource::<GamePreview>()
+      .add_systems(Startup, configure_gizmos)
+      .add_systems(OnExit(EditorState::Editing), clear_previews)
+      .add_systems(Update, (draw_frustums, sync_preview).in_set(Editing));
+  }
+}
+
+fn configure_gizmos(mut config_store: ResMut<GizmoConfigStore>) {
+  let (config, _) = config_store.config_mut::<FrustumGizmos>();
+  config.render_layers = RenderLayers::layer(OUTLINE_LAYER);
+}
+
+fn selected_entities(selection: &InspectorSelection) -> Vec<Entity> {
+  match selection {
+    InspectorSelection::Entities(selected) => selected.iter().collect(),
+    _ => default(),
+  }
+}
+
+fn draw_frustums(
+  mut gizmos: Gizmos<FrustumGizmos>,
+  colors: Res<GizmoColorSettings>,
+  selection: Res<InspectorSelection>,
+  q_cameras: Query<
+    (
+      &GlobalTransform,
+      Option<&Projection>,
+      Option<&OrthographicProjection>,
+    ),
+    SourceCamera,
+  >,
+) {
+  for entity in selected_entities(&selection) {
+    let Ok((transform, projection, orthographic)) = q_cameras.get(entity) else {
+      continue;
+    };
+
+    let corners = match (projection, orthographic) {
+      (Some(Projection::Perspective(perspective)), _) => {
+        perspective.get_frustum_corners(-perspective.near, -perspective.far.min(MAX_FRUSTUM_DEPTH))
+      }
+      (Some(Projection::Orthographic(orthographic)), _) | (None, Some(orthographic)) => {
+        // 2d cameras look at everything between their planes, only the visible area is drawn
+        let far = orthographic.far.min(orthographic.near + MAX_FRUSTUM_DEPTH);
+        orthographic.get_frustum_corners(-orthographic.near.max(0.0), -far.max(0.0))
+      }
+      (None, None) => continue,
+    };
+
+    let corners = corners.map(|corner| transform.transform_point(corner.into()));
+    for i in 0..4 {
+      let next = (i + 1) % 4;
+      gizmos.line(corners[i], corners[next], colors.game_camera);
+      gizmos.line(corners[i + 4], corners[next + 4], colors.game_camera);
+      gizmos.line(corners[i], corners[i + 4], colors.game_camera);
+    }
+  }
+}
+
+/// Keeps a camera rendering into the preview image in sync with the selected camera
+fn sync_preview(
+  mut commands: Commands,
+  mut target: ResMut<PreviewTarget>,
+  mut images: ResMut<Assets<Image>>,
+  mut egui_textures: ResMut<EguiUserTextures>,
+  selection: Res<InspectorSelection>,
+  q_sources: Query<SourceData, SourceCamera>,
+) {
+  let source = match selected_entities(&selection).as_slice() {
+    &[entity] => q_sources.get(entity).ok().map(|source| (entity, source)),
+    _ => None,
+  };
+
+  target
+    .0
+    .sync(&mut commands, &mut images, &mut egui_textures, source);
+}
+
+/// Keeps a camera rendering into the picture-in-picture in sync with the game camera
+pub(crate) fn sync_game_preview<C: Component>(
+  mut commands: Commands,
+  mut preview: ResMut<GamePreview>,
+  mut images: ResMut<Assets<Image>>,
+  mut egui_textures: ResMut<EguiUserTextures>,
+  settings: Res<GamePreviewSettings>,
+  q_sources: Query<(Entity, SourceData), (SourceCamera, With<C>)>,
+) {
+  let source = settings.enabled.then(|| q_sources.iter().next()).flatten();
+
+  preview
+    .0
+    .sync(&mut commands, &mut images, &mut egui_textures, source);
+}
+
+fn clear_previews(
+  mut commands: Commands,
+  mut target: ResMut<PreviewTarget>,
+  mut game_preview: ResMut<GamePreview>,
+) {
+  target.0.clear(&mut commands);
+  game_preview.0.clear(&mut commands);
+}
+
+fn preview_image(size: UVec2) -> Image {
+  let size = Extent3d {
+    width: size.x,
+    height: size.y,
+    depth_or_array_layers: 1,
+  };
+
+  let mut image = Image::new_fill(
+    size,
+    TextureDimension::D2,
+    &[0, 0, 0, 0],
+    TextureFormat::Bgra8UnormSrgb,
+    RenderAssetUsages::default(),
+  );
+  image.texture_descriptor.usage =
+    TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT;
+
+  image
+}
+
+/// What the camera sees, shown in the inspector while it is the only selected entity
+pub(crate) fn ui(ui: &mut egui::Ui, world: &World, entity: Entity) {
+  let Some(texture) = world.resource::<PreviewTarget>().0.texture(Some(entity)) else {
+    return;
+  };
+
+  egui::CollapsingHeader::new("Camera Preview")
+    .id_salt(("camera_preview", entity))
+    .default_open(true)
+    .show(ui, |ui| {
+      let width = ui.available_width().min(PREVIEW_SIZE.x as f32);
+      let height = width * PREVIEW_SIZE.y as f32 / PREVIEW_SIZE.x as f32;
+      ui.image(egui::load::SizedTexture::new(
+        texture,
+        egui::vec2(width, height),
+      ));
+    });
+}
+
+/// What the game camera sees, in the bottom right corner of the editor view
+pub(crate) fn pip_ui(
+  ui: &egui::Ui,
+  viewport: egui::Rect,
+  preview: &GamePreview,
+  settings: &GamePreviewSettings,
+) {
+  let Some(texture) = preview.0.texture(None) else {
+    return;
+  };
+
+  let width = viewport.width() * settings.size.clamp(0.05, 1.0);
+  let size = egui::vec2(width, width * PIP_SIZE.y as f32 / PIP_SIZE.x as f32);
+  let rect = egui::Rect::from_min_size(
+    viewport.right_bottom() - size - egui::Vec2::splat(PIP_MARGIN),
+    size,
+  );
+
+  let painter = ui.painter_at(viewport);
+  painter.image(
+    texture,
+    rect,
+    egui::Rect::from_min_max(egui::Pos2::ZERO, egui::pos2(1.0, 1.0)),
+    egui::Color32::WHITE,
+  );
+  painter.rect_stroke(rect, 0.0, ui.visuals().window_stroke());
+}
@@ -0,0 +1,156 @@
+use crate::{cache::Saveable, settings::EditorSettings};
+use bevy::prelude::*;
+use bevy_egui::egui;
+use serde::{Deserialize, Serialize};
+
+/// Gap between an entity's origin and the bottom of its label, in points
+const LABEL_OFFSET: f32 = 6.0;
+const LABEL_PADDING: f32 = 2.0;
+
+/// Labels with the [`Name`] of every named entity, drawn over the editor view
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone)]
+#[reflect(Resource)]
+pub struct NameLabelSettings {
+  pub enabled: bool,
+  /// Labels shown at once, the closest to the camera win
+  pub max_labels: usize,
+  /// Distance from the 3d camera labels start fading out at
+  pub fade_start: f32,
+  /// Distance from the 3d camera labels are no longer shown at
+  pub fade_end: f32,
+}
+
+impl Default for NameLabelSettings {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      max_labels: 100,
+      fade_start: 25.0,
+      fade_end: 50.0,
+    }
+  }
+}
+
+impl Saveable for NameLabelSettings {
+  const KEY: &str = "name_labels";
+}
+
+impl EditorSettings for NameLabelSettings {
+  const NAME: &str = "Name Labels";
+}
+
+impl NameLabelSettings {
+  /// Opacity of a label at the distance, orthographic cameras don't fade
+  fn opacity(&self, distance: f32, perspective: bool) -> f32 {
+    if !perspective || distance <= self.fade_start {
+      return 1.0;
+    }
+
+    let range = (self.fade_end - self.fade_start).max(f32::EPSILON);
+    1.0 - ((distance - self.fade_start) / range).clamp(0.0, 1.0)
+  }
+}
+
+/// Draws the names of `labels`, given as the world position and name of each entity, over the
+/// viewport of `camera`
+pub fn ui<'a>(
+  ui: &egui::Ui,
+  viewport: egui::Rect,
+  camera: (&Camera, &GlobalTransform),
+  labels: impl Iterator<Item = (Vec3, &'a str)>,
+  settings: &NameLabelSettings,
+) {
+  if !settings.enabled || settings.max_labels == 0 {
+    return;
+  }
+
+  let (camera, camera_transform) = camera;
+  // orthographic projections keep w at 1
+  let perspective = camera.clip_from_view().w_axis.w == 0.0;
+  let camera_position = camera_transform.translation();
+
+  let mut visible = labels
+    .filter_map(|(position, name)| {
+      let ndc = camera.world_to_ndc(camera_transform, position)?;
+      let in_view = ndc.x.abs() <= 1.0 && ndc.y.abs() <= 1.0 && (0.0..=1.0).contains(&ndc.z);
+      if !in_view {
+        return None;
+      }
+
+      // a 2d camera looks down the z axis from far away, only how far off center matters
+      let distance = if perspective {
+        camera_position.distance(position)
+      } else {
+        camera_position.truncate().distance(position.truncate())
+      };
+
+      let opacity = settings.opacity(distance, perspective);
+      if opacity <= 0.0 {
+        return None;
+      }
+
+      let screen = egui::pos2(
+        viewport.left() + (ndc.x + 1.0) / 2.0 * viewport.width(),
+        viewport.top() + (1.0 - ndc.y) / 2.0 * viewport.height(),
+      );
+
+      Some((distance, screen, opacity, name))
+    })
+    .collect::<Vec<_>>();
+
+  visible.sort_by(|(a, ..), (b, ..)| a.total_cmp(b));
+  visible.truncate(settings.max_labels);
+
+  let painter = ui.painter_at(viewport);
+  let font = egui::TextStyle::Small.resolve(ui.style());
+  let visuals = ui.visuals();
+
+  // farthest first so closer labels are drawn on top
+  for (_, screen, opacity, name) in visible.into_iter().rev() {
+    let galley = painter.layout_no_wrap(
+      name.to_string(),
+      font.clone(),
+      visuals.strong_text_color().gamma_multiply(opacity),
+    );
+    let rect = egui::Align2::CENTER_BOTTOM
+      .anchor_size(screen - egui::vec2(0.0, LABEL_OFFSET), galley.size());
+
+    painter.rect_filled(
+      rect.expand(LABEL_PADDING),
+      LABEL_PADDING,
+      visuals.extreme_bg_color.gamma_multiply(0.7 * opacity),
+    );
+    painter.galley(rect.min, galley, egui::Color32::PLACEHOLDER);
+  }
+}
+
+/// Label options shown in the view menu
+pub(crate) fn menu_ui(ui: &mut egui::Ui, settings: &mut NameLabelSettings) {
+  ui.checkbox(&mut settings.enabled, "Entity Names")
+    .on_hover_text("Label every named entity in the editor view");
+
+  ui.add_enabled_ui(settings.enabled, |ui| {
+    ui.menu_button("Name Labels", |ui| {
+      ui.horizontal(|ui| {
+        ui.label("Max Labels");
+        ui.add(egui::DragValue::new(&mut settings.max_labels).range(1..=1000));
+      });
+
+      ui.horizontal(|ui| {
+        ui.label("Fade Distance");
+        ui.add(
+          egui::DragValue::new(&mut settings.fade_start)
+            .range(0.0..=settings.fade_end)
+            .speed(0.5),
+        );
+        ui.add(
+          egui::DragValue::new(&mut settings.fade_end)
+            .range(settings.fade_start..=f32::MAX)
+            .speed(0.5),
+        );
+      })
+      .response
+      .on_hover_text("Labels fade out between these distances from the 3D camera");
+    });
+  });
+}
@@ -0,0 +1,6 @@
+#import bevy_pbr::forward_io::VertexOutput
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+  return vec4(normalize(in.world_normal) * 0.5 + 0.5, 1.0);
+}
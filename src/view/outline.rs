@@ -0,0 +1,285 @@
+use super::{EditorCamera, GizmoColorSettings};
+use crate::{
+  cache::Saveable,
+  settings::{EditorSettings, SettingsAppExt},
+  ui::InspectorSelection,
+  Editing, EditorState,
+};
+use bevy::{
+  asset::load_internal_asset,
+  pbr::{MaterialPipeline, MaterialPipelineKey, NotShadowCaster},
+  prelude::*,
+  render::{
+    mesh::MeshVertexBufferLayoutRef,
+    primitives::Aabb,
+    render_resource::{
+      AsBindGroup, Face, RenderPipelineDescriptor, ShaderRef, SpecializedMeshPipelineError,
+    },
+    view::{RenderLayers, VisibilitySystems},
+  },
+  transform::TransformSystem,
+  utils::{HashMap, HashSet},
+};
+use serde::{Deserialize, Serialize};
+
+/// Render layer outlines are drawn on
+///
+/// Sits just past the layers the render layers panel exposes so outlines can't be muted from there
+pub const OUTLINE_LAYER: usize = 64;
+
+const OUTLINE_SHADER: Handle<Shader> = Handle::weak_from_u128(0x6d1c2f0a8e4b4c3e9f571a2b3c4d5e6f);
+
+pub struct SelectionOutlinePlugin;
+
+impl Plugin for SelectionOutlinePlugin {
+  fn build(&self, app: &mut App) {
+    load_internal_asset!(app, OUTLINE_SHADER, "outline.wgsl", Shader::from_wgsl);
+
+    app
+      .add_plugins(MaterialPlugin::<OutlineMaterial> {
+        prepass_enabled: false,
+        shadows_enabled: false,
+        ..default()
+      })
+      .init_gizmo_group::<OutlineGizmos>()
+      .register_settings::<OutlineSettings>()
+      .init_resource::<OutlineMaterialHandle>()
+      .init_resource::<OutlineHulls>()
+      .add_systems(
+        Update,
+        (
+          include_outline_layer,
+          update_style,
+          update_hulls,
+          draw_2d_outlines,
+        )
+          .in_set(Editing),
+      )
+      .add_systems(
+        PostUpdate,
+        sync_hull_transforms
+          .after(TransformSystem::TransformPropagate)
+          .before(VisibilitySystems::CheckVisibility)
+          .run_if(in_state(EditorState::Editing)),
+      )
+      .add_systems(OnExit(EditorState::Editing), despawn_hulls);
+  }
+}
+
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone)]
+#[reflect(Resource)]
+pub struct OutlineSettings {
+  pub enabled: bool,
+  /// Width of the outline around meshes in world units
+  pub thickness: f32,
+  /// Width of the outline around sprites and 2d meshes in pixels
+  pub line_width: f32,
+}
+
+impl Default for OutlineSettings {
+  fn default() -> Self {
+    Self {
+      enabled: true,
+      thickness: 0.02,
+      line_width: 3.0,
+    }
+  }
+}
+
+impl Saveable for OutlineSettings {
+  const KEY: &str = "selection_outline";
+}
+
+impl EditorSettings for OutlineSettings {
+  const NAME: &str = "Selection Outline";
+}
+
+/// Draws the back faces of a selected mesh pushed out along their normals
+#[derive(Asset, TypePath, AsBindGroup, Clone, Default)]
+pub struct OutlineMaterial {
+  #[uniform(0)]
+  color: LinearRgba,
+  #[uniform(0)]
+  thickness: f32,
+}
+
+impl Material for OutlineMaterial {
+  fn vertex_shader() -> ShaderRef {
+    OUTLINE_SHADER.into()
+  }
+
+  fn fragment_shader() -> ShaderRef {
+    OUTLINE_SHADER.into()
+  }
+
+  fn specialize(
+    _pipeline: &MaterialPipeline<Self>,
+    descriptor: &mut RenderPipelineDescriptor,
+    layout: &MeshVertexBufferLayoutRef,
+    _key: MaterialPipelineKey<Self>,
+  ) -> Result<(), SpecializedMeshPipelineError> {
+    let vertex_layout = layout.0.get_layout(&[
+      Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
+      Mesh::ATTRIBUTE_NORMAL.at_shader_location(1),
+    ])?;
+    descriptor.vertex.buffers = vec![vertex_layout];
+    descriptor.primitive.cull_mode = Some(Face::Front);
+    Ok(())
+  }
+}
+
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct OutlineGizmos;
+
+/// Marker for the overlay entities that draw the outline of a selected mesh
+#[derive(Component)]
+pub struct SelectionOutline;
+
+#[derive(Resource, Deref)]
+struct OutlineMaterialHandle(Handle<OutlineMaterial>);
+
+impl FromWorld for OutlineMaterialHandle {
+  fn from_world(world: &mut World) -> Self {
+    let mut materials = world.resource_mut::<Assets<OutlineMaterial>>();
+    Self(materials.add(OutlineMaterial::default()))
+  }
+}
+
+/// Selected entity to the outline entity drawn for it
+#[derive(Resource, Default, Deref, DerefMut)]
+struct OutlineHulls(HashMap<Entity, Entity>);
+
+fn include_outline_layer(
+  mut commands: Commands,
+  q_cams: Query<(Entity, Option<&RenderLayers>), With<EditorCamera>>,
+) {
+  for (entity, layers) in &q_cams {
+    let layers = layers.cloned().unwrap_or_default();
+    if !layers.intersects(&RenderLayers::layer(OUTLINE_LAYER)) {
+      commands.entity(entity).insert(layers.with(OUTLINE_LAYER));
+    }
+  }
+}
+
+fn update_style(
+  settings: Res<OutlineSettings>,
+  colors: Res<GizmoColorSettings>,
+  material: Res<OutlineMaterialHandle>,
+  mut materials: ResMut<Assets<OutlineMaterial>>,
+  mut config_store: ResMut<GizmoConfigStore>,
+) {
+  if !settings.is_changed() && !colors.is_changed() {
+    return;
+  }
+
+  if let Some(material) = materials.get_mut(&**material) {
+    material.color = colors.selection.into();
+    material.thickness = settings.thickness;
+  }
+
+  let (config, _) = config_store.config_mut::<OutlineGizmos>();
+  config.enabled = settings.enabled;
+  config.line_width = settings.line_width;
+  config.render_layers = RenderLayers::layer(OUTLINE_LAYER);
+}
+
+fn update_hulls(
+  mut commands: Commands,
+  mut hulls: ResMut<OutlineHulls>,
+  settings: Res<OutlineSettings>,
+  selection: Res<InspectorSelection>,
+  material: Res<OutlineMaterialHandle>,
+  q_meshes: Query<&Mesh3d, Without<SelectionOutline>>,
+  q_hulls: Query<&Mesh3d, With<SelectionOutline>>,
+) {
+  let selected = match selection.as_ref() {
+    InspectorSelection::Entities(selected) if settings.enabled => selected
+      .iter()
+      .filter(|entity| q_meshes.contains(*entity))
+      .collect::<HashSet<_>>(),
+    _ => default(),
+  };
+
+  // drop outlines of entities that were deselected, despawned, or had their mesh swapped out
+  hulls.retain(|source, hull| {
+    let current = selected.contains(source)
+      && q_meshes
+        .get(*source)
+        .ok()
+        .zip(q_hulls.get(*hull).ok())
+        .is_some_and(|(source_mesh, hull_mesh)| source_mesh.0 == hull_mesh.0);
+
+    if !current {
+      if let Some(mut hull) = commands.get_entity(*hull) {
+        hull.despawn();
+      }
+    }
+
+    current
+  });
+
+  for source in selected {
+    if hulls.contains_key(&source) {
+      continue;
+    }
+
+    let Ok(mesh) = q_meshes.get(source) else {
+      continue;
+    };
+
+    let hull = commands
+      .spawn((
+        Name::new("Selection Outline"),
+        SelectionOutline,
+        mesh.clone(),
+        MeshMaterial3d(material.0.clone()),
+        NotShadowCaster,
+        RenderLayers::layer(OUTLINE_LAYER),
+      ))
+      .id();
+
+    hulls.insert(source, hull);
+  }
+}
+
+fn sync_hull_transforms(
+  hulls: Res<OutlineHulls>,
+  q_sources: Query<&GlobalTransform, Without<SelectionOutline>>,
+  mut q_hulls: Query<&mut GlobalTransform, With<SelectionOutline>>,
+) {
+  for (source, hull) in hulls.iter() {
+    if let Some((source, mut hull)) = q_sources.get(*source).ok().zip(q_hulls.get_mut(*hull).ok()) {
+      *hull = *source;
+    }
+  }
+}
+
+#[allow(clippy::type_complexity)]
+fn draw_2d_outlines(
+  mut gizmos: Gizmos<OutlineGizmos>,
+  selection: Res<InspectorSelection>,
+  colors: Res<GizmoColorSettings>,
+  q_bounds: Query<(&GlobalTransform, &Aabb), Or<(With<Sprite>, With<Mesh2d>)>>,
+) {
+  let InspectorSelection::Entities(selected) = selection.as_ref() else {
+    return;
+  };
+
+  for (transform, aabb) in selected
+    .iter()
+    .filter_map(|entity| q_bounds.get(entity).ok())
+  {
+    let (scale, rotation, _) = transform.to_scale_rotation_translation();
+    let center = transform.transform_point(aabb.center.into());
+    let size = Vec3::from(aabb.half_extents).truncate() * scale.truncate() * 2.0;
+    gizmos.rect(Isometry3d::new(center, rotation), size, colors.selection);
+  }
+}
+
+fn despawn_hulls(mut commands: Commands, mut hulls: ResMut<OutlineHulls>) {
+  for (_, hull) in hulls.drain() {
+    if let Some(mut hull) = commands.get_entity(hull) {
+      hull.despawn();
+    }
+  }
+}
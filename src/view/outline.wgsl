@@ -0,0 +1,32 @@
+#import bevy_pbr::{
+  mesh_functions,
+  view_transformations::position_world_to_clip,
+}
+
+struct OutlineUniform {
+  color: vec4<f32>,
+  thickness: f32,
+}
+
+@group(2) @binding(0) var<uniform> outline: OutlineUniform;
+
+struct Vertex {
+  @builtin(instance_index) instance_index: u32,
+  @location(0) position: vec3<f32>,
+  @location(1) normal: vec3<f32>,
+}
+
+// inverted hull, the mesh is pushed out along its normals and only the back faces are drawn so the
+// original mesh covers everything but the rim
+@vertex
+fn vertex(vertex: Vertex) -> @builtin(position) vec4<f32> {
+  let world_from_local = mesh_functions::get_world_from_local(vertex.instance_index);
+  let world_position = mesh_functions::mesh_position_local_to_world(world_from_local, vec4(vertex.position, 1.0));
+  let world_normal = mesh_functions::mesh_normal_local_to_world(vertex.normal, vertex.instance_index);
+  return position_world_to_clip(world_position.xyz + normalize(world_normal) * outline.thickness);
+}
+
+@fragment
+fn fragment() -> @location(0) vec4<f32> {
+  return outline.color;
+}
@@ -0,0 +1,71 @@
+use super::{ActiveEditorCamera, UP};
+use crate::{assets::Prefabs, cache::Saveable, settings::EditorSettings, util::WorldExtensions};
+use bevy::{
+  ecs::system::SystemState,
+  picking::mesh_picking::ray_cast::{MeshRayCast, RayCastSettings},
+  prelude::*,
+  render::mesh::MeshAabb,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone, Default)]
+#[reflect(Resource)]
+pub struct PlacementSettings {
+  /// Drop entities spawned in the 3d view onto the surface below them, or the ground plane if
+  /// there is none
+  pub snap_to_ground: bool,
+}
+
+impl Saveable for PlacementSettings {
+  const KEY: &str = "placement";
+}
+
+impl EditorSettings for PlacementSettings {
+  const NAME: &str = "Placement";
+}
+
+/// Spawns a prefab and places it according to the [`PlacementSettings`]
+pub fn spawn_prefab(id: impl AsRef<str>, world: &mut World) -> Option<Entity> {
+  let entity = world.resource_scope(|world, mut prefabs: Mut<Prefabs>| prefabs.spawn(id, world))?;
+
+  let snap = world.resource::<PlacementSettings>().snap_to_ground
+    && world.get_state::<ActiveEditorCamera>() == ActiveEditorCamera::Cam3D;
+
+  if snap {
+    snap_to_ground(entity, world);
+  }
+
+  Some(entity)
+}
+
+/// Moves the entity down until its mesh rests on the first surface below it, or onto Y=0
+pub fn snap_to_ground(entity: Entity, world: &mut World) {
+  let Some(transform) = world.get::<Transform>(entity).copied() else {
+    return;
+  };
+
+  // distance between the origin of the entity and the bottom of its mesh
+  let bottom_offset = world
+    .get::<Mesh3d>(entity)
+    .and_then(|mesh| world.resource::<Assets<Mesh>>().get(mesh))
+    .and_then(Mesh::compute_aabb)
+    .map(|aabb| -aabb.min().y * transform.scale.y)
+    .unwrap_or_default();
+
+  let mut state = SystemState::<MeshRayCast>::new(world);
+  let mut ray_cast = state.get_mut(world);
+
+  let ray = Ray3d::new(transform.translation, Dir3::NEG_Y);
+  let filter = |hit: Entity| hit != entity;
+  let settings = RayCastSettings::default().with_filter(&filter);
+
+  let ground = ray_cast
+    .cast_ray(ray, &settings)
+    .first()
+    .map(|(_, hit)| hit.point.y)
+    .unwrap_or(0.0);
+
+  if let Some(mut transform) = world.get_mut::<Transform>(entity) {
+    transform.translation = transform.translation.reject_from(UP) + UP * (ground + bottom_offset);
+  }
+}
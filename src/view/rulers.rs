@@ -0,0 +1,342 @@
+use crate::{cache::Saveable, settings::EditorSettings};
+use bevy::prelude::*;
+use bevy_egui::egui;
+use serde::{Deserialize, Serialize};
+
+const RULER_SIZE: f32 = 18.0;
+/// Closest two labeled ticks get to each other, in points
+const MIN_TICK_SPACING: f32 = 60.0;
+const MINOR_TICKS: usize = 5;
+/// How far from a guide it can still be grabbed, in points
+const GUIDE_GRAB_DISTANCE: f32 = 4.0;
+
+/// Margins of a device's safe area, as fractions of the frame
+#[derive(Default, Clone, Copy, PartialEq, Debug, Reflect, Serialize, Deserialize)]
+pub enum SafeArea {
+  #[default]
+  None,
+  /// Action safe area of a television, 5% on every side
+  TvActionSafe,
+  /// Title safe area of a television, 10% on every side
+  TvTitleSafe,
+  /// A phone held in landscape with the notch on one side and the home indicator at the bottom
+  PhoneLandscape,
+  /// A phone held in portrait with the notch at the top and the home indicator at the bottom
+  PhonePortrait,
+}
+
+impl SafeArea {
+  const ALL: [Self; 5] = [
+    Self::None,
+    Self::TvActionSafe,
+    Self::TvTitleSafe,
+    Self::PhoneLandscape,
+    Self::PhonePortrait,
+  ];
+
+  fn name(self) -> &'static str {
+    match self {
+      Self::None => "None",
+      Self::TvActionSafe => "TV Action Safe",
+      Self::TvTitleSafe => "TV Title Safe",
+      Self::PhoneLandscape => "Phone Landscape",
+      Self::PhonePortrait => "Phone Portrait",
+    }
+  }
+
+  /// Left, right, top and bottom margins
+  fn margins(self) -> Option<[f32; 4]> {
+    match self {
+      Self::None => None,
+      Self::TvActionSafe => Some([0.05; 4]),
+      Self::TvTitleSafe => Some([0.1; 4]),
+      Self::PhoneLandscape => Some([0.056, 0.056, 0.0, 0.054]),
+      Self::PhonePortrait => Some([0.0, 0.0, 0.056, 0.04]),
+    }
+  }
+}
+
+/// A line across the 2d view at a fixed world position
+#[derive(Clone, Copy, Debug, Reflect, Serialize, Deserialize)]
+pub struct Guide {
+  /// Vertical guides sit at an x position, horizontal ones at a y position
+  pub vertical: bool,
+  pub position: f32,
+}
+
+/// Rulers along the edges of the 2d view, guides dragged out of them and the safe area of the
+/// target device
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone)]
+#[reflect(Resource)]
+pub struct GuideSettings {
+  pub rulers: bool,
+  /// Size of the screen the game targets in world units, centered on the world origin
+  pub frame: Vec2,
+  pub safe_area: SafeArea,
+  pub guides: Vec<Guide>,
+}
+
+impl Default for GuideSettings {
+  fn default() -> Self {
+    Self {
+      rulers: false,
+      frame: Vec2::new(1920.0, 1080.0),
+      safe_area: SafeArea::None,
+      guides: Vec::new(),
+    }
+  }
+}
+
+impl Saveable for GuideSettings {
+  const KEY: &str = "guides_2d";
+}
+
+impl EditorSettings for GuideSettings {
+  const NAME: &str = "2D Rulers & Guides";
+}
+
+/// Converts between world positions and points of the viewport
+struct ViewMapping {
+  viewport: egui::Rect,
+  center: Vec2,
+  units_per_point: f32,
+}
+
+impl ViewMapping {
+  fn to_screen(&self, world: Vec2) -> egui::Pos2 {
+    let offset = (world - self.center) / self.units_per_point;
+    self.viewport.center() + egui::vec2(offset.x, -offset.y)
+  }
+
+  fn to_world(&self, pos: egui::Pos2) -> Vec2 {
+    let offset = pos - self.viewport.center();
+    self.center + Vec2::new(offset.x, -offset.y) * self.units_per_point
+  }
+
+  /// Distance between labeled ticks, a power of ten times 1, 2 or 5
+  fn tick_step(&self) -> f32 {
+    let min_step = MIN_TICK_SPACING * self.units_per_point;
+    let magnitude = 10f32.powf(min_step.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+      .into_iter()
+      .map(|factor| factor * magnitude)
+      .find(|step| *step >= min_step)
+      .unwrap_or(magnitude * 10.0)
+  }
+}
+
+/// Draws the rulers, guides and safe area over the 2d view
+pub fn ui(
+  ui: &mut egui::Ui,
+  viewport: egui::Rect,
+  camera: (&GlobalTransform, &OrthographicProjection),
+  settings: &mut GuideSettings,
+) {
+  let (transform, projection) = camera;
+  if viewport.width() <= 0.0 || projection.area.width() <= 0.0 {
+    return;
+  }
+
+  let mapping = ViewMapping {
+    viewport,
+    center: transform.translation().truncate(),
+    units_per_point: projection.area.width() / viewport.width(),
+  };
+
+  safe_area_ui(ui, &mapping, settings);
+
+  if settings.rulers {
+    guides_ui(ui, &mapping, settings);
+    rulers_ui(ui, &mapping);
+  }
+}
+
+fn safe_area_ui(ui: &egui::Ui, mapping: &ViewMapping, settings: &GuideSettings) {
+  let Some([left, right, top, bottom]) = settings.safe_area.margins() else {
+    return;
+  };
+
+  let half = settings.frame / 2.0;
+  let frame = egui::Rect::from_two_pos(mapping.to_screen(-half), mapping.to_screen(half));
+  let safe = egui::Rect::from_min_max(
+    frame.min + egui::vec2(frame.width() * left, frame.height() * top),
+    frame.max - egui::vec2(frame.width() * right, frame.height() * bottom),
+  );
+
+  let painter = ui.painter_at(mapping.viewport);
+  let color = ui.visuals().warn_fg_color;
+  painter.rect_stroke(
+    frame,
+    0.0,
+    egui::Stroke::new(1.0, color.gamma_multiply(0.5)),
+  );
+  painter.rect_stroke(safe, 0.0, egui::Stroke::new(1.0, color));
+  painter.text(
+    safe.left_top() + egui::vec2(4.0, 4.0),
+    egui::Align2::LEFT_TOP,
+    settings.safe_area.name(),
+    egui::TextStyle::Small.resolve(ui.style()),
+    color,
+  );
+}
+
+fn rulers_ui(ui: &egui::Ui, mapping: &ViewMapping) {
+  let viewport = mapping.viewport;
+  let painter = ui.painter_at(viewport);
+  let visuals = ui.visuals();
+  let font = egui::TextStyle::Small.resolve(ui.style());
+  let stroke = egui::Stroke::new(1.0, visuals.weak_text_color());
+
+  let top = egui::Rect::from_min_size(viewport.min, egui::vec2(viewport.width(), RULER_SIZE));
+  let left = egui::Rect::from_min_size(viewport.min, egui::vec2(RULER_SIZE, viewport.height()));
+  let background = visuals.extreme_bg_color.gamma_multiply(0.9);
+  painter.rect_filled(top, 0.0, background);
+  painter.rect_filled(left, 0.0, background);
+
+  let step = mapping.tick_step();
+  let minor = step / MINOR_TICKS as f32;
+  let min = mapping.to_world(viewport.left_bottom());
+  let max = mapping.to_world(viewport.right_top());
+
+  let ticks = |from: f32, to: f32| {
+    let first = (from / minor).floor() as i64;
+    let last = (to / minor).ceil() as i64;
+    (first..=last).map(move |i| (i as f32 * minor, i.rem_euclid(MINOR_TICKS as i64) == 0))
+  };
+
+  for (x, major) in ticks(min.x, max.x) {
+    let screen_x = mapping.to_screen(Vec2::new(x, 0.0)).x;
+    let length = if major { RULER_SIZE } else { RULER_SIZE / 3.0 };
+    painter.vline(screen_x, top.bottom() - length..=top.bottom(), stroke);
+    if major {
+      painter.text(
+        egui::pos2(screen_x + 2.0, top.top()),
+        egui::Align2::LEFT_TOP,
+        format_units(x),
+        font.clone(),
+        visuals.text_color(),
+      );
+    }
+  }
+
+  for (y, major) in ticks(min.y, max.y) {
+    let screen_y = mapping.to_screen(Vec2::new(0.0, y)).y;
+    let length = if major { RULER_SIZE } else { RULER_SIZE / 3.0 };
+    painter.hline(left.right() - length..=left.right(), screen_y, stroke);
+    if major {
+      painter.text(
+        egui::pos2(left.left() + 1.0, screen_y + 1.0),
+        egui::Align2::LEFT_TOP,
+        format_units(y),
+        font.clone(),
+        visuals.text_color(),
+      );
+    }
+  }
+}
+
+fn format_units(value: f32) -> String {
+  if value.fract().abs() < f32::EPSILON {
+    format!("{value:.0}")
+  } else {
+    format!("{value}")
+  }
+}
+
+/// Draws the guides and lets them be dragged out of the rulers, moved, and dragged back onto a
+/// ruler to remove them
+fn guides_ui(ui: &mut egui::Ui, mapping: &ViewMapping, settings: &mut GuideSettings) {
+  let viewport = mapping.viewport;
+  let top = egui::Rect::from_min_size(viewport.min, egui::vec2(viewport.width(), RULER_SIZE));
+  let left = egui::Rect::from_min_size(viewport.min, egui::vec2(RULER_SIZE, viewport.height()));
+  let id = ui.id().with("guides_2d");
+
+  // dragging out of the top ruler makes a horizontal guide, out of the left one a vertical guide
+  for (rect, vertical) in [(top, false), (left, true)] {
+    let response = ui.interact(rect, id.with(vertical), egui::Sense::drag());
+    if response.drag_started() {
+      settings.guides.push(Guide {
+        vertical,
+        position: 0.0,
+      });
+      ui.data_mut(|data| data.insert_temp(id, settings.guides.len() - 1));
+    }
+  }
+
+  let dragged = ui.data(|data| data.get_temp::<usize>(id));
+  let pointer = ui.input(|input| input.pointer.interact_pos());
+
+  if let (Some(index), Some(pointer)) = (dragged, pointer) {
+    let world = mapping.to_world(pointer);
+    if let Some(guide) = settings.guides.get_mut(index) {
+      guide.position = if guide.vertical { world.x } else { world.y };
+    }
+
+    if ui.input(|input| input.pointer.any_released()) {
+      ui.data_mut(|data| data.remove::<usize>(id));
+      if top.contains(pointer) || left.contains(pointer) {
+        settings.guides.remove(index);
+      }
+    }
+  }
+
+  let painter = ui.painter_at(viewport);
+  let color = ui.visuals().selection.bg_fill;
+
+  for (index, guide) in settings.guides.iter().enumerate() {
+    let (line, cursor) = if guide.vertical {
+      let x = mapping.to_screen(Vec2::new(guide.position, 0.0)).x;
+      painter.vline(x, viewport.y_range(), egui::Stroke::new(1.0, color));
+      (
+        egui::Rect::from_x_y_ranges(
+          x - GUIDE_GRAB_DISTANCE..=x + GUIDE_GRAB_DISTANCE,
+          viewport.y_range(),
+        ),
+        egui::CursorIcon::ResizeHorizontal,
+      )
+    } else {
+      let y = mapping.to_screen(Vec2::new(0.0, guide.position)).y;
+      painter.hline(viewport.x_range(), y, egui::Stroke::new(1.0, color));
+      (
+        egui::Rect::from_x_y_ranges(
+          viewport.x_range(),
+          y - GUIDE_GRAB_DISTANCE..=y + GUIDE_GRAB_DISTANCE,
+        ),
+        egui::CursorIcon::ResizeVertical,
+      )
+    };
+
+    let response = ui
+      .interact(line, id.with(("guide", index)), egui::Sense::drag())
+      .on_hover_cursor(cursor)
+      .on_hover_text(format_units(guide.position));
+    if response.drag_started() {
+      ui.data_mut(|data| data.insert_temp(id, index));
+    }
+  }
+}
+
+/// Guide and safe area options shown in the view menu
+pub(crate) fn menu_ui(ui: &mut egui::Ui, settings: &mut GuideSettings) {
+  ui.checkbox(&mut settings.rulers, "Rulers & Guides")
+    .on_hover_text("Drag from a ruler to add a guide, drag a guide back onto a ruler to remove it");
+
+  ui.add_enabled_ui(!settings.guides.is_empty(), |ui| {
+    if ui.button("Clear Guides").clicked() {
+      settings.guides.clear();
+    }
+  });
+
+  ui.menu_button("Safe Area", |ui| {
+    for safe_area in SafeArea::ALL {
+      ui.radio_value(&mut settings.safe_area, safe_area, safe_area.name());
+    }
+
+    ui.separator();
+    ui.horizontal(|ui| {
+      ui.label("Frame");
+      ui.add(egui::DragValue::new(&mut settings.frame.x).range(1.0..=f32::MAX));
+      ui.add(egui::DragValue::new(&mut settings.frame.y).range(1.0..=f32::MAX));
+    });
+  });
+}
@@ -0,0 +1,442 @@
+use super::{
+  outline::{SelectionOutline, OUTLINE_LAYER},
+  ActiveEditorCamera, EditorCamera, View3d,
+};
+use crate::{cache::Saveable, settings::EditorSettings, EditorState};
+use bevy::{
+  asset::load_internal_asset,
+  pbr::{
+    wireframe::{Wireframe, WireframeColor, WireframePlugin},
+    NotShadowCaster,
+  },
+  prelude::*,
+  render::{
+    render_resource::{AsBindGroup, ShaderRef, WgpuFeatures},
+    renderer::RenderDevice,
+    view::{RenderLayers, VisibilitySystems},
+  },
+  transform::TransformSystem,
+  utils::HashMap,
+};
+use bevy_egui::egui;
+use serde::{Deserialize, Serialize};
+
+/// Render layer the editor cameras switch to while a shading mode other than lit is used
+///
+/// Stand-ins of every visible mesh are drawn on it with the material of the mode
+pub const SHADING_LAYER: usize = 65;
+
+const NORMALS_SHADER: Handle<Shader> = Handle::weak_from_u128(0x2b7e4f1c9d3a4e8b8c6f0d5a1e2b3c4d);
+
+const MARGIN: f32 = 8.0;
+
+/// How the 3d editor view draws the scene
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Reflect, Serialize, Deserialize)]
+pub enum ShadingMode {
+  #[default]
+  Lit,
+  /// Base colors and textures without any lighting
+  Unlit,
+  /// Triangle edges over flat surfaces, needs a gpu that can draw lines
+  Wireframe,
+  /// World space normals as colors
+  Normals,
+  /// Brighter where more surfaces are drawn on top of each other
+  Overdraw,
+}
+
+impl ShadingMode {
+  const ALL: [Self; 5] = [
+    Self::Lit,
+    Self::Unlit,
+    Self::Wireframe,
+    Self::Normals,
+    Self::Overdraw,
+  ];
+
+  fn name(self) -> &'static str {
+    match self {
+      Self::Lit => "Lit",
+      Self::Unlit => "Unlit",
+      Self::Wireframe => "Wireframe",
+      Self::Normals => "Normals",
+      Self::Overdraw => "Overdraw",
+    }
+  }
+}
+
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone, Default)]
+#[reflect(Resource)]
+pub struct ShadingSettings {
+  pub mode: ShadingMode,
+}
+
+impl Saveable for ShadingSettings {
+  const KEY: &str = "viewport_shading";
+}
+
+impl EditorSettings for ShadingSettings {
+  const NAME: &str = "Viewport Shading";
+}
+
+/// Colors meshes by their world space normal
+#[derive(Asset, TypePath, AsBindGroup, Clone, Default)]
+pub struct NormalsMaterial {}
+
+impl Material for NormalsMaterial {
+  fn fragment_shader() -> ShaderRef {
+    NORMALS_SHADER.into()
+  }
+}
+
+/// Marker for the stand-ins drawn in place of meshes while shading
+#[derive(Component)]
+pub struct ShadingProxy;
+
+/// Mesh to the stand-in drawn for it
+#[derive(Resource, Default, Deref, DerefMut)]
+struct ShadingProxies(HashMap<Entity, Entity>);
+
+/// Layers the editor cameras had before they were switched over to [`SHADING_LAYER`]
+///
+/// Muting layers still works while shading, only meshes on these layers get a stand-in
+#[derive(Resource, Default)]
+struct ShadedLayers(Option<RenderLayers>);
+
+/// Materials shared between the stand-ins of the current mode
+#[derive(Resource, Default)]
+struct ShadingMaterials {
+  /// Unlit copies of the scene's materials
+  unlit: HashMap<AssetId<StandardMaterial>, Handle<StandardMaterial>>,
+  flat: Option<Handle<StandardMaterial>>,
+  overdraw: Option<Handle<StandardMaterial>>,
+  normals: Option<Handle<NormalsMaterial>>,
+}
+
+impl ShadingMaterials {
+  /// Unlit copy of the mesh's material, meshes with other kinds of materials are drawn flat
+  fn unlit(
+    &mut self,
+    material: Option<&MeshMaterial3d<StandardMaterial>>,
+    assets: &mut Assets<StandardMaterial>,
+  ) -> Handle<StandardMaterial> {
+    let Some(material) = material else {
+      return self.flat(assets);
+    };
+
+    if let Some(unlit) = self.unlit.get(&material.id()) {
+      return unlit.clone();
+    }
+
+    // not loaded yet, tried again once the mode changes
+    let Some(original) = assets.get(material.id()) else {
+      return self.flat(assets);
+    };
+
+    let unlit = assets.add(StandardMaterial {
+      unlit: true,
+      ..original.clone()
+    });
+    self.unlit.insert(material.id(), unlit.clone());
+    unlit
+  }
+
+  fn flat(&mut self, assets: &mut Assets<StandardMaterial>) -> Handle<StandardMaterial> {
+    self
+      .flat
+      .get_or_insert_with(|| {
+        assets.add(StandardMaterial {
+          base_color: Color::srgb(0.35, 0.35, 0.35),
+          unlit: true,
+          ..default()
+        })
+      })
+      .clone()
+  }
+
+  /// Faint additive color, every surface drawn on top of another adds to it
+  fn overdraw(&mut self, assets: &mut Assets<StandardMaterial>) -> Handle<StandardMaterial> {
+    self
+      .overdraw
+      .get_or_insert_with(|| {
+        assets.add(StandardMaterial {
+          base_color: Color::srgb(0.12, 0.05, 0.02),
+          alpha_mode: AlphaMode::Add,
+          unlit: true,
+          cull_mode: None,
+          ..default()
+        })
+      })
+      .clone()
+  }
+}
+
+pub(crate) struct ShadingPlugin;
+
+impl Plugin for ShadingPlugin {
+  fn build(&self, app: &mut App) {
+    load_internal_asset!(app, NORMALS_SHADER, "normals.wgsl", Shader::from_wgsl);
+
+    if !app.is_plugin_added::<WireframePlugin>() {
+      app.add_plugins(WireframePlugin);
+    }
+
+    app
+      .add_plugins(MaterialPlugin::<NormalsMaterial> {
+        prepass_enabled: false,
+        shadows_enabled: false,
+        ..default()
+      })
+      .init_resource::<ShadingProxies>()
+      .init_resource::<ShadedLayers>()
+      .init_resource::<ShadingMaterials>()
+      .add_systems(
+        Update,
+        (reset_proxies, switch_camera_layers, update_proxies)
+          .chain()
+          .in_set(View3d),
+      )
+      .add_systems(
+        PostUpdate,
+        sync_proxy_transforms
+          .after(TransformSystem::TransformPropagate)
+          .before(VisibilitySystems::CheckVisibility)
+          .run_if(in_state(EditorState::Editing)),
+      )
+      .add_systems(OnExit(EditorState::Editing), clear_shading)
+      .add_systems(OnExit(ActiveEditorCamera::Cam3D), clear_shading);
+  }
+}
+
+fn supports_wireframe(device: Option<&RenderDevice>) -> bool {
+  device.is_some_and(|device| device.features().contains(WgpuFeatures::POLYGON_MODE_LINE))
+}
+
+/// Every stand-in is rebuilt when the mode changes
+fn reset_proxies(
+  mut commands: Commands,
+  settings: Res<ShadingSettings>,
+  mut proxies: ResMut<ShadingProxies>,
+  mut materials: ResMut<ShadingMaterials>,
+) {
+  if !settings.is_changed() {
+    return;
+  }
+
+  for (_, proxy) in proxies.drain() {
+    if let Some(mut proxy) = commands.get_entity(proxy) {
+      proxy.despawn();
+    }
+  }
+
+  *materials = default();
+}
+
+fn switch_camera_layers(
+  mut commands: Commands,
+  settings: Res<ShadingSettings>,
+  mut shaded: ResMut<ShadedLayers>,
+  mut config_store: ResMut<GizmoConfigStore>,
+  q_cams: Query<(Entity, Option<&RenderLayers>), With<EditorCamera>>,
+) {
+  if settings.mode == ShadingMode::Lit {
+    if let Some(layers) = shaded.0.take() {
+      for (entity, _) in &q_cams {
+        commands.entity(entity).insert(layers.clone());
+      }
+    }
+    return;
+  }
+
+  let shading_layers = RenderLayers::from_layers(&[SHADING_LAYER, OUTLINE_LAYER]);
+
+  for (entity, layers) in &q_cams {
+    let layers = layers.cloned().unwrap_or_default();
+    if layers == shading_layers {
+      continue;
+    }
+
+    // the layers were changed from elsewhere, i.e. the render layers panel, so take them over
+    commands.entity(entity).insert(shading_layers.clone());
+    shaded.0 = Some(layers.without(SHADING_LAYER));
+  }
+
+  // gizmos drawn for the user's layers should stay visible
+  if let Some(layers) = &shaded.0 {
+    for (_, config, _) in config_store.iter_mut() {
+      if config.render_layers.intersects(layers)
+        && !config
+          .render_layers
+          .intersects(&RenderLayers::layer(SHADING_LAYER))
+      {
+        config.render_layers = config.render_layers.clone().with(SHADING_LAYER);
+      }
+    }
+  }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn update_proxies(
+  mut commands: Commands,
+  settings: Res<ShadingSettings>,
+  shaded: Res<ShadedLayers>,
+  mut proxies: ResMut<ShadingProxies>,
+  mut materials: ResMut<ShadingMaterials>,
+  mut standard_materials: ResMut<Assets<StandardMaterial>>,
+  mut normals_materials: ResMut<Assets<NormalsMaterial>>,
+  q_meshes: Query<
+    (
+      Entity,
+      &Mesh3d,
+      &InheritedVisibility,
+      Option<&RenderLayers>,
+      Option<&MeshMaterial3d<StandardMaterial>>,
+    ),
+    (Without<SelectionOutline>, Without<ShadingProxy>),
+  >,
+  q_proxies: Query<&Mesh3d, With<ShadingProxy>>,
+) {
+  let Some(layers) = shaded
+    .0
+    .as_ref()
+    .filter(|_| settings.mode != ShadingMode::Lit)
+  else {
+    return;
+  };
+
+  let shown = |visibility: &InheritedVisibility, mesh_layers: Option<&RenderLayers>| {
+    visibility.get() && mesh_layers.cloned().unwrap_or_default().intersects(layers)
+  };
+
+  // drop stand-ins of meshes that were hidden, despawned, or had their mesh swapped out
+  proxies.retain(|source, proxy| {
+    let current = q_meshes
+      .get(*source)
+      .ok()
+      .filter(|(_, _, visibility, mesh_layers, _)| shown(visibility, *mesh_layers))
+      .zip(q_proxies.get(*proxy).ok())
+      .is_some_and(|((_, source_mesh, ..), proxy_mesh)| source_mesh.0 == proxy_mesh.0);
+
+    if !current {
+      if let Some(mut proxy) = commands.get_entity(*proxy) {
+        proxy.despawn();
+      }
+    }
+
+    current
+  });
+
+  for (source, mesh, visibility, mesh_layers, material) in &q_meshes {
+    if proxies.contains_key(&source) || !shown(visibility, mesh_layers) {
+      continue;
+    }
+
+    let mut proxy = commands.spawn((
+      Name::new("Shading Proxy"),
+      ShadingProxy,
+      mesh.clone(),
+      NotShadowCaster,
+      RenderLayers::layer(SHADING_LAYER),
+    ));
+
+    match settings.mode {
+      ShadingMode::Lit => (),
+      ShadingMode::Unlit => {
+        proxy.insert(MeshMaterial3d(
+          materials.unlit(material, &mut standard_materials),
+        ));
+      }
+      ShadingMode::Wireframe => {
+        proxy.insert((
+          MeshMaterial3d(materials.flat(&mut standard_materials)),
+          Wireframe,
+          WireframeColor {
+            color: Color::WHITE,
+          },
+        ));
+      }
+      ShadingMode::Normals => {
+        let normals = materials
+          .normals
+          .get_or_insert_with(|| normals_materials.add(NormalsMaterial {}));
+        proxy.insert(MeshMaterial3d(normals.clone()));
+      }
+      ShadingMode::Overdraw => {
+        proxy.insert(MeshMaterial3d(materials.overdraw(&mut standard_materials)));
+      }
+    }
+
+    proxies.insert(source, proxy.id());
+  }
+}
+
+fn sync_proxy_transforms(
+  proxies: Res<ShadingProxies>,
+  q_sources: Query<&GlobalTransform, Without<ShadingProxy>>,
+  mut q_proxies: Query<&mut GlobalTransform, With<ShadingProxy>>,
+) {
+  for (source, proxy) in proxies.iter() {
+    if let Some((source, mut proxy)) = q_sources
+      .get(*source)
+      .ok()
+      .zip(q_proxies.get_mut(*proxy).ok())
+    {
+      *proxy = *source;
+    }
+  }
+}
+
+fn clear_shading(
+  mut commands: Commands,
+  mut proxies: ResMut<ShadingProxies>,
+  mut shaded: ResMut<ShadedLayers>,
+  q_cams: Query<Entity, With<EditorCamera>>,
+) {
+  for (_, proxy) in proxies.drain() {
+    if let Some(mut proxy) = commands.get_entity(proxy) {
+      proxy.despawn();
+    }
+  }
+
+  if let Some(layers) = shaded.0.take() {
+    for entity in &q_cams {
+      commands.entity(entity).insert(layers.clone());
+    }
+  }
+}
+
+/// Dropdown in the bottom left corner of the editor view
+pub(crate) fn ui(
+  ui: &mut egui::Ui,
+  viewport: egui::Rect,
+  settings: &mut ResMut<ShadingSettings>,
+  device: Option<&RenderDevice>,
+) {
+  let rect = viewport.shrink(MARGIN);
+  let mut ui = ui.new_child(
+    egui::UiBuilder::new()
+      .max_rect(rect)
+      .layout(egui::Layout::bottom_up(egui::Align::Min)),
+  );
+
+  let wireframe = supports_wireframe(device);
+  let mut mode = settings.mode;
+
+  egui::ComboBox::from_id_salt("viewport_shading")
+    .selected_text(mode.name())
+    .show_ui(&mut ui, |ui| {
+      for option in ShadingMode::ALL {
+        let enabled = option != ShadingMode::Wireframe || wireframe;
+        ui.add_enabled_ui(enabled, |ui| {
+          ui.selectable_value(&mut mode, option, option.name());
+        })
+        .response
+        .on_disabled_hover_text("The gpu can't draw lines");
+      }
+    });
+
+  // only touched on a change, the stand-ins are rebuilt whenever the settings change
+  if mode != settings.mode {
+    settings.mode = mode;
+  }
+}
@@ -0,0 +1,136 @@
+use super::placement::PlacementSettings;
+use crate::{cache::Saveable, input::EditorActions, settings::EditorSettings};
+use bevy::{
+  diagnostic::{
+    DiagnosticPath, DiagnosticsStore, EntityCountDiagnosticsPlugin, FrameTimeDiagnosticsPlugin,
+  },
+  prelude::*,
+};
+use bevy_egui::egui;
+use leafwing_input_manager::prelude::ActionState;
+use serde::{Deserialize, Serialize};
+
+const MARGIN: f32 = 8.0;
+
+/// Which lines the statistics overlay in the corner of the editor view shows
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone)]
+#[reflect(Resource)]
+pub struct StatsHudSettings {
+  pub enabled: bool,
+  pub fps: bool,
+  pub frame_time: bool,
+  pub entity_count: bool,
+  pub camera: bool,
+  pub snapping: bool,
+}
+
+impl Default for StatsHudSettings {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      fps: true,
+      frame_time: true,
+      entity_count: true,
+      camera: true,
+      snapping: true,
+    }
+  }
+}
+
+impl Saveable for StatsHudSettings {
+  const KEY: &str = "stats_hud";
+}
+
+impl EditorSettings for StatsHudSettings {
+  const NAME: &str = "Stats Overlay";
+}
+
+pub(super) fn toggle(
+  q_action_states: Query<&ActionState<EditorActions>>,
+  mut settings: ResMut<StatsHudSettings>,
+) {
+  for action_state in &q_action_states {
+    if action_state.just_pressed(&EditorActions::ToggleStats) {
+      settings.enabled = !settings.enabled;
+    }
+  }
+}
+
+/// Draws the overlay in the top left corner of the viewport
+pub fn ui(
+  ui: &mut egui::Ui,
+  viewport: egui::Rect,
+  settings: &StatsHudSettings,
+  diagnostics: &DiagnosticsStore,
+  placement: &PlacementSettings,
+  camera: Option<&GlobalTransform>,
+) {
+  if !settings.enabled {
+    return;
+  }
+
+  let smoothed = |path: &DiagnosticPath| {
+    diagnostics
+      .get(path)
+      .and_then(|diagnostic| diagnostic.smoothed())
+  };
+
+  let mut lines = Vec::new();
+
+  if settings.fps {
+    if let Some(fps) = smoothed(&FrameTimeDiagnosticsPlugin::FPS) {
+      lines.push(format!("FPS: {fps:.0}"));
+    }
+  }
+
+  if settings.frame_time {
+    if let Some(frame_time) = smoothed(&FrameTimeDiagnosticsPlugin::FRAME_TIME) {
+      lines.push(format!("Frame: {frame_time:.2} ms"));
+    }
+  }
+
+  if settings.entity_count {
+    if let Some(count) = smoothed(&EntityCountDiagnosticsPlugin::ENTITY_COUNT) {
+      lines.push(format!("Entities: {count:.0}"));
+    }
+  }
+
+  if let Some(transform) = camera.filter(|_| settings.camera) {
+    let (_, rotation, translation) = transform.to_scale_rotation_translation();
+    let (yaw, pitch, roll) = rotation.to_euler(EulerRot::YXZ);
+    lines.push(format!(
+      "Camera: {:.2}, {:.2}, {:.2}",
+      translation.x, translation.y, translation.z
+    ));
+    lines.push(format!(
+      "Rotation: {:.1}°, {:.1}°, {:.1}°",
+      yaw.to_degrees(),
+      pitch.to_degrees(),
+      roll.to_degrees()
+    ));
+  }
+
+  if settings.snapping {
+    let snap = if placement.snap_to_ground {
+      "on"
+    } else {
+      "off"
+    };
+    lines.push(format!("Snap to ground: {snap}"));
+  }
+
+  if lines.is_empty() {
+    return;
+  }
+
+  let rect = viewport.shrink(MARGIN);
+  let mut ui = ui.new_child(egui::UiBuilder::new().max_rect(rect));
+
+  egui::Frame::popup(ui.style())
+    .multiply_with_opacity(0.8)
+    .show(&mut ui, |ui| {
+      for line in lines {
+        ui.monospace(line);
+      }
+    });
+}
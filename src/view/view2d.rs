@@ -1,7 +1,9 @@
-use super::{EditorCamera, PanState, UP};
+use super::{EditorCamera, FocusedPane, PanState, UP};
 use crate::{
   cache::{Cache, Saveable},
   input::EditorActions,
+  settings::EditorSettings,
+  ui::InspectorSelection,
   util,
 };
 use bevy::{
@@ -46,6 +48,7 @@ pub fn enable(
   commands.spawn((
     Name::new("Editor Camera 2D"),
     EditorCamera2d,
+    FocusedPane,
     CameraState::default(),
     settings,
     transform,
@@ -213,8 +216,147 @@ pub fn pan_system(
   }
 }
 
+/// Moving selected sprites by dragging them in the 2d view
+///
+/// Holding shift keeps them on the axis they moved furthest along, holding alt flips snapping
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone)]
+#[reflect(Resource)]
+pub struct SpriteDragSettings {
+  pub enabled: bool,
+  pub snap: bool,
+  /// Size of the steps sprites move in while snapping, in world units
+  pub snap_step: f32,
+}
+
+impl Default for SpriteDragSettings {
+  fn default() -> Self {
+    Self {
+      enabled: true,
+      snap: false,
+      snap_step: 16.0,
+    }
+  }
+}
+
+impl Saveable for SpriteDragSettings {
+  const KEY: &str = "sprite_drag";
+}
+
+impl EditorSettings for SpriteDragSettings {
+  const NAME: &str = "2D Drag To Move";
+}
+
+/// Pointer position the drag started at and where the dragged entities were
+pub(super) struct SpriteDrag {
+  start: Vec2,
+  entities: Vec<(Entity, Vec3)>,
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub(super) fn drag_sprites(
+  mut drag: Local<Option<SpriteDrag>>,
+  settings: Res<SpriteDragSettings>,
+  selection: Res<InspectorSelection>,
+  keys: Res<ButtonInput<KeyCode>>,
+  mut drag_starts: EventReader<Pointer<DragStart>>,
+  mut drags: EventReader<Pointer<Drag>>,
+  mut drag_ends: EventReader<Pointer<DragEnd>>,
+  camera: Single<(&Camera, &GlobalTransform), With<EditorCamera2d>>,
+  q_draggable: Query<(), Or<(With<Sprite>, With<Mesh2d>)>>,
+  mut q_transforms: Query<(&mut Transform, Option<&Parent>)>,
+  q_parents: Query<&GlobalTransform>,
+) {
+  let (camera, camera_transform) = *camera;
+  let to_world = |position: Vec2| {
+    let viewport = camera.logical_viewport_rect().unwrap_or_default();
+    camera
+      .viewport_to_world_2d(camera_transform, position - viewport.min)
+      .ok()
+  };
+
+  let InspectorSelection::Entities(selected) = selection.as_ref() else {
+    return;
+  };
+
+  for event in drag_starts.read() {
+    if event.button != PointerButton::Primary
+      || !settings.enabled
+      || !selected.contains(event.target)
+      || !q_draggable.contains(event.target)
+    {
+      continue;
+    }
+
+    let Some(start) = to_world(event.pointer_location.position) else {
+      continue;
+    };
+
+    let entities = selected
+      .iter()
+      .filter(|entity| q_draggable.contains(*entity))
+      .filter_map(|entity| {
+        let (transform, _) = q_transforms.get(entity).ok()?;
+        Some((entity, transform.translation))
+      })
+      .collect();
+
+    *drag = Some(SpriteDrag { start, entities });
+  }
+
+  if let Some(SpriteDrag { start, entities }) = drag.as_ref() {
+    let latest = drags
+      .read()
+      .filter(|event| event.button == PointerButton::Primary)
+      .last();
+
+    if let Some(position) = latest.and_then(|event| to_world(event.pointer_location.position)) {
+      let mut delta = position - *start;
+
+      if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        if delta.x.abs() > delta.y.abs() {
+          delta.y = 0.0;
+        } else {
+          delta.x = 0.0;
+        }
+      }
+
+      let snap = settings.snap != keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]);
+      if snap && settings.snap_step > 0.0 {
+        delta = (delta / settings.snap_step).round() * settings.snap_step;
+      }
+
+      for (entity, translation) in entities {
+        let Ok((mut transform, parent)) = q_transforms.get_mut(*entity) else {
+          continue;
+        };
+
+        // the delta is in world space, children move in the space of their parent
+        let delta = parent
+          .and_then(|parent| q_parents.get(parent.get()).ok())
+          .map_or(delta.extend(0.0), |parent| {
+            parent
+              .affine()
+              .inverse()
+              .transform_vector3(delta.extend(0.0))
+          });
+
+        transform.translation = *translation + delta;
+      }
+    }
+  } else {
+    drags.clear();
+  }
+
+  if drag_ends
+    .read()
+    .any(|event| event.button == PointerButton::Primary)
+  {
+    *drag = None;
+  }
+}
+
 #[derive(Default, Serialize, Deserialize)]
-struct CameraSaveData {
+pub(crate) struct CameraSaveData {
   settings: CameraSettings,
   transform: Transform,
   orthographic_scale: Option<f32>,
@@ -1,10 +1,13 @@
-use super::{EditorCamera, OrbitState, PanState, UP};
+use super::{EditorCamera, EditorPane, FocusedPane, GizmoColorSettings, OrbitState, PanState, UP};
 use crate::{
   cache::{Cache, Saveable},
   input::EditorActions,
+  settings::EditorSettings,
+  ui::InspectorSelection,
   util,
 };
-use bevy::{input::mouse::MouseMotion, prelude::*};
+use bevy::{input::mouse::MouseMotion, prelude::*, render::camera::ScalingMode};
+use bevy_egui::egui;
 use leafwing_input_manager::prelude::ActionState;
 use serde::{Deserialize, Serialize};
 
@@ -15,6 +18,9 @@ pub struct View3d;
 #[require(EditorCamera, Camera3d, CameraSettings)]
 pub struct EditorCamera3d;
 
+/// The 3d camera of the pane navigation goes to
+type FocusedCamera3d = (With<EditorCamera3d>, With<FocusedPane>);
+
 pub fn enable(
   mut commands: Commands,
   mut q_prev_cams: Query<Entity, With<EditorCamera>>,
@@ -34,6 +40,7 @@ pub fn enable(
   commands.spawn((
     Name::new("Editor Camera 3D"),
     EditorCamera3d,
+    FocusedPane,
     settings,
     transform,
     Camera {
@@ -45,9 +52,10 @@ pub fn enable(
 
 pub fn save_settings(
   mut cache: ResMut<Cache>,
-  q_cam: Query<(&Transform, &CameraSettings), With<EditorCamera3d>>,
+  q_cam: Query<(&Transform, &CameraSettings, &EditorPane), With<EditorCamera3d>>,
 ) {
-  for (cam_transform, cam_settings) in &q_cam {
+  // the other panes always start from their fixed views
+  for (cam_transform, cam_settings, _) in q_cam.iter().filter(|(_, _, pane)| pane.0 == 0) {
     cache.store(&CameraSaveData {
       settings: cam_settings.clone(),
       transform: *cam_transform,
@@ -115,7 +123,7 @@ pub(super) fn released_mouse_input_actions(
 
 pub fn movement_system(
   q_action_states: Query<&ActionState<EditorActions>>,
-  mut q_cam: Single<(&CameraSettings, &mut Transform), With<EditorCamera3d>>,
+  mut q_cam: Single<(&CameraSettings, &mut Transform), FocusedCamera3d>,
   time: Res<Time>,
 ) {
   for action_state in &q_action_states {
@@ -149,10 +157,117 @@ pub fn movement_system(
   }
 }
 
+const PIVOT_GIZMO_RADIUS: f32 = 0.1;
+
+/// What the 3d editor camera orbits around
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Reflect, Serialize, Deserialize)]
+pub enum OrbitPivot {
+  /// Turns the camera in place
+  #[default]
+  Camera,
+  /// The center of the selected entities, turns in place when nothing is selected
+  Selection,
+  /// The center of the last selection, kept after the selection is cleared
+  LastFocused,
+}
+
+impl OrbitPivot {
+  const ALL: [Self; 3] = [Self::Camera, Self::Selection, Self::LastFocused];
+
+  fn name(self) -> &'static str {
+    match self {
+      Self::Camera => "Camera",
+      Self::Selection => "Selection",
+      Self::LastFocused => "Last Focused Point",
+    }
+  }
+}
+
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone, Default)]
+#[reflect(Resource)]
+pub struct OrbitSettings {
+  pub pivot: OrbitPivot,
+}
+
+impl Saveable for OrbitSettings {
+  const KEY: &str = "orbit";
+}
+
+impl EditorSettings for OrbitSettings {
+  const NAME: &str = "Orbit";
+}
+
+impl OrbitSettings {
+  pub(crate) fn menu_ui(ui: &mut egui::Ui, settings: &mut Self) {
+    ui.menu_button("Orbit Around", |ui| {
+      for pivot in OrbitPivot::ALL {
+        ui.radio_value(&mut settings.pivot, pivot, pivot.name());
+      }
+    });
+  }
+}
+
+/// Points the camera can orbit around, follows the selection
+#[derive(Resource, Default)]
+pub struct OrbitFocus {
+  selection: Option<Vec3>,
+  last: Option<Vec3>,
+}
+
+impl OrbitFocus {
+  pub fn pivot(&self, pivot: OrbitPivot) -> Option<Vec3> {
+    match pivot {
+      OrbitPivot::Camera => None,
+      OrbitPivot::Selection => self.selection,
+      OrbitPivot::LastFocused => self.last,
+    }
+  }
+
+  pub(super) fn track_selection(
+    selection: Res<InspectorSelection>,
+    q_transforms: Query<&GlobalTransform>,
+    mut focus: ResMut<Self>,
+  ) {
+    let positions = match selection.as_ref() {
+      InspectorSelection::Entities(selected) => selected
+        .iter()
+        .filter_map(|entity| q_transforms.get(entity).ok())
+        .map(GlobalTransform::translation)
+        .collect::<Vec<_>>(),
+      _ => Vec::new(),
+    };
+
+    focus.selection =
+      (!positions.is_empty()).then(|| positions.iter().sum::<Vec3>() / positions.len() as f32);
+
+    if focus.selection.is_some() {
+      focus.last = focus.selection;
+    }
+  }
+
+  /// Marks the point being orbited around
+  pub(super) fn draw_pivot(
+    settings: Res<OrbitSettings>,
+    focus: Res<Self>,
+    colors: Res<GizmoColorSettings>,
+    mut gizmos: Gizmos,
+  ) {
+    if let Some(pivot) = focus.pivot(settings.pivot) {
+      gizmos.sphere(
+        Isometry3d::from_translation(pivot),
+        PIVOT_GIZMO_RADIUS,
+        colors.selection,
+      );
+    }
+  }
+}
+
 pub fn orbit_system(
   q_action_states: Query<&ActionState<EditorActions>>,
-  mut q_cam: Single<(&CameraSettings, &mut Transform), With<EditorCamera3d>>,
+  mut q_cam: Single<(&CameraSettings, &mut Transform), FocusedCamera3d>,
   mut mouse_motion: EventReader<MouseMotion>,
+  orbit_settings: Res<OrbitSettings>,
+  focus: Res<OrbitFocus>,
   time: Res<Time>,
 ) {
   let should_orbit = q_action_states
@@ -172,14 +287,18 @@ pub fn orbit_system(
     .map(|mouse| mouse * settings.orbit_sensitivity * time.delta_secs())
     .unwrap_or_default();
 
+  let pivot = focus
+    .pivot(orbit_settings.pivot)
+    .unwrap_or(transform.translation);
+
   let right = transform.right();
-  transform.rotate_axis(right, -orbit.y);
-  transform.rotate_axis(Dir3::new(UP).unwrap(), -orbit.x);
+  transform.rotate_around(pivot, Quat::from_axis_angle(*right, -orbit.y));
+  transform.rotate_around(pivot, Quat::from_axis_angle(UP, -orbit.x));
 }
 
 pub fn pan_system(
   q_action_states: Query<&ActionState<EditorActions>>,
-  mut q_cam: Single<(&CameraSettings, &mut Transform), With<EditorCamera3d>>,
+  mut q_cam: Single<(&CameraSettings, &mut Transform), FocusedCamera3d>,
   mut mouse_motion: EventReader<MouseMotion>,
   time: Res<Time>,
 ) {
@@ -209,7 +328,7 @@ pub fn pan_system(
 
 pub fn zoom_system(
   q_action_states: Query<&ActionState<EditorActions>>,
-  mut q_cam: Query<(&CameraSettings, &mut Projection), With<EditorCamera3d>>,
+  mut q_cam: Query<(&CameraSettings, &mut Projection), FocusedCamera3d>,
   time: Res<Time>,
 ) {
   let Ok((cam_settings, mut projection)) = q_cam.get_single_mut() else {
@@ -234,7 +353,7 @@ pub fn zoom_system(
 }
 
 #[derive(Default, Serialize, Deserialize)]
-struct CameraSaveData {
+pub(crate) struct CameraSaveData {
   settings: CameraSettings,
   transform: Transform,
 }
@@ -261,3 +380,60 @@ impl Default for CameraSettings {
     }
   }
 }
+
+/// Views looking down an axis that the extra panes of a split editor view start from
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FixedView {
+  Top,
+  Front,
+  Side,
+}
+
+impl FixedView {
+  /// How far from the origin the camera is placed, and how much of the world fits vertically
+  const DISTANCE: f32 = 50.0;
+  const HEIGHT: f32 = 20.0;
+
+  pub fn name(self) -> &'static str {
+    match self {
+      Self::Top => "Top",
+      Self::Front => "Front",
+      Self::Side => "Side",
+    }
+  }
+
+  fn transform(self) -> Transform {
+    match self {
+      Self::Top => {
+        Transform::from_translation(UP * Self::DISTANCE).looking_at(Vec3::ZERO, -Vec3::Z)
+      }
+      Self::Front => {
+        Transform::from_translation(Vec3::Z * Self::DISTANCE).looking_at(Vec3::ZERO, UP)
+      }
+      Self::Side => {
+        Transform::from_translation(Vec3::X * Self::DISTANCE).looking_at(Vec3::ZERO, UP)
+      }
+    }
+  }
+}
+
+pub fn spawn_fixed_view(commands: &mut Commands, pane: usize, view: FixedView) {
+  commands.spawn((
+    Name::new(format!("Editor Camera 3D ({})", view.name())),
+    EditorCamera3d,
+    EditorPane(pane),
+    view.transform(),
+    Projection::Orthographic(OrthographicProjection {
+      scaling_mode: ScalingMode::FixedVertical {
+        viewport_height: FixedView::HEIGHT,
+      },
+      ..OrthographicProjection::default_3d()
+    }),
+    Camera {
+      order: isize::MIN + pane as isize,
+      // the main pane already cleared the window
+      clear_color: ClearColorConfig::None,
+      ..default()
+    },
+  ));
+}
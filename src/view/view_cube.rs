@@ -0,0 +1,138 @@
+use super::UP;
+use bevy::prelude::*;
+use bevy_egui::egui;
+
+const SIZE: f32 = 90.0;
+const MARGIN: f32 = 8.0;
+const BUBBLE_RADIUS: f32 = 9.0;
+const DRAG_SENSITIVITY: f32 = 0.01;
+const MIN_PIVOT_DISTANCE: f32 = 1.0;
+
+const AXES: [(Vec3, &str, egui::Color32); 3] = [
+  (Vec3::X, "X", egui::Color32::from_rgb(230, 75, 60)),
+  (Vec3::Y, "Y", egui::Color32::from_rgb(110, 200, 70)),
+  (Vec3::Z, "Z", egui::Color32::from_rgb(70, 130, 230)),
+];
+
+/// Orientation widget anchored to the top right corner of the 3d editor view
+///
+/// Clicking an axis snaps the camera to look down it and dragging orbits the camera, both around
+/// the point in front of the camera as far away as the world origin. Returns the new camera
+/// transform if it was changed
+pub fn ui(ui: &mut egui::Ui, viewport: egui::Rect, transform: &Transform) -> Option<Transform> {
+  let rect = egui::Rect::from_min_size(
+    egui::pos2(viewport.right() - SIZE - MARGIN, viewport.top() + MARGIN),
+    egui::Vec2::splat(SIZE),
+  );
+
+  let response = ui.interact(
+    rect,
+    ui.id().with("view-cube"),
+    egui::Sense::click_and_drag(),
+  );
+
+  let center = rect.center();
+  let radius = SIZE / 2.0 - BUBBLE_RADIUS;
+  let view_from_world = transform.rotation.inverse();
+
+  // every axis in both directions with its position on the widget and its depth towards the viewer
+  let mut bubbles = AXES
+    .iter()
+    .flat_map(|(axis, label, color)| [(*axis, Some(*label), *color), (-*axis, None, *color)])
+    .map(|(axis, label, color)| {
+      let view = view_from_world * axis;
+      let pos = center + egui::vec2(view.x, -view.y) * radius;
+      (axis, label, color, pos, view.z)
+    })
+    .collect::<Vec<_>>();
+
+  bubbles.sort_by(|a, b| a.4.total_cmp(&b.4));
+
+  let painter = ui.painter_at(rect);
+  let hovered = response.hover_pos();
+
+  if response.hovered() || response.dragged() {
+    painter.circle_filled(center, SIZE / 2.0, ui.visuals().faint_bg_color);
+  }
+
+  for (_, label, color, pos, _) in &bubbles {
+    let is_hovered = hovered.is_some_and(|hover| hover.distance(*pos) <= BUBBLE_RADIUS);
+    let fill = if is_hovered {
+      ui.visuals().strong_text_color()
+    } else {
+      *color
+    };
+
+    if let Some(label) = label {
+      painter.line_segment([center, *pos], egui::Stroke::new(2.0, *color));
+      painter.circle_filled(*pos, BUBBLE_RADIUS, fill);
+      painter.text(
+        *pos,
+        egui::Align2::CENTER_CENTER,
+        *label,
+        egui::FontId::proportional(BUBBLE_RADIUS * 1.4),
+        egui::Color32::BLACK,
+      );
+    } else {
+      painter.circle(
+        *pos,
+        BUBBLE_RADIUS * 0.8,
+        fill.gamma_multiply(0.4),
+        egui::Stroke::new(1.0, *color),
+      );
+    }
+  }
+
+  if response.dragged() {
+    let delta = response.drag_delta() * DRAG_SENSITIVITY;
+    if delta != egui::Vec2::ZERO {
+      let pivot = pivot_of(transform);
+      let mut transform = *transform;
+      let right = transform.right().as_vec3();
+      transform.rotate_around(pivot, Quat::from_axis_angle(UP, -delta.x));
+      transform.rotate_around(pivot, Quat::from_axis_angle(right, -delta.y));
+      return Some(transform);
+    }
+  }
+
+  if response.clicked() {
+    let clicked = response.interact_pointer_pos().and_then(|click| {
+      // front most bubble wins when they overlap
+      bubbles
+        .iter()
+        .rev()
+        .find(|(_, _, _, pos, _)| click.distance(*pos) <= BUBBLE_RADIUS)
+    });
+
+    if let Some((axis, ..)) = clicked {
+      return Some(snap_to(transform, *axis));
+    }
+  }
+
+  None
+}
+
+fn pivot_of(transform: &Transform) -> Vec3 {
+  let distance = transform.translation.length().max(MIN_PIVOT_DISTANCE);
+  transform.translation + transform.forward() * distance
+}
+
+/// Moves the camera onto `axis` looking back at the pivot
+fn snap_to(transform: &Transform, axis: Vec3) -> Transform {
+  let pivot = pivot_of(transform);
+  let distance = transform.translation.distance(pivot);
+  let up = if axis.abs() == UP {
+    // keep facing the same way on screen when looking straight up or down
+    transform
+      .forward()
+      .as_vec3()
+      .reject_from(UP)
+      .normalize_or(Vec3::NEG_Z)
+  } else {
+    UP
+  };
+
+  Transform::from_translation(pivot + axis * distance)
+    .looking_at(pivot, up)
+    .with_scale(transform.scale)
+}